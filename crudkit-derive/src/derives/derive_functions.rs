@@ -1,8 +1,10 @@
+use convert_case::{Boundary, Case, Converter};
 use deluxe::ExtractAttributes;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use syn::{
-    Data, DataStruct, DeriveInput, Field, Fields, FieldsNamed, Ident, Result as SynResult, Type,
+    Attribute, Data, DataStruct, DeriveInput, Field, Fields, FieldsNamed, GenericArgument, Ident,
+    PathArguments, Result as SynResult, Type,
 };
 
 use crate::synerror;
@@ -13,6 +15,15 @@ struct RelationAttributes {
     schema_name: Option<String>,
     relation_name: String,
     primary_key: String,
+    key_type: Option<Type>,
+    default_order: Option<String>,
+    nulls: Option<String>,
+    identifiers: Option<String>,
+    soft_delete: Option<String>,
+    // * The record type is parsed separately, since the other relation derives need it too
+    #[allow(dead_code)]
+    record: Option<String>,
+    view: deluxe::Flag,
 }
 
 #[derive(ExtractAttributes)]
@@ -27,6 +38,54 @@ struct AutoPrimaryKeyAttribute;
 #[deluxe(attributes(manual_primary_key))]
 struct ManualPrimaryKeyAttribute;
 
+#[derive(ExtractAttributes)]
+#[deluxe(attributes(generated_column))]
+struct GeneratedColumnAttribute;
+
+#[derive(ExtractAttributes)]
+#[deluxe(attributes(no_overwrite))]
+struct NoOverwriteAttribute;
+
+#[derive(ExtractAttributes)]
+#[deluxe(attributes(created_timestamp))]
+struct CreatedTimestampAttribute;
+
+#[derive(ExtractAttributes)]
+#[deluxe(attributes(updated_timestamp))]
+struct UpdatedTimestampAttribute;
+
+#[derive(ExtractAttributes)]
+#[deluxe(attributes(version))]
+struct VersionAttribute;
+
+#[derive(ExtractAttributes)]
+#[deluxe(attributes(skip))]
+struct SkipAttribute;
+
+#[derive(ExtractAttributes)]
+#[deluxe(attributes(map_key))]
+struct MapKeyAttribute;
+
+#[derive(ExtractAttributes)]
+#[deluxe(attributes(map_value))]
+struct MapValueAttribute;
+
+#[derive(ExtractAttributes)]
+#[deluxe(attributes(column))]
+struct ColumnAttribute {
+    #[deluxe(default)]
+    rename: Option<String>,
+    #[deluxe(default)]
+    pg_type: Option<String>,
+}
+
+#[derive(ExtractAttributes, Clone)]
+#[deluxe(attributes(columns))]
+struct ColumnsAttribute {
+    names: Vec<String>,
+    binder: syn::Path,
+}
+
 #[derive(Clone, PartialEq)]
 enum PrimaryKeyAttribute {
     Auto,
@@ -39,17 +98,71 @@ pub fn derive_id_parameter(input: TokenStream2) -> SynResult<TokenStream2> {
     let (_, unparsed_type_fields) =
         get_struct_data_and_unparsed_fields(&type_name, &type_data, "IdParameter")?;
 
-    let first_field = unparsed_type_fields.named.into_iter().next().unwrap();
+    let Some(first_field) = unparsed_type_fields.named.into_iter().next() else {
+        return synerror!(
+            type_name,
+            "cannot derive `IdParameter` for structs without fields"
+        );
+    };
+    let openapi_implementation = derive_openapi_fields(
+        &type_name,
+        [(
+            field_name_string(&first_field),
+            postgres_type_name(&first_field.ty),
+            option_inner_type(&first_field.ty).is_none(),
+        )],
+    );
     let first_field_name = first_field.ident.unwrap();
+    let first_field_type = first_field.ty;
 
     Ok(quote! {
         impl crudkit::traits::id_parameter::IdParameter for #type_name {
-            fn new(#first_field_name: usize) -> Self {
+            type Id = #first_field_type;
+
+            fn new(#first_field_name: #first_field_type) -> Self {
                 Self { #first_field_name }
             }
 
-            fn id(&self) -> usize {
-                self.#first_field_name
+            fn id(&self) -> #first_field_type {
+                self.#first_field_name.clone()
+            }
+        }
+
+        #openapi_implementation
+    }
+    .into())
+}
+
+pub fn derive_composite_id_parameter(input: TokenStream2) -> SynResult<TokenStream2> {
+    let (type_name, type_data) = parse_type_ident_and_data(input)?;
+    let (_, unparsed_type_fields) =
+        get_struct_data_and_unparsed_fields(&type_name, &type_data, "CompositeIdParameter")?;
+
+    // * `CompositeKey` is only implemented for tuples of two to four values
+    let field_count = unparsed_type_fields.named.len();
+    if !(2..=4).contains(&field_count) {
+        return synerror!(
+            type_name,
+            "cannot derive `CompositeIdParameter` for structs without two to four fields"
+        );
+    }
+
+    let (field_names, field_types): (Vec<_>, Vec<_>) = unparsed_type_fields
+        .named
+        .into_iter()
+        .map(|field| (field.ident.unwrap(), field.ty))
+        .unzip();
+
+    Ok(quote! {
+        impl crudkit::traits::id_parameter::CompositeIdParameter for #type_name {
+            type Key = (#(#field_types,)*);
+
+            fn new((#(#field_names,)*): Self::Key) -> Self {
+                Self { #(#field_names),* }
+            }
+
+            fn key(&self) -> Self::Key {
+                (#(self.#field_names.clone(),)*)
             }
         }
     }
@@ -60,14 +173,23 @@ pub fn derive_relation(input: TokenStream2) -> SynResult<TokenStream2> {
     let mut input: DeriveInput = syn::parse2(input)?;
     let type_name = input.ident.clone();
     let type_data = input.data.clone();
-    let record_type_name = suffix_ident(&type_name, "Record");
+    let record_type_name = relation_record_type_name(&input)?;
 
-    get_struct_data_and_unparsed_fields(&type_name, &type_data, "Relation")?;
+    let (_, unparsed_type_fields) =
+        get_struct_data_and_unparsed_fields(&type_name, &type_data, "Relation")?;
+    validate_records_field(&type_name, &unparsed_type_fields, &record_type_name)?;
 
     let Ok(RelationAttributes {
         schema_name,
         relation_name,
         primary_key,
+        key_type,
+        default_order,
+        nulls,
+        identifiers,
+        soft_delete,
+        record: _,
+        view,
     }) = deluxe::extract_attributes(&mut input)
     else {
         return synerror!(
@@ -76,21 +198,103 @@ pub fn derive_relation(input: TokenStream2) -> SynResult<TokenStream2> {
         );
     };
 
+    let primary_key_columns: Vec<&str> = primary_key
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .split(',')
+        .map(str::trim)
+        .collect();
+
+    let key_type = key_type.map_or_else(|| quote!(i32), |key_type| quote!(#key_type));
+
     let optional_schema_definition = schema_name.map(|schema_name| {
         quote! {
             const SCHEMA_NAME: &str = #schema_name;
         }
     });
 
+    let optional_default_order_definition = default_order.map(|default_order| {
+        quote! {
+            const DEFAULT_ORDER: &str = #default_order;
+        }
+    });
+
+    let optional_soft_delete_definition = soft_delete.map(|soft_delete_column| {
+        quote! {
+            const SOFT_DELETE_COLUMN: Option<&'static str> = Some(#soft_delete_column);
+        }
+    });
+
+    let nulls_order_variant = match nulls.as_deref() {
+        Some("first") => Some(quote!(First)),
+        Some("last") => Some(quote!(Last)),
+        Some(_) => {
+            return synerror!(
+                type_name,
+                "`nulls` in `#[relation(...)]` attribute must be either \"first\" or \"last\""
+            )
+        }
+        None => None,
+    };
+    let identifier_strategy_variant = match identifiers.as_deref() {
+        Some("as_is") => Some(quote!(AsIs)),
+        Some("quote_all") => Some(quote!(QuoteAll)),
+        Some("snake_case") => Some(quote!(SnakeCase)),
+        Some(_) => {
+            return synerror!(
+                type_name,
+                "`identifiers` in `#[relation(...)]` attribute must be one of \"as_is\", \
+                \"quote_all\", or \"snake_case\""
+            )
+        }
+        None => None,
+    };
+    let optional_identifier_strategy_definition = identifier_strategy_variant.map(|variant| {
+        quote! {
+            const IDENTIFIER_STRATEGY: crudkit::traits::shared::IdentifierStrategy =
+                crudkit::traits::shared::IdentifierStrategy::#variant;
+        }
+    });
+
+    let optional_nulls_order_definition = nulls_order_variant.map(|variant| {
+        quote! {
+            const NULLS_ORDER: Option<crudkit::traits::shared::NullsOrder> =
+                Some(crudkit::traits::shared::NullsOrder::#variant);
+        }
+    });
+
+    // * Views are read-only, so their read traits are implemented here rather than by separate
+    // * derives, and `WriteRelation` refuses to be derived for them
+    let optional_view_definitions = view.is_set().then(|| {
+        quote! {
+            impl crudkit::traits::read::ReadRelation for #type_name {
+                type ReadRecord = #record_type_name;
+            }
+
+            impl crudkit::traits::read::ReadRecord for #record_type_name {
+                type ReadRelation = #type_name;
+            }
+        }
+    });
+
     Ok(quote! {
         impl crudkit::traits::shared::Relation for #type_name {
             type Record = #record_type_name;
+            type Key = #key_type;
             #optional_schema_definition
             const RELATION_NAME: &str = #relation_name;
             const PRIMARY_KEY: &str = #primary_key;
+            const PRIMARY_KEY_COLUMNS: &[&str] = &[#(#primary_key_columns),*];
+            #optional_default_order_definition
+            #optional_nulls_order_definition
+            #optional_identifier_strategy_definition
+            #optional_soft_delete_definition
 
-            fn with_records(records: Vec<Self::Record>) -> Self {
-                Self { records }
+            fn with_records(records: impl Into<Vec<Self::Record>>) -> Self {
+                Self {
+                    records: records.into(),
+                }
             }
 
             fn take_records(self) -> Vec<Self::Record> {
@@ -100,16 +304,41 @@ pub fn derive_relation(input: TokenStream2) -> SynResult<TokenStream2> {
             fn records(&self) -> &[Self::Record] {
                 &self.records
             }
+
+            fn retain<F: FnMut(&Self::Record) -> bool>(&mut self, f: F) {
+                self.records.retain(f)
+            }
         }
+
+        impl IntoIterator for #type_name {
+            type Item = #record_type_name;
+            type IntoIter = std::vec::IntoIter<#record_type_name>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.records.into_iter()
+            }
+        }
+
+        impl<'a> IntoIterator for &'a #type_name {
+            type Item = &'a #record_type_name;
+            type IntoIter = std::slice::Iter<'a, #record_type_name>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.records.iter()
+            }
+        }
+
+        #optional_view_definitions
     }
     .into())
 }
 
 pub fn derive_read_relation(input: TokenStream2) -> SynResult<TokenStream2> {
-    let (type_name, type_data) = parse_type_ident_and_data(input)?;
-    let record_type_name = suffix_ident(&type_name, "Record");
+    let input: DeriveInput = syn::parse2(input)?;
+    let type_name = input.ident.clone();
+    let record_type_name = relation_record_type_name(&input)?;
 
-    get_struct_data_and_unparsed_fields(&type_name, &type_data, "ReadRelation")?;
+    get_struct_data_and_unparsed_fields(&type_name, &input.data, "ReadRelation")?;
 
     Ok(quote! {
         impl crudkit::traits::read::ReadRelation for #type_name {
@@ -120,10 +349,12 @@ pub fn derive_read_relation(input: TokenStream2) -> SynResult<TokenStream2> {
 }
 
 pub fn derive_write_relation(input: TokenStream2) -> SynResult<TokenStream2> {
-    let (type_name, type_data) = parse_type_ident_and_data(input)?;
-    let record_type_name = suffix_ident(&type_name, "Record");
+    let input: DeriveInput = syn::parse2(input)?;
+    let type_name = input.ident.clone();
+    let record_type_name = relation_record_type_name(&input)?;
 
-    get_struct_data_and_unparsed_fields(&type_name, &type_data, "WriteRelation")?;
+    get_struct_data_and_unparsed_fields(&type_name, &input.data, "WriteRelation")?;
+    reject_view(&input, "WriteRelation")?;
 
     Ok(quote! {
         impl crudkit::traits::write::WriteRelation for #type_name {
@@ -134,32 +365,306 @@ pub fn derive_write_relation(input: TokenStream2) -> SynResult<TokenStream2> {
 }
 
 pub fn derive_record(input: TokenStream2) -> SynResult<TokenStream2> {
-    let (type_name, type_data) = parse_type_ident_and_data(input)?;
-    let relation_type_name = trim_ident_suffix(&type_name, "Record");
+    let input: DeriveInput = syn::parse2(input)?;
+    let (type_name, type_data) = (input.ident.clone(), input.data.clone());
+    let relation_type_name = record_relation_type_name(&input)?;
 
     let (_, unparsed_type_fields) =
         get_struct_data_and_unparsed_fields(&type_name, &type_data, "Record")?;
 
-    let column_names: Vec<String> = parse_field_data(&unparsed_type_fields)?
-        .into_iter()
-        .map(|f| f.name)
+    let type_fields = parse_field_data_with_attributes(&type_name, &unparsed_type_fields)?;
+    // * Skipped fields are not columns, so they are left out of all column metadata
+    let column_fields: Vec<&FieldDataWithAttributeFlags> =
+        type_fields.iter().filter(|f| !f.skip).collect();
+
+    let column_names: Vec<TokenStream2> = column_fields
+        .iter()
+        .flat_map(|f| column_name_expressions(&relation_type_name, f))
+        .collect();
+    let generated_column_names: Vec<TokenStream2> = column_fields
+        .iter()
+        .filter(|f| f.generated)
+        .flat_map(|f| column_name_expressions(&relation_type_name, f))
+        .collect();
+    // * The types of columns contributed by a `#[columns(...)]` field cannot be inferred, and those
+    // * given with `#[column(pg_type = "...")]` are usually enums, which `information_schema`
+    // * only reports as `USER-DEFINED`
+    let column_types: Vec<TokenStream2> = column_fields
+        .iter()
+        .flat_map(|f| match &f.columns {
+            Some(columns) => vec![quote!(None); columns.names.len()],
+            None if f.data.pg_type.is_some() => vec![quote!(None)],
+            None => match postgres_type_name(&f.data.r#type) {
+                Some(type_name) => vec![quote!(Some(#type_name))],
+                None => vec![quote!(None)],
+            },
+        })
+        .collect();
+    let nullable_column_names: Vec<TokenStream2> = column_fields
+        .iter()
+        .filter(|f| option_inner_type(&f.data.r#type).is_some())
+        .flat_map(|f| column_name_expressions(&relation_type_name, f))
+        .collect();
+
+    let column_getter_arms: Vec<TokenStream2> = column_fields
+        .iter()
+        .flat_map(|f| {
+            let field_ident = &f.data.ident;
+            match &f.columns {
+                // * Composite values are expected to serialize to an object keyed by column name,
+                // * the same way they are flattened when decoded with `#[sqlx(flatten)]`
+                Some(columns) => columns
+                    .names
+                    .iter()
+                    .map(|column_name| {
+                        quote! {
+                            #column_name => crudkit::serde_json::to_value(&self.#field_ident)
+                                .ok()
+                                .and_then(|value| value.get(#column_name).cloned())
+                        }
+                    })
+                    .collect(),
+                None => {
+                    let column_name = column_name_expression(&relation_type_name, &f.data);
+                    vec![quote! {
+                        column_name if column_name == #column_name => {
+                            crudkit::serde_json::to_value(&self.#field_ident).ok()
+                        }
+                    }]
+                }
+            }
+        })
         .collect();
 
+    let key_value_record_implementation = derive_key_value_record(&type_name, &type_fields)?;
+    let openapi_implementation = derive_openapi_fields(
+        &type_name,
+        type_fields
+            .iter()
+            .map(|f| openapi_field(f, option_inner_type(&f.data.r#type).is_none())),
+    );
+
     Ok(quote! {
         impl crudkit::traits::shared::Record for #type_name {
             const COLUMN_NAMES: &[&str] = &[#(#column_names),*];
+            const GENERATED_COLUMN_NAMES: &[&str] = &[#(#generated_column_names),*];
+            const COLUMN_TYPES: &[Option<&str>] = &[#(#column_types),*];
+            const NULLABLE_COLUMN_NAMES: &[&str] = &[#(#nullable_column_names),*];
 
             type Relation = #relation_type_name;
+
+            fn get_column(&self, name: &str) -> Option<crudkit::serde_json::Value> {
+                match name {
+                    #(#column_getter_arms,)*
+                    _ => None,
+                }
+            }
         }
+
+        #key_value_record_implementation
+        #openapi_implementation
     }
     .into())
 }
 
-pub fn derive_read_record(input: TokenStream2) -> SynResult<TokenStream2> {
+/// Generate an implementation of `arbitrary::Arbitrary` for a record type, so that random records
+/// can be generated for property tests and fuzzing.
+///
+/// Text columns are generated using the helpers in `crudkit::fuzz`, since Postgres rejects text
+/// containing NUL characters. Generated columns and managed timestamps are always given their
+/// default value, since their values are never taken from the record. All other fields use their
+/// own `Arbitrary` implementation, so [`Option`] fields are `None` for some records.
+#[cfg(feature = "arbitrary")]
+pub fn derive_arbitrary_record(input: TokenStream2) -> SynResult<TokenStream2> {
     let (type_name, type_data) = parse_type_ident_and_data(input)?;
-    let relation_type_name = trim_ident_suffix(&type_name, "Record");
+    let (_, unparsed_type_fields) =
+        get_struct_data_and_unparsed_fields(&type_name, &type_data, "ArbitraryRecord")?;
+    let type_fields = parse_field_data_with_attributes(&type_name, &unparsed_type_fields)?;
+
+    let field_initializers: Vec<TokenStream2> = type_fields
+        .iter()
+        .map(|f| {
+            let field_ident = &f.data.ident;
+            let field_type = &f.data.r#type;
+            let is_text = f.columns.is_none() && postgres_type_name(field_type) == Some("text");
+            if f.generated || f.is_managed_timestamp() || f.skip {
+                quote!(#field_ident: ::core::default::Default::default())
+            } else if is_text && option_inner_type(field_type).is_some() {
+                quote!(#field_ident: crudkit::fuzz::arbitrary_optional_text(u)?)
+            } else if is_text {
+                quote!(#field_ident: crudkit::fuzz::arbitrary_text(u)?)
+            } else {
+                quote!(#field_ident: crudkit::arbitrary::Arbitrary::arbitrary(u)?)
+            }
+        })
+        .collect();
+
+    Ok(quote! {
+        impl<'a> crudkit::arbitrary::Arbitrary<'a> for #type_name {
+            fn arbitrary(
+                u: &mut crudkit::arbitrary::Unstructured<'a>,
+            ) -> crudkit::arbitrary::Result<Self> {
+                Ok(Self {
+                    #(#field_initializers,)*
+                })
+            }
+        }
+    })
+}
+
+#[cfg(not(feature = "arbitrary"))]
+pub fn derive_arbitrary_record(input: TokenStream2) -> SynResult<TokenStream2> {
+    let (type_name, _) = parse_type_ident_and_data(input)?;
+
+    synerror!(
+        type_name,
+        "cannot derive `ArbitraryRecord` without the `arbitrary` feature of `crudkit`"
+    )
+}
+
+/// Generate an implementation of `crudkit::openapi::OpenApiFields` for a type, given the name,
+/// Postgres type, and requiredness of each of its fields.
+#[cfg(feature = "openapi")]
+fn derive_openapi_fields(
+    type_name: &Ident,
+    fields: impl IntoIterator<Item = (String, Option<&'static str>, bool)>,
+) -> Option<TokenStream2> {
+    let field_schemas: Vec<TokenStream2> = fields
+        .into_iter()
+        .map(|(name, postgres_type, required)| {
+            let postgres_type = match postgres_type {
+                Some(postgres_type) => quote!(Some(#postgres_type)),
+                None => quote!(None),
+            };
+            quote! {
+                crudkit::openapi::FieldSchema {
+                    name: #name,
+                    postgres_type: #postgres_type,
+                    required: #required,
+                }
+            }
+        })
+        .collect();
+
+    Some(quote! {
+        impl crudkit::openapi::OpenApiFields for #type_name {
+            const FIELDS: &'static [crudkit::openapi::FieldSchema] = &[#(#field_schemas),*];
+        }
+    })
+}
+
+#[cfg(not(feature = "openapi"))]
+fn derive_openapi_fields(
+    _type_name: &Ident,
+    _fields: impl IntoIterator<Item = (String, Option<&'static str>, bool)>,
+) -> Option<TokenStream2> {
+    None
+}
+
+/// Generate the attributes which derive `validator::Validate` for a parameters type, if any of the
+/// record's fields are annotated with `#[validate(...)]`.
+///
+/// Without the `validator` feature, the annotations are ignored, since they may be used by a
+/// separate derive of `validator::Validate` on the record itself.
+#[cfg(feature = "validator")]
+fn derive_validator_attributes(has_validated_fields: bool) -> Option<TokenStream2> {
+    has_validated_fields.then(|| {
+        quote! {
+            #[derive(crudkit::validator::Validate)]
+            #[validate(crate = "crudkit::validator")]
+        }
+    })
+}
+
+#[cfg(not(feature = "validator"))]
+fn derive_validator_attributes(_has_validated_fields: bool) -> Option<TokenStream2> {
+    None
+}
+
+/// Get the `#[validate(...)]` attributes of a field to copy onto the parameters types, which is
+/// only done when `validator::Validate` is derived for them, since nothing would accept the
+/// attributes otherwise.
+fn params_validate_attributes(
+    field: &FieldDataWithAttributeFlags,
+    derives_validator: bool,
+) -> &[Attribute] {
+    match derives_validator {
+        true => &field.validate_attributes,
+        false => &[],
+    }
+}
+
+/// Get the OpenAPI description of a record field, for use with [`derive_openapi_fields()`].
+///
+/// The types of fields marked with `#[columns(...)]` cannot be inferred, so they are left untyped.
+fn openapi_field(
+    field: &FieldDataWithAttributeFlags,
+    required: bool,
+) -> (String, Option<&'static str>, bool) {
+    let postgres_type = match field.columns {
+        Some(_) => None,
+        None => postgres_type_name(&field.data.r#type),
+    };
+
+    (field.data.name.clone(), postgres_type, required)
+}
+
+fn derive_key_value_record(
+    type_name: &Ident,
+    type_fields: &[FieldDataWithAttributeFlags],
+) -> SynResult<Option<TokenStream2>> {
+    let key_fields: Vec<&FieldData> = type_fields
+        .iter()
+        .filter(|f| f.map_key)
+        .map(|f| &f.data)
+        .collect();
+    let value_fields: Vec<&FieldData> = type_fields
+        .iter()
+        .filter(|f| f.map_value)
+        .map(|f| &f.data)
+        .collect();
+
+    let (key_field, value_field) = match (key_fields.as_slice(), value_fields.as_slice()) {
+        ([], []) => return Ok(None),
+        ([key_field], [value_field]) if type_fields.len() == 2 => (key_field, value_field),
+        _ => {
+            return synerror!(
+                type_name,
+                "`#[map_key]` and `#[map_value]` must each be used exactly once, on a record with exactly two fields"
+            )
+        }
+    };
+
+    let key_field_ident = &key_field.ident;
+    let key_field_type = &key_field.r#type;
+    let value_field_ident = &value_field.ident;
+    let value_field_type = &value_field.r#type;
+
+    Ok(Some(quote! {
+        impl crudkit::traits::shared::KeyValueRecord for #type_name {
+            type Key = #key_field_type;
+            type Value = #value_field_type;
+
+            fn into_key_value(self) -> (Self::Key, Self::Value) {
+                (self.#key_field_ident, self.#value_field_ident)
+            }
+
+            fn from_key_value(key: Self::Key, value: Self::Value) -> Self {
+                Self {
+                    #key_field_ident: key,
+                    #value_field_ident: value,
+                }
+            }
+        }
+    }))
+}
+
+pub fn derive_read_record(input: TokenStream2) -> SynResult<TokenStream2> {
+    let input: DeriveInput = syn::parse2(input)?;
+    let type_name = input.ident.clone();
+    let relation_type_name = record_relation_type_name(&input)?;
 
-    get_struct_data_and_unparsed_fields(&type_name, &type_data, "ReadRecord")?;
+    get_struct_data_and_unparsed_fields(&type_name, &input.data, "ReadRecord")?;
 
     Ok(quote! {
         impl crudkit::traits::read::ReadRecord for #type_name {
@@ -170,9 +675,15 @@ pub fn derive_read_record(input: TokenStream2) -> SynResult<TokenStream2> {
 }
 
 pub fn derive_write_record(input: TokenStream2) -> SynResult<TokenStream2> {
-    let (type_name, type_data) = parse_type_ident_and_data(input)?;
+    let input: DeriveInput = syn::parse2(input)?;
+    let type_name = input.ident.clone();
+    let type_data = input.data.clone();
+    let custom_validation = input
+        .attrs
+        .iter()
+        .any(|a| a.path().is_ident("custom_validation"));
 
-    let relation_type_name = trim_ident_suffix(&type_name, "Record");
+    let relation_type_name = record_relation_type_name(&input)?;
     let create_params_type_name = suffix_ident(&type_name, "CreateQueryParameters");
     let update_params_type_name = suffix_ident(&type_name, "UpdateQueryParameters");
 
@@ -180,80 +691,265 @@ pub fn derive_write_record(input: TokenStream2) -> SynResult<TokenStream2> {
         get_struct_data_and_unparsed_fields(&type_name, &type_data, "WriteRecord")?;
 
     let type_fields = parse_field_data_with_attributes(&type_name, &unparsed_type_fields)?;
+    // * Generated columns are computed by the database, so they can never be written to
+    let writable_type_fields: Vec<&FieldDataWithAttributeFlags> = type_fields
+        .iter()
+        .filter(|f| !f.generated && !f.skip)
+        .collect();
 
-    let type_field_idents: Vec<Ident> = type_fields.iter().map(|f| f.data.ident.clone()).collect();
+    // * Timestamps are set to `NOW()` by the library, so they are never given by the client
+    let client_writable_type_fields: Vec<&FieldDataWithAttributeFlags> = writable_type_fields
+        .iter()
+        .copied()
+        .filter(|f| !f.is_managed_timestamp())
+        .collect();
+
+    let type_field_idents: Vec<Ident> = client_writable_type_fields
+        .iter()
+        .map(|f| f.data.ident.clone())
+        .collect();
+
+    // * Bulk updates match records by a list of keys instead, so only the updated columns are used
+    let set_field_idents: Vec<Ident> = client_writable_type_fields
+        .iter()
+        .filter(|f| f.primary_key == PrimaryKeyAttribute::None && !f.version)
+        .map(|f| f.data.ident.clone())
+        .collect();
 
-    let primary_key_field_data_and_accessors: Vec<(FieldData, TokenStream2)> = type_fields
+    let primary_key_field_data_and_accessors: Vec<(FieldData, TokenStream2)> = writable_type_fields
         .iter()
         .filter_map(|f| {
             let field_ident = f.data.ident.clone();
             match f.primary_key {
-                PrimaryKeyAttribute::Auto => Some((f.data.clone(), quote!(#field_ident.unwrap()))),
+                PrimaryKeyAttribute::Auto => Some((f.data.clone(), quote!(#field_ident))),
                 PrimaryKeyAttribute::Manual => Some((f.data.clone(), quote!(#field_ident))),
                 PrimaryKeyAttribute::None => None,
             }
         })
         .collect();
 
+    // * Auto primary keys are optional in the update parameters so that they can be shared with bulk
+    // * updates, which do not use them, so a missing key must be rejected rather than unwrapped
+    let primary_key_presence_checks: Vec<TokenStream2> = writable_type_fields
+        .iter()
+        .filter(|f| f.primary_key == PrimaryKeyAttribute::Auto)
+        .map(|f| {
+            let field_ident = f.data.ident.clone();
+            quote! {
+                let Some(#field_ident) = #field_ident else {
+                    return Err(crudkit::error::Error {
+                        kind: crudkit::error::ErrorKind::InvalidQuery,
+                        source: None,
+                        message: None,
+                        status_code: crudkit::http::StatusCode::BAD_REQUEST,
+                    });
+                };
+            }
+        })
+        .collect();
+
+    let version_fields: Vec<&FieldDataWithAttributeFlags> = writable_type_fields
+        .iter()
+        .copied()
+        .filter(|f| f.version)
+        .collect();
+    if version_fields.len() > 1 {
+        return synerror!(type_name, "cannot use `#[version]` on more than one column");
+    }
+
+    let version_column_name = match version_fields.first() {
+        Some(f) => {
+            let column_name = column_name_expression(&relation_type_name, &f.data);
+            quote!(Some(#column_name))
+        }
+        None => quote!(None),
+    };
+
+    // * The version is checked alongside the primary key so that the update only matches the
+    // * record if it has not been modified since the caller read it
+    let where_clause_version_conditions: Vec<TokenStream2> = version_fields
+        .iter()
+        .map(|f| {
+            let field_ident = f.data.ident.clone();
+            let column_name = column_name_expression(&relation_type_name, &f.data);
+            let type_cast = type_cast_statement(quote!(where_clause), &f.data);
+            quote! {
+                where_clause.push(format!(
+                    "{} = ",
+                    <#relation_type_name as crudkit::traits::shared::Relation>::render_identifier(
+                        #column_name
+                    )
+                ));
+                where_clause.push_bind_unseparated(#field_ident);
+                #type_cast
+            }
+        })
+        .collect();
+
+    let version_set_clause_bindings: Vec<TokenStream2> = version_fields
+        .iter()
+        .map(|f| {
+            let column_name = column_name_expression(&relation_type_name, &f.data);
+            quote! {
+                if updated_column_count > 0 {
+                    let version_column_name =
+                        <#relation_type_name as crudkit::traits::shared::Relation>::render_identifier(
+                            #column_name
+                        );
+                    query_builder.push(format!(
+                        ", {version_column_name} = {version_column_name} + 1"
+                    ));
+                }
+            }
+        })
+        .collect();
+
     let where_clause_primary_key_conditions: Vec<TokenStream2> =
         primary_key_field_data_and_accessors
             .iter()
             .map(|(data, accessor)| {
-                let field_name = data.name.clone();
+                let column_name = column_name_expression(&relation_type_name, data);
+                let type_cast = type_cast_statement(quote!(where_clause), data);
                 quote! {
-                    format!("{} = {}", #field_name, #accessor)
+                    where_clause.push(format!(
+                        "{} = ",
+                        <#relation_type_name as crudkit::traits::shared::Relation>::render_identifier(
+                            #column_name
+                        )
+                    ));
+                    where_clause.push_bind_unseparated(#accessor);
+                    #type_cast
                 }
             })
             .collect();
 
-    let where_clause_builder = quote! {
-        let mut where_clause_conditions = Vec::new();
-        #(
-            where_clause_conditions.push(#where_clause_primary_key_conditions);
-        )*
-
-        let where_clause_conditions = where_clause_conditions.join(" AND ");
-        let where_clause = format!("WHERE {}", where_clause_conditions);
-    };
-
-    let conditional_column_specifiers: Vec<TokenStream2> = type_fields
+    let conditional_set_clause_bindings: Vec<TokenStream2> = client_writable_type_fields
         .iter()
         .filter_map(|f| {
-            if f.primary_key == PrimaryKeyAttribute::None {
+            if f.primary_key == PrimaryKeyAttribute::None && f.columns.is_none() && !f.version {
                 let field_ident = f.data.ident.clone();
-                let field_name = f.data.name.clone();
-                Some(quote! {
-                    if #field_ident.is_some() {
-                        column_bind_specifiers.push(format!(
-                            concat!(#field_name, " = ${}"),
-                            column_bind_specifiers.len() + 1
-                        ));
+                let column_name = column_name_expression(&relation_type_name, &f.data);
+                let rendered_column_name = quote! {
+                    <#relation_type_name as crudkit::traits::shared::Relation>::render_identifier(
+                        #column_name
+                    )
+                };
+                let type_cast = type_cast_statement(quote!(set_clause), &f.data);
+                let set_bound_value = quote! {
+                    set_clause.push(format!("{} = ", #rendered_column_name));
+                    set_clause.push_bind_unseparated(#field_ident);
+                    #type_cast
+                    updated_column_count += 1;
+                };
+                let set_keyword = |keyword: &str| {
+                    quote! {
+                        set_clause.push(format!("{} = {}", #rendered_column_name, #keyword));
+                        updated_column_count += 1;
                     }
-                })
+                };
+
+                if f.defaultable {
+                    let set_default = set_keyword("DEFAULT");
+                    Some(quote! {
+                        match #field_ident {
+                            crudkit::traits::write::UpdateValue::Keep => {}
+                            crudkit::traits::write::UpdateValue::Set(#field_ident) => {
+                                #set_bound_value
+                            }
+                            crudkit::traits::write::UpdateValue::Default => {
+                                #set_default
+                            }
+                        }
+                    })
+                } else if option_inner_type(&f.data.r#type).is_some() {
+                    let set_null = set_keyword("NULL");
+                    Some(quote! {
+                        match #field_ident {
+                            crudkit::traits::write::FieldUpdate::Unchanged => {}
+                            crudkit::traits::write::FieldUpdate::Set(#field_ident) => {
+                                #set_bound_value
+                            }
+                            crudkit::traits::write::FieldUpdate::SetNull => {
+                                #set_null
+                            }
+                        }
+                    })
+                } else {
+                    Some(quote! {
+                        if let Some(#field_ident) = #field_ident {
+                            #set_bound_value
+                        }
+                    })
+                }
             } else {
                 None
             }
         })
         .collect();
 
-    let conditional_binding_statements: Vec<TokenStream2> = type_fields
+    // * Fields which contribute multiple columns cannot be pushed to the separated `SET` clause,
+    // * because their binder pushes its own separated values, so they are assigned as a row instead
+    let conditional_row_set_clause_bindings: Vec<TokenStream2> = client_writable_type_fields
         .iter()
         .filter_map(|f| {
-            if f.primary_key == PrimaryKeyAttribute::None {
-                let field_ident = f.data.ident.clone();
-                Some(quote! {
-                    if let Some(#field_ident) = #field_ident {
-                        query = query.bind(#field_ident);
+            let columns = f.columns.as_ref()?;
+            let field_ident = f.data.ident.clone();
+            let column_names = &columns.names;
+            let binder = &columns.binder;
+            Some(quote! {
+                if let Some(#field_ident) = #field_ident {
+                    if updated_column_count > 0 {
+                        query_builder.push(", ");
                     }
-                })
-            } else {
-                None
+
+                    let column_names: Vec<String> = [#(#column_names),*]
+                        .iter()
+                        .map(|column_name| {
+                            <#relation_type_name as crudkit::traits::shared::Relation>::render_identifier(
+                                column_name
+                            )
+                            .into_owned()
+                        })
+                        .collect();
+                    query_builder.push(format!("({}) = ROW(", column_names.join(", ")));
+                    let mut row_values = query_builder.separated(", ");
+                    #binder(&mut row_values, #field_ident);
+                    query_builder.push(")");
+                    updated_column_count += 1;
+                }
+            })
+        })
+        .collect();
+
+    // * Updated timestamps are only set if another column is updated, so that an empty update is
+    // * still rejected
+    let updated_timestamp_set_clause_bindings: Vec<TokenStream2> = writable_type_fields
+        .iter()
+        .filter(|f| f.updated_timestamp)
+        .map(|f| {
+            let column_name = column_name_expression(&relation_type_name, &f.data);
+            quote! {
+                if updated_column_count > 0 {
+                    query_builder.push(format!(
+                        ", {} = NOW()",
+                        <#relation_type_name as crudkit::traits::shared::Relation>::render_identifier(
+                            #column_name
+                        )
+                    ));
+                }
             }
         })
         .collect();
 
-    let create_params_field_declarations: Vec<TokenStream2> = type_fields
+    let validator_attributes = derive_validator_attributes(
+        client_writable_type_fields
+            .iter()
+            .any(|f| !f.validate_attributes.is_empty()),
+    );
+
+    let create_params_field_declarations: Vec<TokenStream2> = client_writable_type_fields
         .iter()
+        .filter(|f| !f.version)
         .filter_map(|f| match f.primary_key {
             PrimaryKeyAttribute::Auto => None,
             _ => {
@@ -261,7 +957,9 @@ pub fn derive_write_record(input: TokenStream2) -> SynResult<TokenStream2> {
                 // * additional attributes on the field would be included in the output
                 let field_ident = f.data.ident.clone();
                 let field_type = f.data.r#type.clone();
-                Some(quote!(#field_ident: #field_type))
+                let validate_attributes =
+                    params_validate_attributes(f, validator_attributes.is_some());
+                Some(quote!(#(#validate_attributes)* #field_ident: #field_type))
             }
         })
         .collect();
@@ -270,6 +968,10 @@ pub fn derive_write_record(input: TokenStream2) -> SynResult<TokenStream2> {
         .iter()
         .map(|f| {
             let field_ident = f.data.ident.clone();
+            if f.generated || f.is_managed_timestamp() || f.version || f.skip {
+                return quote!(#field_ident: Default::default());
+            }
+
             match f.primary_key {
                 PrimaryKeyAttribute::Auto => quote!(#field_ident: None),
                 _ => quote!(#field_ident: params.#field_ident),
@@ -277,35 +979,108 @@ pub fn derive_write_record(input: TokenStream2) -> SynResult<TokenStream2> {
         })
         .collect();
 
-    let update_params_field_declarations: Vec<TokenStream2> = type_fields
+    let update_params_field_declarations: Vec<TokenStream2> = client_writable_type_fields
         .iter()
         .map(|f| {
             let field_ident = f.data.ident.clone();
             let field_type = f.data.r#type.clone();
+            let validate_attributes = params_validate_attributes(f, validator_attributes.is_some());
+            // * The expected version is always required, the same as the primary key
             let new_field_type = match f.primary_key {
-                PrimaryKeyAttribute::None => quote!(Option<#field_type>),
+                // * Defaultable fields are only optional so that `None` can be inserted as
+                // * `DEFAULT`, so updates can reset them to `DEFAULT` in the same way
+                PrimaryKeyAttribute::None if f.defaultable => {
+                    let inner_type = option_inner_type(&field_type).unwrap_or(&field_type);
+                    return quote! {
+                        #[serde(default)]
+                        #(#validate_attributes)*
+                        #field_ident: crudkit::traits::write::UpdateValue<#inner_type>
+                    };
+                }
+                PrimaryKeyAttribute::None if !f.version => match option_inner_type(&field_type) {
+                    // * Nullable columns are given a separate state for `NULL` rather than a nested
+                    // * `Option`, except for multi-column fields which are assigned as a row
+                    Some(inner_type) if f.columns.is_none() => {
+                        return quote! {
+                            #[serde(default)]
+                            #(#validate_attributes)*
+                            #field_ident: crudkit::traits::write::FieldUpdate<#inner_type>
+                        };
+                    }
+                    _ => quote!(Option<#field_type>),
+                },
                 _ => quote!(#field_type),
             };
 
-            quote!(#field_ident: #new_field_type)
+            quote!(#(#validate_attributes)* #field_ident: #new_field_type)
         })
         .collect();
 
+    // * With `#[custom_validation]`, the implementations are written by hand instead
+    let validation_implementations = (!custom_validation).then(|| {
+        let validate_method = validator_attributes.as_ref().map(|_| {
+            quote! {
+                fn validate(&self) -> Result<(), String> {
+                    crudkit::validate::describe_validation_errors(
+                        crudkit::validator::Validate::validate(self),
+                    )
+                }
+            }
+        });
+
+        quote! {
+            impl crudkit::validate::Validate for #create_params_type_name {
+                #validate_method
+            }
+
+            impl crudkit::validate::Validate for #update_params_type_name {
+                #validate_method
+            }
+        }
+    });
+
+    let create_params_openapi_implementation = derive_openapi_fields(
+        &create_params_type_name,
+        client_writable_type_fields
+            .iter()
+            .filter(|f| !f.version && f.primary_key != PrimaryKeyAttribute::Auto)
+            .map(|f| openapi_field(f, option_inner_type(&f.data.r#type).is_none())),
+    );
+    // * Only the primary key and expected version are required to update a record
+    let update_params_openapi_implementation = derive_openapi_fields(
+        &update_params_type_name,
+        client_writable_type_fields.iter().map(|f| {
+            let required = (f.primary_key != PrimaryKeyAttribute::None || f.version)
+                && option_inner_type(&f.data.r#type).is_none();
+            openapi_field(f, required)
+        }),
+    );
+
     Ok(quote! {
+        // * Field names are copied from the record, which may not be in snake case if the relation
+        // * uses an identifier strategy other than `AsIs`
+        #[allow(non_snake_case)]
         #[derive(Clone, serde::Deserialize)]
+        #validator_attributes
         pub struct #create_params_type_name {
             #(
                 #create_params_field_declarations
             ),*
         }
 
+        #[allow(non_snake_case)]
         #[derive(Clone, serde::Deserialize)]
+        #validator_attributes
         pub struct #update_params_type_name {
             #(
                 #update_params_field_declarations
             ),*
         }
 
+        #create_params_openapi_implementation
+        #update_params_openapi_implementation
+        #validation_implementations
+
         impl From<#create_params_type_name> for #type_name {
             fn from(params: #create_params_type_name) -> Self {
                 Self {
@@ -321,59 +1096,106 @@ pub fn derive_write_record(input: TokenStream2) -> SynResult<TokenStream2> {
             type CreateQueryParameters = #create_params_type_name;
             type UpdateQueryParameters = #update_params_type_name;
 
-            async fn update_one(
-                database: &crudkit::database::PgDatabase,
-                update_params: Self::UpdateQueryParameters,
-            ) -> Result<(), crudkit::error::Error> {
-                let relation_name = Self::Relation::get_qualified_name();
-                crudkit::log::debug!(
-                    "Dispatching single-UPDATE query to database, targeting relation
-                    {relation_name}"
-                );
+            const VERSION_COLUMN_NAME: Option<&'static str> = #version_column_name;
 
+            #[allow(non_snake_case)]
+            fn build_update_query(
+                update_params: Self::UpdateQueryParameters,
+            ) -> Result<
+                sqlx::query_builder::QueryBuilder<'static, sqlx::Postgres>,
+                crudkit::error::Error,
+            > {
                 let #update_params_type_name {
                     #(
                         #type_field_idents
                     ),*
                 } = update_params;
+                #(
+                    #primary_key_presence_checks
+                )*
 
-                #where_clause_builder
+                let mut query_builder =
+                    <Self as crudkit::traits::write::WriteRecord>::get_update_query_builder();
 
-                let mut column_bind_specifiers: Vec<String> = Vec::new();
+                let mut updated_column_count = 0;
+                let mut set_clause = query_builder.separated(", ");
+                #(
+                    #conditional_set_clause_bindings
+                )*
+                #(
+                    #conditional_row_set_clause_bindings
+                )*
+                #(
+                    #version_set_clause_bindings
+                )*
+                #(
+                    #updated_timestamp_set_clause_bindings
+                )*
 
+                query_builder.push(" WHERE ");
+                let mut where_clause = query_builder.separated(" AND ");
                 #(
-                    #conditional_column_specifiers
+                    #where_clause_primary_key_conditions
                 )*
+                #(
+                    #where_clause_version_conditions
+                )*
+
+                // * An update with no columns to set is a client error rather than a missing record
+                if updated_column_count == 0 {
+                    return Err(crudkit::error::Error {
+                        kind: crudkit::error::ErrorKind::InvalidQuery,
+                        source: None,
+                        message: None,
+                        status_code: crudkit::http::StatusCode::BAD_REQUEST,
+                    });
+                }
 
-                let query_string = format!(
-                    "UPDATE {}.{} SET {} {}",
-                    Self::Relation::SCHEMA_NAME,
-                    Self::Relation::RELATION_NAME,
-                    column_bind_specifiers.join(", "),
-                    where_clause,
-                );
+                Ok(query_builder)
+            }
 
-                crudkit::log::trace!("Raw query prior to variable binding: {query_string}");
+            #[allow(non_snake_case)]
+            fn build_update_many_query(
+                update_params: Self::UpdateQueryParameters,
+            ) -> Result<
+                sqlx::query_builder::QueryBuilder<'static, sqlx::Postgres>,
+                crudkit::error::Error,
+            > {
+                let #update_params_type_name {
+                    #(
+                        #set_field_idents,
+                    )*
+                    ..
+                } = update_params;
 
-                use crudkit::traits::shared::Relation;
-                let mut query = sqlx::query(&query_string);
+                let mut query_builder =
+                    <Self as crudkit::traits::write::WriteRecord>::get_update_query_builder();
 
+                let mut updated_column_count = 0;
+                let mut set_clause = query_builder.separated(", ");
+                #(
+                    #conditional_set_clause_bindings
+                )*
+                #(
+                    #conditional_row_set_clause_bindings
+                )*
+                #(
+                    #version_set_clause_bindings
+                )*
                 #(
-                    #conditional_binding_statements
+                    #updated_timestamp_set_clause_bindings
                 )*
 
-                if !column_bind_specifiers.is_empty() {
-                    match query.execute(&database.connection).await {
-                        Ok(_) => Ok(()),
-                        Err(e) => Err(crudkit::error::Error::from(e)),
-                    }
-                } else {
-                    Err(crudkit::error::Error {
+                if updated_column_count == 0 {
+                    return Err(crudkit::error::Error {
                         kind: crudkit::error::ErrorKind::InvalidQuery,
                         source: None,
-                        status_code: crudkit::http::StatusCode::NOT_FOUND,
-                    })
+                        message: None,
+                        status_code: crudkit::http::StatusCode::BAD_REQUEST,
+                    });
                 }
+
+                Ok(query_builder)
             }
         }
     }
@@ -381,39 +1203,69 @@ pub fn derive_write_record(input: TokenStream2) -> SynResult<TokenStream2> {
 }
 
 pub fn derive_single_insert(input: TokenStream2) -> SynResult<TokenStream2> {
-    let (type_name, type_data) = parse_type_ident_and_data(input)?;
+    let input: DeriveInput = syn::parse2(input)?;
+    let (type_name, type_data) = (input.ident.clone(), input.data.clone());
 
     let (_, unparsed_type_fields) =
         get_struct_data_and_unparsed_fields(&type_name, &type_data, "SingleInsert")?;
+    let relation_type_name = record_relation_type_name(&input)?;
 
     let type_fields = parse_field_data_with_attributes(&type_name, &unparsed_type_fields)?;
 
+    let no_overwrite_column_names: Vec<TokenStream2> = type_fields
+        .iter()
+        .filter(|f| f.no_overwrite || f.created_timestamp)
+        .flat_map(|f| column_name_expressions(&relation_type_name, f))
+        .collect();
+
+    // * Postgres rejects `INSERT INTO t () VALUES ()`, so there must be at least one column to write
+    if type_fields.iter().all(|f| f.generated || f.skip) {
+        return synerror!(
+            type_name,
+            "cannot insert a record with no columns, since every field is skipped or generated"
+        );
+    }
+
     let binding_statements: Vec<TokenStream2> = type_fields
         .into_iter()
+        .filter(|f| !f.generated && !f.skip)
         .map(|f| {
+            let type_cast = type_cast_statement(quote!(builder), &f.data);
             let FieldDataWithAttributeFlags {
                 data: FieldData {
                     ident: field_ident, ..
                 },
+                primary_key,
                 defaultable,
+                created_timestamp,
+                updated_timestamp,
+                columns,
                 ..
             } = f;
 
-            if defaultable {
+            // * Auto primary keys are assigned by the database, such as from a sequence or
+            // * `gen_random_uuid()`, so they are always defaultable
+            if let Some(ColumnsAttribute { binder, .. }) = columns {
+                quote!(#binder(&mut builder, record.#field_ident);)
+            } else if created_timestamp || updated_timestamp {
+                quote!(builder.push("NOW()");)
+            } else if defaultable || primary_key == PrimaryKeyAttribute::Auto {
                 quote! {
                     match record.#field_ident {
-                        Some(column_value) => { builder.push_bind(column_value); },
+                        Some(column_value) => { builder.push_bind(column_value); #type_cast },
                         None => { builder.push("DEFAULT"); },
                     }
                 }
             } else {
-                quote!(builder.push_bind(record.#field_ident);)
+                quote!(builder.push_bind(record.#field_ident); #type_cast)
             }
         })
         .collect();
 
     Ok(quote! {
         impl crudkit::traits::write::SingleInsert for #type_name {
+            const NO_OVERWRITE_COLUMN_NAMES: &[&str] = &[#(#no_overwrite_column_names),*];
+
             fn push_column_bindings(
                 mut builder: sqlx::query_builder::Separated<sqlx::Postgres, &str>,
                 record: Self,
@@ -428,11 +1280,31 @@ pub fn derive_single_insert(input: TokenStream2) -> SynResult<TokenStream2> {
 }
 
 pub fn derive_bulk_insert(input: TokenStream2) -> SynResult<TokenStream2> {
-    let (type_name, type_data) = parse_type_ident_and_data(input)?;
-    get_struct_data_and_unparsed_fields(&type_name, &type_data, "BulkInsert")?;
+    let input: DeriveInput = syn::parse2(input)?;
+    let type_name = input.ident.clone();
+    get_struct_data_and_unparsed_fields(&type_name, &input.data, "BulkInsert")?;
+    reject_view(&input, "BulkInsert")?;
 
+    // * These are checked at compile time so that a record type without columns to insert, or with
+    // * more columns than can be bound in a single query, cannot send invalid SQL or silently
+    // * produce empty chunks
     Ok(quote! {
         impl crudkit::traits::write::BulkInsert for #type_name {}
+
+        const _: () = {
+            assert!(
+                <<#type_name as crudkit::traits::shared::Relation>::Record
+                    as crudkit::traits::shared::Record>::COLUMN_NAMES.len()
+                    > <<#type_name as crudkit::traits::shared::Relation>::Record
+                        as crudkit::traits::shared::Record>::GENERATED_COLUMN_NAMES.len(),
+                "cannot insert a record with no columns"
+            );
+            assert!(
+                <#type_name as crudkit::traits::write::BulkInsert>::CHUNK_SIZE > 0,
+                "cannot derive `BulkInsert` for a relation whose records have more columns than \
+                can be bound in a single query"
+            );
+        };
     }
     .into())
 }
@@ -442,13 +1314,130 @@ pub fn derive_identifiable_record(input: TokenStream2) -> SynResult<TokenStream2
     let (_, unparsed_type_fields) =
         get_struct_data_and_unparsed_fields(&type_name, &type_data, "IdentifiableRecord")?;
 
-    let first_field = unparsed_type_fields.named.into_iter().next().unwrap();
-    let first_field_name = first_field.ident.unwrap();
+    let type_fields = parse_field_data_with_attributes(&type_name, &unparsed_type_fields)?;
+    let primary_key_fields: Vec<&FieldDataWithAttributeFlags> = type_fields
+        .iter()
+        .filter(|f| f.primary_key != PrimaryKeyAttribute::None)
+        .collect();
 
+    let primary_key_field_ident = match primary_key_fields.as_slice() {
+        [primary_key_field] => &primary_key_field.data.ident,
+        [] => {
+            return synerror!(
+                type_name,
+                "cannot derive `IdentifiableRecord` without a field marked with \
+                `#[auto_primary_key]` or `#[manual_primary_key]`"
+            )
+        }
+        _ => {
+            return synerror!(
+                type_name,
+                "cannot derive `IdentifiableRecord` for a record with a composite primary key"
+            )
+        }
+    };
+
+    // * Auto primary keys are optional and manual ones are not, so `.into()` covers both
     Ok(quote! {
         impl crudkit::traits::shared::IdentifiableRecord for #type_name {
-            fn id(&self) -> Option<i32> {
-                self.#first_field_name.into()
+            fn id(
+                &self,
+            ) -> Option<<<Self as crudkit::traits::shared::Record>::Relation as crudkit::traits::shared::Relation>::Key> {
+                self.#primary_key_field_ident.clone().into()
+            }
+        }
+    }
+    .into())
+}
+
+pub fn derive_generate_table(input: TokenStream2) -> SynResult<TokenStream2> {
+    let input: DeriveInput = syn::parse2(input)?;
+    let (type_name, type_data) = (input.ident.clone(), input.data.clone());
+
+    let (_, unparsed_type_fields) =
+        get_struct_data_and_unparsed_fields(&type_name, &type_data, "GenerateTable")?;
+    let relation_type_name = record_relation_type_name(&input)?;
+
+    let type_fields = parse_field_data_with_attributes(&type_name, &unparsed_type_fields)?;
+
+    let mut column_definitions = Vec::new();
+    for f in type_fields.iter().filter(|f| !f.skip) {
+        let field_ident = &f.data.ident;
+        if f.columns.is_some() || f.generated {
+            return synerror!(
+                field_ident,
+                "cannot derive `GenerateTable` for a generated or multi-column field"
+            );
+        }
+
+        let Some(type_name) = f
+            .data
+            .pg_type
+            .as_deref()
+            .or_else(|| postgres_type_name(&f.data.r#type))
+        else {
+            return synerror!(
+                field_ident,
+                "cannot infer the Postgres type of this field for `GenerateTable`"
+            );
+        };
+
+        let type_clause = match f.primary_key {
+            PrimaryKeyAttribute::Auto => match type_name {
+                "smallint" => "smallserial PRIMARY KEY".to_owned(),
+                "integer" => "serial PRIMARY KEY".to_owned(),
+                "bigint" => "bigserial PRIMARY KEY".to_owned(),
+                "uuid" => "uuid PRIMARY KEY DEFAULT gen_random_uuid()".to_owned(),
+                _ => {
+                    return synerror!(
+                        field_ident,
+                        "`#[auto_primary_key]` columns must be an integer or UUID type for `GenerateTable`"
+                    )
+                }
+            },
+            PrimaryKeyAttribute::Manual => format!("{type_name} PRIMARY KEY"),
+            PrimaryKeyAttribute::None if option_inner_type(&f.data.r#type).is_some() => {
+                type_name.to_owned()
+            }
+            PrimaryKeyAttribute::None => format!("{type_name} NOT NULL"),
+        };
+
+        let column_name = column_name_expression(&relation_type_name, &f.data);
+        column_definitions.push(quote! {
+            format!(
+                "{} {}",
+                <#relation_type_name as crudkit::traits::shared::Relation>::render_identifier(#column_name),
+                #type_clause,
+            )
+        });
+    }
+
+    // * Relations whose primary key is not marked on a field, such as junction tables with a
+    // * composite key, are given a table constraint using the columns of the relation's key
+    let primary_key_constraint = if type_fields
+        .iter()
+        .all(|f| f.primary_key == PrimaryKeyAttribute::None)
+    {
+        quote! {
+            let primary_key_columns: Vec<String> = <#relation_type_name as crudkit::traits::shared::Relation>::get_primary_key_columns()
+                .into_iter()
+                .map(|column_name| {
+                    <#relation_type_name as crudkit::traits::shared::Relation>::render_identifier(column_name).into_owned()
+                })
+                .collect();
+            column_definitions.push(format!("PRIMARY KEY ({})", primary_key_columns.join(", ")));
+        }
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        impl crudkit::schema::GenerateTable for #type_name {
+            fn column_definitions() -> Vec<String> {
+                #[allow(unused_mut)]
+                let mut column_definitions = vec![#(#column_definitions),*];
+                #primary_key_constraint
+                column_definitions
             }
         }
     }
@@ -465,6 +1454,129 @@ fn parse_type_ident_and_data(input: TokenStream2) -> SynResult<(Ident, Data)> {
     Ok((struct_ident, struct_data))
 }
 
+/// Get the name of the record type paired with a relation.
+///
+/// This is given using `#[relation(record = "...")]`, and is the name of the relation with a
+/// `Record` suffix by default, such as `CustomersTableRecord` for `CustomersTable`.
+fn relation_record_type_name(input: &DeriveInput) -> SynResult<Ident> {
+    let mut record_type_name = None;
+    for attribute in input.attrs.iter().filter(|a| a.path().is_ident("relation")) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("record") {
+                record_type_name = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(record_type_name.unwrap_or_else(|| suffix_ident(&input.ident, "Record")))
+}
+
+/// Get the name of the relation type paired with a record.
+///
+/// This is given using `#[record(relation = "...")]`, and is the name of the record without its
+/// `Record` suffix by default, such as `CustomersTable` for `CustomersTableRecord`.
+fn record_relation_type_name(input: &DeriveInput) -> SynResult<Ident> {
+    let mut relation_type_name = None;
+    for attribute in input.attrs.iter().filter(|a| a.path().is_ident("record")) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("relation") {
+                relation_type_name = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `relation` in `#[record(...)]` attribute"))
+            }
+        })?;
+    }
+
+    match relation_type_name {
+        Some(relation_type_name) => Ok(relation_type_name),
+        None if input.ident.to_string().ends_with("Record") => {
+            Ok(trim_ident_suffix(&input.ident, "Record"))
+        }
+        None => {
+            let type_name = &input.ident;
+            synerror!(
+                type_name,
+                format!(
+                    "cannot find the relation type of `{type_name}`, because its name does not end \
+                    in `Record`, so the relation type must be given using \
+                    `#[record(relation = \"...\")]`"
+                )
+            )
+        }
+    }
+}
+
+/// Check that the `records` field of a relation holds its record type.
+///
+/// The record type is only known by name, so this catches a misnamed or missing record type where
+/// the relation is declared, rather than with an error pointing into the generated code.
+fn validate_records_field(
+    type_name: &Ident,
+    fields: &FieldsNamed,
+    record_type_name: &Ident,
+) -> SynResult<()> {
+    let Some(records_field) = fields
+        .named
+        .iter()
+        .find(|f| f.ident.as_ref().is_some_and(|ident| ident == "records"))
+    else {
+        return synerror!(
+            type_name,
+            format!("cannot derive `Relation` without a `records: Vec<{record_type_name}>` field")
+        );
+    };
+
+    let record_type_ident = match wrapped_inner_type(&records_field.ty, "Vec") {
+        Some(Type::Path(type_path)) => type_path.path.segments.last().map(|s| &s.ident),
+        _ => None,
+    };
+    if record_type_ident != Some(record_type_name) {
+        return Err(syn::Error::new_spanned(
+            &records_field.ty,
+            format!(
+                "expected `records` to be a `Vec<{record_type_name}>`, because the record type of \
+                `{type_name}` is its name with a `Record` suffix unless it is given using \
+                `#[relation(record = \"...\")]`"
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Return an error if the type is marked as a view using `#[relation(view)]`, because views are
+/// read-only and cannot have write traits derived for them.
+fn reject_view(input: &DeriveInput, derive_name: &str) -> SynResult<()> {
+    let type_name = &input.ident;
+
+    for attribute in input.attrs.iter().filter(|a| a.path().is_ident("relation")) {
+        let mut is_view = false;
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("view") {
+                is_view = true;
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+
+            Ok(())
+        })?;
+
+        if is_view {
+            return synerror!(
+                type_name,
+                format!("cannot derive `{derive_name}` for a view, because views are read-only")
+            );
+        }
+    }
+
+    Ok(())
+}
+
 fn get_struct_data_and_unparsed_fields(
     ident: &Ident,
     data: &Data,
@@ -487,10 +1599,6 @@ fn get_struct_data_and_unparsed_fields(
     Ok((data_struct.clone(), struct_fields.clone()))
 }
 
-fn parse_field_data(unparsed_fields: &FieldsNamed) -> SynResult<Vec<FieldData>> {
-    Ok(unparsed_fields.named.iter().map(FieldData::from).collect())
-}
-
 fn parse_field_data_with_attributes(
     struct_ident: &Ident,
     unparsed_fields: &FieldsNamed,
@@ -505,6 +1613,34 @@ fn parse_field_data_with_attributes(
             let manual_primary_key =
                 deluxe::extract_attributes::<_, ManualPrimaryKeyAttribute>(&mut f).is_ok();
             let defaultable = deluxe::extract_attributes::<_, DefaultableRecordAttribute>(&mut f).is_ok();
+            let generated = deluxe::extract_attributes::<_, GeneratedColumnAttribute>(&mut f).is_ok();
+            let no_overwrite = deluxe::extract_attributes::<_, NoOverwriteAttribute>(&mut f).is_ok();
+            let created_timestamp = deluxe::extract_attributes::<_, CreatedTimestampAttribute>(&mut f).is_ok();
+            let updated_timestamp = deluxe::extract_attributes::<_, UpdatedTimestampAttribute>(&mut f).is_ok();
+            let version = deluxe::extract_attributes::<_, VersionAttribute>(&mut f).is_ok();
+            let skip = deluxe::extract_attributes::<_, SkipAttribute>(&mut f).is_ok();
+            let map_key = deluxe::extract_attributes::<_, MapKeyAttribute>(&mut f).is_ok();
+            let map_value = deluxe::extract_attributes::<_, MapValueAttribute>(&mut f).is_ok();
+            // * Unlike the marker attributes, a malformed `#[columns(...)]` should be reported
+            // * rather than silently ignored, so it is only extracted if it is present
+            let columns = if f.attrs.iter().any(|a| a.path().is_ident("columns")) {
+                Some(deluxe::extract_attributes::<_, ColumnsAttribute>(&mut f)?)
+            } else {
+                None
+            };
+            let (column_rename, pg_type) = if f.attrs.iter().any(|a| a.path().is_ident("column")) {
+                let ColumnAttribute { rename, pg_type } = deluxe::extract_attributes(&mut f)?;
+                if rename.is_none() && pg_type.is_none() {
+                    return synerror!(struct_ident, "`#[column(...)]` requires `rename` or `pg_type`");
+                }
+                (rename, pg_type)
+            } else {
+                (None, None)
+            };
+
+            if (column_rename.is_some() || pg_type.is_some()) && columns.is_some() {
+                return synerror!(struct_ident, "cannot use both `#[column(...)]` and `#[columns(...)]` on a single field");
+            }
 
             let primary_key = match (auto_primary_key, manual_primary_key) {
                 (true, true) => return synerror!(struct_ident, "cannot use both `#[auto_primary_key]` and `#[manual_primary_key]` on a single column"),
@@ -513,13 +1649,154 @@ fn parse_field_data_with_attributes(
                 (false, false) => PrimaryKeyAttribute::None,
             };
 
-            let data = FieldData::from(&f);
+            if columns.is_some() && (primary_key != PrimaryKeyAttribute::None || defaultable || generated) {
+                return synerror!(struct_ident, "cannot use `#[columns(...)]` on a primary key, defaultable, or generated column");
+            }
+
+            if (created_timestamp || updated_timestamp) && (primary_key != PrimaryKeyAttribute::None || defaultable || generated || columns.is_some()) {
+                return synerror!(struct_ident, "cannot use `#[created_timestamp]` or `#[updated_timestamp]` on a primary key, defaultable, generated, or multi-column field");
+            }
+
+            if version && (primary_key != PrimaryKeyAttribute::None || defaultable || generated || created_timestamp || updated_timestamp || columns.is_some()) {
+                return synerror!(struct_ident, "cannot use `#[version]` on a primary key, defaultable, generated, timestamp, or multi-column field");
+            }
+
+            if skip && (primary_key != PrimaryKeyAttribute::None || defaultable || generated || no_overwrite || created_timestamp || updated_timestamp || version || map_key || map_value || columns.is_some() || column_rename.is_some() || pg_type.is_some()) {
+                return synerror!(struct_ident, "cannot use `#[skip]` together with any other field attribute");
+            }
+
+            // * These are left on the field rather than extracted, since they belong to `validator`
+            let validate_attributes: Vec<Attribute> = f.attrs.iter().filter(|a| a.path().is_ident("validate")).cloned().collect();
 
-            Ok(FieldDataWithAttributeFlags{ data, primary_key, defaultable })
+            let data = FieldData {
+                column_rename,
+                pg_type,
+                ..FieldData::from(&f)
+            };
+
+            Ok(FieldDataWithAttributeFlags{ data, primary_key, defaultable, generated, no_overwrite, created_timestamp, updated_timestamp, version, skip, map_key, map_value, columns, validate_attributes })
         })
         .collect()
 }
 
+/// Get the inner type of an `Option<T>`, or [`None`] if the type is not an [`Option`].
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    wrapped_inner_type(ty, "Option")
+}
+
+/// Get the type parameter of a generic type with the given name, such as `T` for `Vec<T>`.
+fn wrapped_inner_type<'t>(ty: &'t Type, wrapper: &str) -> Option<&'t Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+
+    let last_segment = type_path.path.segments.last()?;
+    if last_segment.ident != wrapper {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(arguments) = &last_segment.arguments else {
+        return None;
+    };
+
+    match arguments.args.first() {
+        Some(GenericArgument::Type(inner_type)) => Some(inner_type),
+        _ => None,
+    }
+}
+
+/// Get the name of the Postgres type corresponding to a Rust type, as reported by the `data_type`
+/// column of `information_schema.columns`.
+///
+/// [`Option`] types are mapped to the type of their inner value. Only the last segment of a type
+/// path is checked, so this is a best-effort mapping for well-known types. [`None`] is returned for
+/// any type which is not recognized.
+fn postgres_type_name(ty: &Type) -> Option<&'static str> {
+    let ty = option_inner_type(ty).unwrap_or(ty);
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+
+    let last_segment = type_path.path.segments.last()?;
+    let type_name = match last_segment.ident.to_string().as_str() {
+        "bool" => "boolean",
+        "i8" => "\"char\"",
+        "i16" => "smallint",
+        "i32" => "integer",
+        "i64" => "bigint",
+        "f32" => "real",
+        "f64" => "double precision",
+        "String" => "text",
+        "Uuid" => "uuid",
+        "Decimal" | "BigDecimal" => "numeric",
+        "NaiveDate" | "Date" => "date",
+        "NaiveTime" | "Time" => "time without time zone",
+        "NaiveDateTime" | "PrimitiveDateTime" => "timestamp without time zone",
+        "DateTime" | "OffsetDateTime" => "timestamp with time zone",
+        "Value" | "JsonValue" => "jsonb",
+        _ => return None,
+    };
+
+    Some(type_name)
+}
+
+/// Get expressions for the names of the columns contributed by a field, which is only the column
+/// corresponding to the field itself unless the field is marked with `#[columns(...)]`.
+///
+/// Names given explicitly with `#[columns(...)]` are used exactly as written.
+fn column_name_expressions(
+    relation_type_name: &Ident,
+    field: &FieldDataWithAttributeFlags,
+) -> Vec<TokenStream2> {
+    match &field.columns {
+        Some(columns) => columns.names.iter().map(|name| quote!(#name)).collect(),
+        None => vec![column_name_expression(relation_type_name, &field.data)],
+    }
+}
+
+/// Get a constant expression for the column name corresponding to a field.
+///
+/// Names given explicitly with `#[column(rename = "...")]` are used exactly as written. Otherwise,
+/// the column name depends on the relation's identifier strategy, so the snake case version of the
+/// field name is computed here and selected between when the expression is evaluated. If the field
+/// name is already in snake case, it is used directly.
+fn column_name_expression(relation_type_name: &Ident, field: &FieldData) -> TokenStream2 {
+    if let Some(column_rename) = &field.column_rename {
+        return quote!(#column_rename);
+    }
+
+    let field_name = field.name.as_str();
+    let snake_case_name = Converter::new()
+        .set_boundaries(&[
+            Boundary::Underscore,
+            Boundary::LowerUpper,
+            Boundary::Acronym,
+        ])
+        .to_case(Case::Snake)
+        .convert(field_name);
+
+    if snake_case_name == field_name {
+        return quote!(#field_name);
+    }
+
+    quote! {
+        crudkit::traits::shared::IdentifierStrategy::select_column_name(
+            <#relation_type_name as crudkit::traits::shared::Relation>::IDENTIFIER_STRATEGY,
+            #field_name,
+            #snake_case_name,
+        )
+    }
+}
+
+/// Get the statement which casts the value just bound for a field to the type given with
+/// `#[column(pg_type = "...")]`, if any, such as `$1::order_status`.
+///
+/// The type is written exactly as given, so that schema-qualified names can be used.
+fn type_cast_statement(builder: TokenStream2, field: &FieldData) -> Option<TokenStream2> {
+    let type_cast = format!("::{}", field.pg_type.as_ref()?);
+    Some(quote!(#builder.push_unseparated(#type_cast);))
+}
+
 fn field_name_string(field: &Field) -> String {
     field
         .ident
@@ -559,12 +1836,35 @@ struct FieldData {
     ident: Ident,
     r#type: Type,
     name: String,
+    /// The column name given with `#[column(rename = "...")]`, if any.
+    column_rename: Option<String>,
+    /// The Postgres type given with `#[column(pg_type = "...")]`, if any, which bound values are
+    /// cast to.
+    pg_type: Option<String>,
 }
 
 struct FieldDataWithAttributeFlags {
     data: FieldData,
     primary_key: PrimaryKeyAttribute,
     defaultable: bool,
+    generated: bool,
+    no_overwrite: bool,
+    created_timestamp: bool,
+    updated_timestamp: bool,
+    version: bool,
+    skip: bool,
+    map_key: bool,
+    map_value: bool,
+    columns: Option<ColumnsAttribute>,
+    /// The `#[validate(...)]` attributes of the field, which are copied to the parameters types.
+    validate_attributes: Vec<Attribute>,
+}
+
+impl FieldDataWithAttributeFlags {
+    /// Whether the column is a timestamp which is set by the library rather than the client.
+    fn is_managed_timestamp(&self) -> bool {
+        self.created_timestamp || self.updated_timestamp
+    }
 }
 
 impl From<&Field> for FieldData {
@@ -577,6 +1877,8 @@ impl From<&Field> for FieldData {
             ident,
             r#type,
             name,
+            column_rename: None,
+            pg_type: None,
         }
     }
 }
@@ -26,6 +26,13 @@ pub fn derive_id_parameter(input: TokenStream) -> TokenStream {
     propagate_synerror!(derives::derive_functions::derive_id_parameter(input.into()))
 }
 
+#[proc_macro_derive(CompositeIdParameter)]
+pub fn derive_composite_id_parameter(input: TokenStream) -> TokenStream {
+    propagate_synerror!(derives::derive_functions::derive_composite_id_parameter(
+        input.into()
+    ))
+}
+
 #[proc_macro_derive(Relation, attributes(relation))]
 pub fn derive_relation(input: TokenStream) -> TokenStream {
     propagate_synerror!(derives::derive_functions::derive_relation(input.into()))
@@ -45,22 +52,71 @@ pub fn derive_write_relation(input: TokenStream) -> TokenStream {
     ))
 }
 
-#[proc_macro_derive(Record)]
+#[proc_macro_derive(
+    Record,
+    attributes(
+        record,
+        generated_column,
+        created_timestamp,
+        updated_timestamp,
+        map_key,
+        map_value,
+        column,
+        skip,
+        columns
+    )
+)]
 pub fn derive_record(input: TokenStream) -> TokenStream {
     propagate_synerror!(derives::derive_functions::derive_record(input.into()))
 }
 
-#[proc_macro_derive(ReadRecord)]
+#[proc_macro_derive(ArbitraryRecord)]
+pub fn derive_arbitrary_record(input: TokenStream) -> TokenStream {
+    propagate_synerror!(derives::derive_functions::derive_arbitrary_record(
+        input.into()
+    ))
+}
+
+#[proc_macro_derive(ReadRecord, attributes(record))]
 pub fn derive_read_record(input: TokenStream) -> TokenStream {
     propagate_synerror!(derives::derive_functions::derive_read_record(input.into()))
 }
 
-#[proc_macro_derive(WriteRecord, attributes(auto_primary_key, manual_primary_key))]
+#[proc_macro_derive(
+    WriteRecord,
+    attributes(
+        record,
+        auto_primary_key,
+        manual_primary_key,
+        generated_column,
+        created_timestamp,
+        updated_timestamp,
+        version,
+        column,
+        skip,
+        columns,
+        validate,
+        custom_validation
+    )
+)]
 pub fn derive_write_record(input: TokenStream) -> TokenStream {
     propagate_synerror!(derives::derive_functions::derive_write_record(input.into()))
 }
 
-#[proc_macro_derive(SingleInsert, attributes(defaultable))]
+#[proc_macro_derive(
+    SingleInsert,
+    attributes(
+        record,
+        defaultable,
+        generated_column,
+        no_overwrite,
+        created_timestamp,
+        updated_timestamp,
+        column,
+        skip,
+        columns
+    )
+)]
 pub fn derive_single_insert(input: TokenStream) -> TokenStream {
     propagate_synerror!(derives::derive_functions::derive_single_insert(
         input.into()
@@ -72,9 +128,27 @@ pub fn derive_bulk_insert(input: TokenStream) -> TokenStream {
     propagate_synerror!(derives::derive_functions::derive_bulk_insert(input.into()))
 }
 
-#[proc_macro_derive(IdentifiableRecord)]
+#[proc_macro_derive(IdentifiableRecord, attributes(auto_primary_key, manual_primary_key))]
 pub fn derive_identifiable_record(input: TokenStream) -> TokenStream {
     propagate_synerror!(derives::derive_functions::derive_identifiable_record(
         input.into()
     ))
 }
+
+#[proc_macro_derive(
+    GenerateTable,
+    attributes(
+        record,
+        auto_primary_key,
+        manual_primary_key,
+        generated_column,
+        column,
+        skip,
+        columns
+    )
+)]
+pub fn derive_generate_table(input: TokenStream) -> TokenStream {
+    propagate_synerror!(derives::derive_functions::derive_generate_table(
+        input.into()
+    ))
+}
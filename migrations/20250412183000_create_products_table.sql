@@ -0,0 +1,6 @@
+CREATE TABLE main.products (
+    id serial PRIMARY KEY,
+    name text NOT NULL,
+    amount_cents bigint NOT NULL,
+    currency text NOT NULL
+);
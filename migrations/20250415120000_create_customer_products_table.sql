@@ -0,0 +1,6 @@
+CREATE TABLE main.customer_products (
+    customer_id integer NOT NULL,
+    product_id integer NOT NULL,
+    quantity integer NOT NULL,
+    PRIMARY KEY (customer_id, product_id)
+);
@@ -0,0 +1,5 @@
+CREATE TABLE main.orders (
+    id bigserial PRIMARY KEY,
+    customer_id integer NOT NULL,
+    total_cents bigint NOT NULL
+);
@@ -0,0 +1,4 @@
+CREATE TABLE main.devices (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    name text NOT NULL
+);
@@ -0,0 +1,16 @@
+CREATE TABLE main.wide_records (
+    id integer PRIMARY KEY,
+    column_01 integer NOT NULL,
+    column_02 integer NOT NULL,
+    column_03 integer NOT NULL,
+    column_04 integer NOT NULL,
+    column_05 integer NOT NULL,
+    column_06 integer NOT NULL,
+    column_07 integer NOT NULL,
+    column_08 integer NOT NULL,
+    column_09 integer NOT NULL,
+    column_10 integer NOT NULL,
+    column_11 integer NOT NULL,
+    column_12 integer NOT NULL,
+    column_sum integer GENERATED ALWAYS AS (column_01 + column_12) STORED
+);
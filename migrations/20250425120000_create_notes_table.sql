@@ -0,0 +1,6 @@
+CREATE TABLE main.notes (
+    id serial PRIMARY KEY,
+    body text NOT NULL,
+    created_at timestamptz NOT NULL,
+    updated_at timestamptz NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE main.documents (
+    id serial PRIMARY KEY,
+    title text NOT NULL,
+    version integer NOT NULL
+);
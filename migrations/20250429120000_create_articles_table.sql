@@ -0,0 +1,5 @@
+CREATE TABLE main.articles (
+    id integer PRIMARY KEY,
+    title text NOT NULL,
+    deleted_at timestamptz
+);
@@ -0,0 +1,5 @@
+CREATE TABLE main.vehicles (
+    id serial PRIMARY KEY,
+    type text NOT NULL,
+    license_plate text
+);
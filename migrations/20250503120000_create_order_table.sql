@@ -0,0 +1,5 @@
+CREATE TABLE main."order" (
+    "orderNumber" integer PRIMARY KEY,
+    "itemCount" integer NOT NULL,
+    "group" text
+);
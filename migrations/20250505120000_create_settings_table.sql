@@ -0,0 +1,4 @@
+CREATE TABLE main.settings (
+    name text PRIMARY KEY,
+    value text NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE main.tickets (
+    id integer PRIMARY KEY,
+    title text NOT NULL,
+    priority integer NOT NULL DEFAULT 3
+);
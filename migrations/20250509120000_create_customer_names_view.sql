@@ -0,0 +1,3 @@
+CREATE VIEW main.customer_names AS
+SELECT id, name
+FROM main.customers;
@@ -0,0 +1,7 @@
+CREATE TYPE main.shipment_status AS ENUM ('pending', 'shipped', 'delivered');
+
+CREATE TABLE main.shipments (
+    id integer PRIMARY KEY,
+    status main.shipment_status NOT NULL,
+    carrier text NOT NULL
+);
@@ -0,0 +1,90 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::database::PgDatabase;
+use crate::error::Result as CrudkitResult;
+use crate::logging;
+use crate::traits::read::ReadRelation;
+
+/// A cache for the result of [`ReadRelation::query_all()`], refreshed after a fixed TTL.
+///
+/// This is meant for small, rarely-changing lookup tables which are read on a hot path, where
+/// re-querying the entire relation for every request is wasteful. It caches only the full relation,
+/// not individual records. The cache is typically stored in the server state and shared between
+/// handlers.
+///
+/// Writes made through Crudkit do not automatically invalidate the cache. If a relation can be
+/// written to while it is cached, [`CachedReadRelation::invalidate()`] should be called after each
+/// write so that the next read is fresh.
+pub struct CachedReadRelation<R: ReadRelation> {
+    ttl: Duration,
+    entry: Mutex<Option<CacheEntry<R>>>,
+}
+
+struct CacheEntry<R> {
+    relation: Arc<R>,
+    fetched_at: Instant,
+}
+
+impl<R: ReadRelation> CachedReadRelation<R> {
+    /// Create an empty cache which keeps each query result for the given TTL.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entry: Mutex::new(None),
+        }
+    }
+
+    /// Query all records for the relation, returning the cached result if it has not expired.
+    ///
+    /// If the cache is empty or expired, the relation is queried from the database and the cache
+    /// is refreshed. Concurrent callers may refresh an expired cache more than once, but they will
+    /// never observe a result older than the TTL.
+    pub async fn query_all_cached(&self, database: &PgDatabase) -> CrudkitResult<Arc<R>> {
+        let relation_name = R::get_qualified_name();
+
+        // * The lock must be released before querying the database, since it cannot be held across
+        // * an await point without making the future non-`Send`
+        if let Some(relation) = self.get_fresh() {
+            logging::debug!("Serving cached multi-SELECT result for relation {relation_name}");
+            return Ok(relation);
+        }
+
+        logging::debug!(
+            "Cached multi-SELECT result for relation {relation_name} is stale, refreshing"
+        );
+
+        let relation = Arc::new(R::query_all(database).await?);
+        *self.lock() = Some(CacheEntry {
+            relation: relation.clone(),
+            fetched_at: Instant::now(),
+        });
+
+        Ok(relation)
+    }
+
+    /// Clear the cache, so that the next read queries the database.
+    pub fn invalidate(&self) {
+        logging::debug!(
+            "Invalidating cached multi-SELECT result for relation {}",
+            R::get_qualified_name()
+        );
+
+        *self.lock() = None;
+    }
+
+    fn get_fresh(&self) -> Option<Arc<R>> {
+        self.lock()
+            .as_ref()
+            .filter(|entry| entry.fetched_at.elapsed() < self.ttl)
+            .map(|entry| entry.relation.clone())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<CacheEntry<R>>> {
+        // * A poisoned lock only means a panic occurred while replacing the entry, which cannot
+        // * leave it in an inconsistent state
+        self.entry
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+use sqlx::query_builder::QueryBuilder;
+use sqlx::{Encode, Postgres, Type};
+
+use crate::database::PgAcquire;
+use crate::error::{Error as CrudkitError, Result as CrudkitResult};
+use crate::logging;
+use crate::traits::read::ReadRelation;
+#[allow(unused_imports)]
+use crate::traits::shared::Relation;
+
+/// A builder for reads which select a relation's records from a common table expression (CTE).
+///
+/// This is an escape hatch for list queries which cannot be expressed with filters, such as
+/// ranking records within a group and then keeping only the top few. The body of the CTE is written
+/// by the caller using [`CteQuery::push()`] and [`CteQuery::push_bind()`], and the query is
+/// completed as `WITH <name> AS (<body>) SELECT * FROM <name> ORDER BY ...`, sorted by
+/// [`Relation::DEFAULT_ORDER`]. Calling [`CteQuery::push_where()`] adds a `WHERE` clause to the
+/// final `SELECT`, after which any pushed SQL is written to that clause instead.
+///
+/// The CTE must return every column of the relation's record type. Extra columns, such as a rank,
+/// are ignored when decoding. The SQL pushed to the builder is not checked, so it must never be
+/// built from user input; values should always be bound using [`CteQuery::push_bind()`].
+pub struct CteQuery<R: ReadRelation> {
+    query_builder: QueryBuilder<'static, Postgres>,
+    cte_name: String,
+    in_where_clause: bool,
+    relation: PhantomData<R>,
+}
+
+impl<R: ReadRelation> CteQuery<R> {
+    /// Start a query using a CTE with the given name.
+    ///
+    /// The name is rendered using the relation's
+    /// [`IdentifierStrategy`](crate::traits::shared::IdentifierStrategy).
+    pub fn new(cte_name: &str) -> Self {
+        let cte_name = R::render_identifier(cte_name).into_owned();
+        Self {
+            query_builder: QueryBuilder::new(format!("WITH {cte_name} AS (")),
+            cte_name,
+            in_where_clause: false,
+            relation: PhantomData,
+        }
+    }
+
+    /// Push raw SQL to the body of the CTE, or to the final `WHERE` clause if
+    /// [`CteQuery::push_where()`] has been called.
+    pub fn push(&mut self, sql: impl Display) -> &mut Self {
+        self.query_builder.push(sql);
+        self
+    }
+
+    /// Push a parameter binding to the body of the CTE, or to the final `WHERE` clause if
+    /// [`CteQuery::push_where()`] has been called.
+    pub fn push_bind<T>(&mut self, value: T) -> &mut Self
+    where
+        T: for<'q> Encode<'q, Postgres> + Type<Postgres> + Send + 'static,
+    {
+        self.query_builder.push_bind(value);
+        self
+    }
+
+    /// End the body of the CTE and start a `WHERE` clause on the final `SELECT`.
+    ///
+    /// This has no effect if the `WHERE` clause has already been started.
+    pub fn push_where(&mut self) -> &mut Self {
+        if !self.in_where_clause {
+            self.query_builder
+                .push(format!(") SELECT * FROM {} WHERE ", self.cte_name));
+            self.in_where_clause = true;
+        }
+
+        self
+    }
+
+    /// Execute the query and return all records selected from the CTE.
+    pub async fn fetch_all<'c>(mut self, database: impl PgAcquire<'c>) -> CrudkitResult<R> {
+        let relation_name = R::get_qualified_name();
+        if !self.in_where_clause {
+            self.query_builder
+                .push(format!(") SELECT * FROM {}", self.cte_name));
+        }
+        self.query_builder
+            .push(format!(" ORDER BY {}", R::get_order_by_clause()));
+
+        logging::debug!(
+            "Dispatching CTE multi-SELECT query to database, targeting relation \
+            {relation_name}"
+        );
+        logging::trace!(
+            "Raw query prior to variable binding: {}",
+            self.query_builder.sql()
+        );
+
+        match self
+            .query_builder
+            .build_query_as()
+            .fetch_all(&mut *database.acquire().await?)
+            .await
+        {
+            Ok(records) => Ok(R::with_records(records)),
+            Err(e) => Err(CrudkitError::from(e)),
+        }
+    }
+}
@@ -1,11 +1,20 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
 use http::StatusCode;
+use serde::Serialize;
 use sqlx::Error as SqlxError;
 
+use crate::database::DatabaseState;
+
 pub(crate) type Result<T> = core::result::Result<T, Error>;
 
-// TODO: Implement `Error` trait
 /// The Crudkit error type.
 ///
+/// The [`Display`] output contains the [`ErrorKind`] and a summary of the underlying [`sqlx`]
+/// error, if there is one. The summary never includes the database's error message, which can
+/// contain the values of query parameters, so errors can be safely logged using `{}`. For the full
+/// details, use [`Error::source`] instead.
+///
 /// It is recommended that you alias this error type when importing it, in order to avoid confusion
 /// with the many other types and traits named `Error`. This can be done like so:
 /// ```rs
@@ -28,10 +37,20 @@ pub struct Error {
     /// The HTTP status code corresponding to the error.
     ///
     /// [`Error`] is exposed directly in the return types of the read/write functions for records
-    /// and relations. In the Axum handler versions of the functions, it is converted to a
-    /// [`StatusCode`] in order to be returned as an [`axum::response::Response`]. These
-    /// [`StatusCode`] mappings are relatively basic and are subject to change in the future.
+    /// and relations. In the Axum handler versions of the functions, it is returned as an
+    /// [`axum::response::Response`] with this status code and an
+    /// [`ErrorResponse`](crate::response::ErrorResponse) body. These
+    /// [`StatusCode`] mappings are relatively basic and are subject to change in the future. The
+    /// handlers can be made to respond with different status codes by overriding
+    /// [`DatabaseState::error_status_code()`](crate::database::DatabaseState::error_status_code).
     pub status_code: StatusCode,
+    /// A description of the problem beyond the [`ErrorKind`], if there is one.
+    ///
+    /// This is only set for errors which are not caused by the database, such as parameters which
+    /// were rejected by [`Validate`](crate::validate::Validate), and is included in both the
+    /// [`Display`] output and the body of the error response. It never contains the values of
+    /// query parameters.
+    pub message: Option<String>,
 }
 
 /// A set of broad categories used by [`Error`].
@@ -40,7 +59,7 @@ pub struct Error {
 /// surface-level information that can be handled simply should an error need to be handled
 /// explicitly, but in most cases, errors should be propagated to the user via
 /// [`Error::status_code`].
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[non_exhaustive]
 pub enum ErrorKind {
     /// The database is no longer accessible because of a connection or authentication failure.
@@ -51,6 +70,149 @@ pub enum ErrorKind {
     /// The database returned an unexpected result based on the operation performed, i.e. returning
     /// no rows when expected to return a single row.
     UnexpectedQueryResult,
+    /// The write conflicts with the existing state of the relation. This is used when a write would
+    /// violate a unique constraint, such as inserting a duplicate primary key, and for optimistic
+    /// concurrency control when the record was modified by another query since it was last read,
+    /// see [`WriteRecord::VERSION_COLUMN_NAME`](crate::traits::write::WriteRecord::VERSION_COLUMN_NAME).
+    Conflict,
+    /// The write was rejected because it would violate a foreign key or not-null constraint.
+    ConstraintViolation,
+}
+
+impl Error {
+    /// Create an error which was not mapped from an underlying [`sqlx::Error`].
+    pub(crate) fn new(kind: ErrorKind, status_code: StatusCode) -> Self {
+        Self {
+            kind,
+            source: None,
+            message: None,
+            status_code,
+        }
+    }
+
+    /// Attach a description of the problem to the error, see [`Error::message`].
+    pub(crate) fn with_message(mut self, message: String) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    /// Replace the status code with the one chosen by
+    /// [`DatabaseState::error_status_code()`] for the given relation.
+    pub(crate) fn with_state_status_code<S: DatabaseState>(
+        mut self,
+        state: &S,
+        relation_name: &str,
+    ) -> Self {
+        self.status_code = state.error_status_code(relation_name, &self);
+        self
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.kind)?;
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+        if let Some(source) = &self.source {
+            write!(f, ": ")?;
+            summarize_sqlx_error(source, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let description = match self {
+            Self::BrokenDatabaseConnection => "broken database connection",
+            Self::InvalidQuery => "invalid query",
+            Self::UnexpectedQueryResult => "unexpected query result",
+            Self::Conflict => "conflicting modification",
+            Self::ConstraintViolation => "constraint violation",
+        };
+
+        write!(f, "{description}")
+    }
+}
+
+/// Write a summary of a [`sqlx::Error`] which does not include any values from the query.
+///
+/// The [`Display`] implementations of some [`sqlx::Error`] variants include messages from the
+/// database or the encoder/decoder, which can contain the values of query parameters. Only names
+/// and codes are included here instead.
+fn summarize_sqlx_error(error: &SqlxError, f: &mut Formatter<'_>) -> FmtResult {
+    match error {
+        SqlxError::Configuration(_) => write!(f, "invalid database configuration"),
+        SqlxError::Io(_) => write!(f, "I/O error while communicating with the database"),
+        SqlxError::Tls(_) => write!(f, "TLS error while connecting to the database"),
+        SqlxError::Protocol(_) => write!(f, "database protocol error"),
+        SqlxError::AnyDriverError(_) => write!(f, "database driver error"),
+        SqlxError::PoolTimedOut => write!(f, "timed out waiting for a database connection"),
+        SqlxError::PoolClosed => write!(f, "database connection pool is closed"),
+        SqlxError::WorkerCrashed => write!(f, "database connection worker crashed"),
+        SqlxError::Database(database_error) => {
+            write!(f, "database error")?;
+            if let Some(code) = database_error.code() {
+                write!(f, " (code {code})")?;
+            }
+            if let Some(constraint) = database_error.constraint() {
+                write!(f, " on constraint {constraint}")?;
+            }
+
+            Ok(())
+        }
+        SqlxError::TypeNotFound { type_name } => write!(f, "type {type_name} not found"),
+        SqlxError::ColumnIndexOutOfBounds { index, len } => {
+            write!(
+                f,
+                "column index {index} out of bounds for row of {len} columns"
+            )
+        }
+        SqlxError::ColumnNotFound(column_name) => write!(f, "column {column_name} not found"),
+        SqlxError::ColumnDecode { index, .. } => write!(f, "failed to decode column {index}"),
+        SqlxError::Encode(_) => write!(f, "failed to encode query parameter"),
+        SqlxError::Decode(_) => write!(f, "failed to decode query result"),
+        SqlxError::RowNotFound => write!(f, "no rows returned"),
+        _ => write!(f, "unknown database error"),
+    }
+}
+
+/// The SQLSTATE code used by Postgres for unique constraint violations.
+const UNIQUE_VIOLATION_CODE: &str = "23505";
+/// The SQLSTATE code used by Postgres for foreign key constraint violations.
+const FOREIGN_KEY_VIOLATION_CODE: &str = "23503";
+/// The SQLSTATE code used by Postgres for not-null constraint violations.
+const NOT_NULL_VIOLATION_CODE: &str = "23502";
+/// The SQLSTATE class used by Postgres for connection exceptions.
+const CONNECTION_EXCEPTION_CLASS: &str = "08";
+/// The SQLSTATE class used by Postgres for data exceptions, such as malformed timestamps.
+const DATA_EXCEPTION_CLASS: &str = "22";
+/// The SQLSTATE codes used by Postgres when the server is shutting down or starting up.
+const SERVER_UNAVAILABLE_CODES: [&str; 3] = ["57P01", "57P02", "57P03"];
+
+/// Check whether a [`sqlx::Error`] is caused by a transient connection failure, such as the
+/// database restarting, so that the operation may succeed if it is retried.
+///
+/// Errors caused by the query itself, such as constraint violations, are never transient.
+pub(crate) fn is_transient_sqlx_error(error: &SqlxError) -> bool {
+    match error {
+        SqlxError::Io(_) | SqlxError::PoolTimedOut => true,
+        SqlxError::Database(database_error) => database_error.code().is_some_and(|code| {
+            code.starts_with(CONNECTION_EXCEPTION_CLASS)
+                || SERVER_UNAVAILABLE_CODES.contains(&code.as_ref())
+        }),
+        _ => false,
+    }
 }
 
 impl From<SqlxError> for Error {
@@ -63,31 +225,63 @@ impl From<SqlxError> for Error {
             | SqlxError::AnyDriverError(_)
             | SqlxError::PoolTimedOut
             | SqlxError::PoolClosed
-            | SqlxError::WorkerCrashed
-            | SqlxError::Database(_) => Self {
+            | SqlxError::WorkerCrashed => Self {
                 kind: ErrorKind::BrokenDatabaseConnection,
                 source: Some(source_error),
+                message: None,
                 status_code: StatusCode::INTERNAL_SERVER_ERROR,
             },
+            SqlxError::Database(database_error) => {
+                let (kind, status_code) = match database_error.code().as_deref() {
+                    Some(UNIQUE_VIOLATION_CODE) => (ErrorKind::Conflict, StatusCode::CONFLICT),
+                    Some(FOREIGN_KEY_VIOLATION_CODE | NOT_NULL_VIOLATION_CODE) => {
+                        (ErrorKind::ConstraintViolation, StatusCode::BAD_REQUEST)
+                    }
+                    Some(code) if code.starts_with(DATA_EXCEPTION_CLASS) => {
+                        (ErrorKind::InvalidQuery, StatusCode::BAD_REQUEST)
+                    }
+                    _ => (
+                        ErrorKind::BrokenDatabaseConnection,
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                };
+
+                Self {
+                    kind,
+                    source: Some(source_error),
+                    message: None,
+                    status_code,
+                }
+            }
             SqlxError::TypeNotFound { .. }
             | SqlxError::ColumnIndexOutOfBounds { .. }
             | SqlxError::ColumnNotFound(_)
             | SqlxError::Encode(_) => Self {
                 kind: ErrorKind::InvalidQuery,
                 source: Some(source_error),
+                message: None,
                 status_code: StatusCode::BAD_REQUEST,
             },
             SqlxError::RowNotFound => Self {
                 kind: ErrorKind::UnexpectedQueryResult,
                 source: Some(source_error),
+                message: None,
                 status_code: StatusCode::NOT_FOUND,
             },
-            SqlxError::Decode(_) => Self {
+            SqlxError::ColumnDecode { .. } | SqlxError::Decode(_) => Self {
                 kind: ErrorKind::UnexpectedQueryResult,
                 source: Some(source_error),
+                message: None,
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            // * This covers transaction failures such as `BeginFailed`, as well as any variants
+            // * which are added to `sqlx::Error` in the future
+            _ => Self {
+                kind: ErrorKind::BrokenDatabaseConnection,
+                source: Some(source_error),
+                message: None,
                 status_code: StatusCode::INTERNAL_SERVER_ERROR,
             },
-            _ => todo!(),
         }
     }
 }
@@ -0,0 +1,614 @@
+use std::collections::{BTreeMap, HashMap};
+
+use http::StatusCode;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::PgArguments;
+use sqlx::query_builder::QueryBuilder;
+use sqlx::{Arguments, Encode, Postgres, Type};
+
+use crate::error::{Error as CrudkitError, ErrorKind, Result as CrudkitResult};
+use crate::logging;
+use crate::traits::shared::{IdentifierStrategy, Record, Relation};
+
+/// A condition used to narrow down the records affected by a query.
+///
+/// Filters are always combined using `AND`. The column targeted by a filter is validated against
+/// [`Record::COLUMN_NAMES`] before any SQL is generated, and the filter value is always bound as a
+/// query parameter, so filters are safe to construct from user input.
+pub struct Filter {
+    /// The column (or column expression) being filtered on.
+    pub column: FilterColumn,
+    /// The condition the column must satisfy.
+    pub condition: FilterCondition,
+}
+
+impl Filter {
+    /// Create a filter which matches records where the column is equal to the given value.
+    pub fn equals(column: impl Into<FilterColumn>, value: impl Into<FilterValue>) -> Self {
+        Self {
+            column: column.into(),
+            condition: FilterCondition::Equals(value.into()),
+        }
+    }
+
+    /// Create a filter which matches records where the column is not equal to the given value.
+    pub fn not_equals(column: impl Into<FilterColumn>, value: impl Into<FilterValue>) -> Self {
+        Self {
+            column: column.into(),
+            condition: FilterCondition::NotEquals(value.into()),
+        }
+    }
+
+    /// Create a filter which matches records where the column is greater than the given value.
+    pub fn greater_than(column: impl Into<FilterColumn>, value: impl Into<FilterValue>) -> Self {
+        Self {
+            column: column.into(),
+            condition: FilterCondition::GreaterThan(value.into()),
+        }
+    }
+
+    /// Create a filter which matches records where the column is less than the given value.
+    pub fn less_than(column: impl Into<FilterColumn>, value: impl Into<FilterValue>) -> Self {
+        Self {
+            column: column.into(),
+            condition: FilterCondition::LessThan(value.into()),
+        }
+    }
+
+    /// Create a filter which matches records where the column matches the given `LIKE` pattern.
+    pub fn like(column: impl Into<FilterColumn>, pattern: impl Into<FilterValue>) -> Self {
+        Self {
+            column: column.into(),
+            condition: FilterCondition::Like(pattern.into()),
+        }
+    }
+
+    /// Create a filter which matches records where the column is equal to any of the given values.
+    ///
+    /// If no values are given, the filter matches no records.
+    pub fn is_in<T: Into<FilterValue>>(
+        column: impl Into<FilterColumn>,
+        values: impl IntoIterator<Item = T>,
+    ) -> Self {
+        Self {
+            column: column.into(),
+            condition: FilterCondition::In(values.into_iter().map(Into::into).collect()),
+        }
+    }
+
+    /// Create a filter which matches records where the column falls within the given range.
+    ///
+    /// Both bounds are inclusive. If neither bound is set, the filter matches every record.
+    pub fn range<T: Into<FilterValue>>(
+        column: impl Into<FilterColumn>,
+        range: RangeFilter<T>,
+    ) -> Self {
+        Self {
+            column: column.into(),
+            condition: FilterCondition::Range {
+                from: range.from.map(Into::into),
+                to: range.to.map(Into::into),
+            },
+        }
+    }
+
+    /// Push the filter's condition into the [`QueryBuilder`].
+    ///
+    /// If the condition does not constrain the column at all, nothing is pushed and `false` is
+    /// returned.
+    fn push_condition(
+        &self,
+        builder: &mut QueryBuilder<'_, Postgres>,
+        prefix: &str,
+        strategy: IdentifierStrategy,
+    ) -> bool {
+        let column = &self.column;
+        let (operator, value) = match &self.condition {
+            FilterCondition::Equals(value) => (" = ", value),
+            FilterCondition::NotEquals(value) => (" <> ", value),
+            FilterCondition::GreaterThan(value) => (" > ", value),
+            FilterCondition::LessThan(value) => (" < ", value),
+            FilterCondition::Like(value) => (" LIKE ", value),
+            FilterCondition::In(values) => {
+                builder.push(prefix);
+                // * `IN ()` is not valid SQL, so an empty list is written as a condition which
+                // * never matches
+                if values.is_empty() {
+                    builder.push("FALSE");
+                    return true;
+                }
+
+                column.push_column(builder, strategy);
+                builder.push(" IN (");
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        builder.push(", ");
+                    }
+
+                    column.push_value(builder, value);
+                }
+                builder.push(")");
+
+                return true;
+            }
+            FilterCondition::Range {
+                from: Some(from),
+                to: Some(to),
+            } => {
+                builder.push(prefix);
+                column.push_column(builder, strategy);
+                builder.push(" BETWEEN ");
+                column.push_value(builder, from);
+                builder.push(" AND ");
+                column.push_value(builder, to);
+
+                return true;
+            }
+            FilterCondition::Range {
+                from: Some(from),
+                to: None,
+            } => (" >= ", from),
+            FilterCondition::Range {
+                from: None,
+                to: Some(to),
+            } => (" <= ", to),
+            FilterCondition::Range {
+                from: None,
+                to: None,
+            } => return false,
+        };
+
+        builder.push(prefix);
+        column.push_column(builder, strategy);
+        builder.push(operator);
+        column.push_value(builder, value);
+
+        true
+    }
+}
+
+/// The condition which a [`Filter`] applies to its column.
+#[non_exhaustive]
+pub enum FilterCondition {
+    /// `column = value`
+    Equals(FilterValue),
+    /// `column <> value`
+    NotEquals(FilterValue),
+    /// `column > value`
+    GreaterThan(FilterValue),
+    /// `column < value`
+    LessThan(FilterValue),
+    /// `column LIKE pattern`
+    Like(FilterValue),
+    /// `column IN (value, ...)`
+    In(Vec<FilterValue>),
+    /// `column BETWEEN from AND to`, or `column >= from`/`column <= to` if only one bound is set.
+    Range {
+        from: Option<FilterValue>,
+        to: Option<FilterValue>,
+    },
+}
+
+/// An inclusive range of values used to create a range [`Filter`].
+///
+/// Either bound can be left open by setting it to [`None`].
+pub struct RangeFilter<T> {
+    /// The lower bound of the range.
+    pub from: Option<T>,
+    /// The upper bound of the range.
+    pub to: Option<T>,
+}
+
+impl<T> Default for RangeFilter<T> {
+    fn default() -> Self {
+        Self {
+            from: None,
+            to: None,
+        }
+    }
+}
+
+/// A column targeted by a [`Filter`], optionally wrapped in a SQL function.
+///
+/// When a function is used, it is applied to both the column and the bound value, which allows
+/// filters such as `lower(email) = lower($1)` to make use of functional indexes.
+pub struct FilterColumn {
+    /// The name of the column, which must be present in [`Record::COLUMN_NAMES`].
+    pub name: String,
+    /// The function to apply to the column and value, if any.
+    pub function: Option<ColumnFunction>,
+}
+
+impl FilterColumn {
+    /// Target a column directly.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            function: None,
+        }
+    }
+
+    /// Target a column wrapped in `lower(...)`.
+    pub fn lower(name: impl Into<String>) -> Self {
+        Self::with_function(name, ColumnFunction::Lower)
+    }
+
+    /// Target a column wrapped in `upper(...)`.
+    pub fn upper(name: impl Into<String>) -> Self {
+        Self::with_function(name, ColumnFunction::Upper)
+    }
+
+    /// Target a column wrapped in `date_trunc('<precision>', ...)`.
+    pub fn date_trunc(name: impl Into<String>, precision: DateTruncPrecision) -> Self {
+        Self::with_function(name, ColumnFunction::DateTrunc(precision))
+    }
+
+    fn with_function(name: impl Into<String>, function: ColumnFunction) -> Self {
+        Self {
+            name: name.into(),
+            function: Some(function),
+        }
+    }
+
+    /// Push the column, wrapped in its function if it has one, into the [`QueryBuilder`].
+    fn push_column(&self, builder: &mut QueryBuilder<'_, Postgres>, strategy: IdentifierStrategy) {
+        self.push_wrapped(builder, |builder| {
+            builder.push(strategy.render(&self.name));
+        });
+    }
+
+    /// Push the value, wrapped in the column's function if it has one, into the [`QueryBuilder`].
+    fn push_value(&self, builder: &mut QueryBuilder<'_, Postgres>, value: &FilterValue) {
+        self.push_wrapped(builder, |builder| value.push_bind(builder));
+    }
+
+    fn push_wrapped<'a>(
+        &self,
+        builder: &mut QueryBuilder<'a, Postgres>,
+        push_inner: impl FnOnce(&mut QueryBuilder<'a, Postgres>),
+    ) {
+        match &self.function {
+            None => push_inner(builder),
+            Some(function) => {
+                builder.push(function.prefix());
+                push_inner(builder);
+                builder.push(")");
+            }
+        }
+    }
+}
+
+impl From<&str> for FilterColumn {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for FilterColumn {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+/// The set of SQL functions which can be applied to a [`FilterColumn`].
+///
+/// This is deliberately a fixed list rather than an arbitrary function name, because the function
+/// is written directly into the generated SQL and therefore cannot be bound as a parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ColumnFunction {
+    /// `lower(column)`
+    Lower,
+    /// `upper(column)`
+    Upper,
+    /// `date_trunc('<precision>', column)`
+    DateTrunc(DateTruncPrecision),
+}
+
+impl ColumnFunction {
+    /// Get the opening portion of the function call, up to and including the opening parenthesis.
+    fn prefix(&self) -> String {
+        match self {
+            Self::Lower => "lower(".to_owned(),
+            Self::Upper => "upper(".to_owned(),
+            Self::DateTrunc(precision) => format!("date_trunc('{}', ", precision.as_str()),
+        }
+    }
+}
+
+/// The precisions supported by [`ColumnFunction::DateTrunc`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateTruncPrecision {
+    Microseconds,
+    Milliseconds,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+    Decade,
+    Century,
+    Millennium,
+}
+
+impl DateTruncPrecision {
+    /// Get the name of the precision as expected by Postgres.
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Microseconds => "microseconds",
+            Self::Milliseconds => "milliseconds",
+            Self::Second => "second",
+            Self::Minute => "minute",
+            Self::Hour => "hour",
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Quarter => "quarter",
+            Self::Year => "year",
+            Self::Decade => "decade",
+            Self::Century => "century",
+            Self::Millennium => "millennium",
+        }
+    }
+}
+
+/// A value which is bound as a query parameter when a [`Filter`] is applied.
+///
+/// Any type which can be bound to a Postgres query can be converted into a [`FilterValue`] using
+/// [`From`]/[`Into`]. Values which only exist as text, such as those parsed from query parameters,
+/// can be cast to the type of the column using [`FilterValue::with_cast()`]. These values are also
+/// used as the parameters of [`PgDatabase::query_json()`](crate::database::PgDatabase::query_json).
+pub struct FilterValue {
+    value: Box<dyn BindValue>,
+    cast: Option<ValueCast>,
+}
+
+impl FilterValue {
+    /// Cast the bound parameter to the given type in the generated SQL, as in `$1::timestamptz`.
+    pub fn with_cast(mut self, cast: ValueCast) -> Self {
+        self.cast = Some(cast);
+        self
+    }
+
+    /// Push the value into the [`QueryBuilder`] as a bound parameter.
+    fn push_bind(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        self.value.push_bind(builder);
+        if let Some(cast) = self.cast {
+            builder.push("::");
+            builder.push(cast.as_str());
+        }
+    }
+
+    /// Add the value to a set of query arguments, ignoring any cast.
+    pub(crate) fn add_to_arguments(&self, arguments: &mut PgArguments) -> Result<(), BoxDynError> {
+        self.value.add_to_arguments(arguments)
+    }
+}
+
+impl<T> From<T> for FilterValue
+where
+    T: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Send + Sync + 'static,
+{
+    fn from(value: T) -> Self {
+        Self {
+            value: Box::new(value),
+            cast: None,
+        }
+    }
+}
+
+/// The set of types which a [`FilterValue`] can be cast to.
+///
+/// Like [`ColumnFunction`], this is a fixed list because the type name is written directly into
+/// the generated SQL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValueCast {
+    Text,
+    Boolean,
+    Integer,
+    BigInt,
+    Numeric,
+    Date,
+    Timestamp,
+    Timestamptz,
+}
+
+impl ValueCast {
+    /// Get the cast corresponding to a column type, as listed in [`Record::COLUMN_TYPES`].
+    fn from_column_type(column_type: &str) -> Option<Self> {
+        let cast = match column_type {
+            "text" => Self::Text,
+            "boolean" => Self::Boolean,
+            "integer" => Self::Integer,
+            "bigint" => Self::BigInt,
+            "numeric" => Self::Numeric,
+            "date" => Self::Date,
+            "timestamp without time zone" => Self::Timestamp,
+            "timestamp with time zone" => Self::Timestamptz,
+            _ => return None,
+        };
+
+        Some(cast)
+    }
+
+    /// Get the name of the type as expected by Postgres.
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Boolean => "boolean",
+            Self::Integer => "integer",
+            Self::BigInt => "bigint",
+            Self::Numeric => "numeric",
+            Self::Date => "date",
+            Self::Timestamp => "timestamp",
+            Self::Timestamptz => "timestamptz",
+        }
+    }
+}
+
+/// An object-safe wrapper over the bounds needed to bind a value to a [`QueryBuilder`].
+trait BindValue: Send + Sync {
+    fn push_bind(&self, builder: &mut QueryBuilder<'_, Postgres>);
+    fn add_to_arguments(&self, arguments: &mut PgArguments) -> Result<(), BoxDynError>;
+}
+
+impl<T> BindValue for T
+where
+    T: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Send + Sync + 'static,
+{
+    fn push_bind(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        builder.push_bind(self.clone());
+    }
+
+    fn add_to_arguments(&self, arguments: &mut PgArguments) -> Result<(), BoxDynError> {
+        arguments.add(self.clone())
+    }
+}
+
+/// Push a `WHERE` clause for the given filters into the [`QueryBuilder`], also excluding records
+/// which have been soft-deleted if the relation has a [`Relation::SOFT_DELETE_COLUMN`].
+///
+/// Nothing is pushed if there are no filters and the relation does not use soft deletes. If any
+/// filter targets a column that is not present in [`Record::COLUMN_NAMES`], an
+/// [`ErrorKind::InvalidQuery`] error is returned.
+pub(crate) fn push_visible_where_clause<R: Record>(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    filters: &[Filter],
+) -> CrudkitResult<()> {
+    let has_conditions = push_filter_conditions::<R>(builder, filters)?;
+    if let Some(condition) = R::Relation::get_soft_delete_condition() {
+        builder.push(if has_conditions { " AND " } else { " WHERE " });
+        builder.push(condition);
+    }
+
+    Ok(())
+}
+
+/// Push the condition for each filter, returning whether any conditions were pushed.
+fn push_filter_conditions<R: Record>(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    filters: &[Filter],
+) -> CrudkitResult<bool> {
+    let mut has_conditions = false;
+    for filter in filters {
+        if !R::COLUMN_NAMES.contains(&filter.column.name.as_str()) {
+            logging::debug!("Rejecting filter on unknown column {}", filter.column.name);
+            return Err(invalid_filter_error());
+        }
+
+        let prefix = if has_conditions { " AND " } else { " WHERE " };
+        has_conditions |= filter.push_condition(builder, prefix, R::Relation::IDENTIFIER_STRATEGY);
+    }
+
+    Ok(has_conditions)
+}
+
+/// Parse range filters from query parameters of the form `<column>_from` and `<column>_to`.
+///
+/// Because query parameters are untyped, every bound is cast to `timestamptz`, which makes this
+/// suitable for filtering timestamp columns such as `created_at` using `created_at_from` and
+/// `created_at_to`. Any parameter without one of the two suffixes results in an
+/// [`ErrorKind::InvalidQuery`] error.
+pub(crate) fn parse_timestamp_range_parameters(
+    parameters: HashMap<String, String>,
+) -> CrudkitResult<Vec<Filter>> {
+    let mut ranges: BTreeMap<String, RangeFilter<FilterValue>> = BTreeMap::new();
+    for (name, value) in parameters {
+        let value = FilterValue::from(value).with_cast(ValueCast::Timestamptz);
+        if let Some(column) = name.strip_suffix("_from") {
+            ranges.entry(column.to_owned()).or_default().from = Some(value);
+        } else if let Some(column) = name.strip_suffix("_to") {
+            ranges.entry(column.to_owned()).or_default().to = Some(value);
+        } else {
+            logging::debug!("Rejecting unexpected range parameter {name}");
+            return Err(invalid_filter_error());
+        }
+    }
+
+    Ok(ranges
+        .into_iter()
+        .map(|(column, range)| Filter::range(column, range))
+        .collect())
+}
+
+/// Parse filters from query parameters of the form `<column>=<operator>.<value>`, such as
+/// `name=eq.John` or `id=in.(1,2,3)`.
+///
+/// The supported operators are `eq`, `neq`, `gt`, `lt`, `like`, and `in`, following the syntax used
+/// by PostgREST. In `like` patterns, `*` can be used in place of `%` to avoid URL-encoding it. Each
+/// value is cast to the type of its column if the type is known from [`Record::COLUMN_TYPES`], and
+/// is otherwise bound as text. Unknown columns or operators result in an
+/// [`ErrorKind::InvalidQuery`] error.
+pub(crate) fn parse_where_parameters<R: Record>(
+    parameters: HashMap<String, String>,
+) -> CrudkitResult<Vec<Filter>> {
+    let mut parameters: Vec<(String, String)> = parameters.into_iter().collect();
+    // * Sorting keeps the generated SQL stable regardless of the order of the query parameters
+    parameters.sort();
+
+    parameters
+        .into_iter()
+        .map(|(column, parameter)| {
+            let Some(column_index) = R::COLUMN_NAMES.iter().position(|name| *name == column) else {
+                logging::debug!("Rejecting filter parameter on unknown column {column}");
+                return Err(invalid_filter_error());
+            };
+            let cast = R::COLUMN_TYPES
+                .get(column_index)
+                .copied()
+                .flatten()
+                .and_then(ValueCast::from_column_type);
+            let to_value = |value: &str| {
+                let value = FilterValue::from(value.to_owned());
+                match cast {
+                    Some(cast) => value.with_cast(cast),
+                    None => value,
+                }
+            };
+
+            let Some((operator, value)) = parameter.split_once('.') else {
+                logging::debug!(
+                    "Rejecting filter parameter without an operator on column {column}"
+                );
+                return Err(invalid_filter_error());
+            };
+
+            let filter = match operator {
+                "eq" => Filter::equals(column, to_value(value)),
+                "neq" => Filter::not_equals(column, to_value(value)),
+                "gt" => Filter::greater_than(column, to_value(value)),
+                "lt" => Filter::less_than(column, to_value(value)),
+                "like" => Filter::like(column, to_value(&value.replace('*', "%"))),
+                "in" => {
+                    let Some(values) = value.strip_prefix('(').and_then(|v| v.strip_suffix(')'))
+                    else {
+                        logging::debug!("Rejecting malformed list in filter on column {column}");
+                        return Err(invalid_filter_error());
+                    };
+                    let values: Vec<FilterValue> = match values.is_empty() {
+                        true => Vec::new(),
+                        false => values.split(',').map(to_value).collect(),
+                    };
+
+                    Filter::is_in(column, values)
+                }
+                _ => {
+                    logging::debug!(
+                        "Rejecting unknown filter operator {operator} on column {column}"
+                    );
+                    return Err(invalid_filter_error());
+                }
+            };
+
+            Ok(filter)
+        })
+        .collect()
+}
+
+fn invalid_filter_error() -> CrudkitError {
+    CrudkitError::new(ErrorKind::InvalidQuery, StatusCode::BAD_REQUEST)
+}
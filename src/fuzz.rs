@@ -0,0 +1,21 @@
+use arbitrary::{Result as ArbitraryResult, Unstructured};
+
+/// Generate an arbitrary string which can be stored in a Postgres `text` column.
+///
+/// Postgres does not allow NUL characters in text values, so they are removed from the generated
+/// string. This is used by the `arbitrary::Arbitrary` implementation generated by
+/// `#[derive(ArbitraryRecord)]`.
+pub fn arbitrary_text(u: &mut Unstructured<'_>) -> ArbitraryResult<String> {
+    let text: String = u.arbitrary()?;
+    Ok(text.replace('\0', ""))
+}
+
+/// Generate an arbitrary optional string which can be stored in a nullable Postgres `text` column.
+///
+/// See [`arbitrary_text()`].
+pub fn arbitrary_optional_text(u: &mut Unstructured<'_>) -> ArbitraryResult<Option<String>> {
+    match u.arbitrary()? {
+        true => Ok(Some(arbitrary_text(u)?)),
+        false => Ok(None),
+    }
+}
@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use http::{HeaderMap, StatusCode};
+use sqlx::PgConnection;
+
+use crate::database::PgDatabase;
+use crate::error::{Error as CrudkitError, ErrorKind, Result as CrudkitResult};
+use crate::logging;
+use crate::traits::shared::DEFAULT_SCHEMA_NAME;
+#[allow(unused_imports)]
+use crate::traits::write::WriteRelation;
+
+/// The name of the request header which carries the idempotency key of a CREATE request.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// The schema of the table in which processed idempotency keys are stored.
+pub const IDEMPOTENCY_SCHEMA_NAME: &str = DEFAULT_SCHEMA_NAME;
+/// The name of the table in which processed idempotency keys are stored.
+pub const IDEMPOTENCY_RELATION_NAME: &str = "crudkit_idempotency_keys";
+
+/// Get the idempotency key of a request from its headers, if one was provided.
+///
+/// Keys which are empty or are not valid visible ASCII are ignored.
+pub(crate) fn get_idempotency_key(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|key| !key.is_empty())
+        .map(str::to_owned)
+}
+
+/// Remove the entry for an idempotency key if it was stored for the given relation longer ago than
+/// the TTL, so that the request is processed again.
+pub(crate) async fn delete_expired_key(
+    connection: &mut PgConnection,
+    relation_name: &str,
+    key: &str,
+    ttl: Duration,
+) -> CrudkitResult<()> {
+    let query_string = format!(
+        "DELETE FROM {IDEMPOTENCY_SCHEMA_NAME}.{IDEMPOTENCY_RELATION_NAME} \
+        WHERE relation_name = $1 AND idempotency_key = $2 \
+        AND created_at < now() - make_interval(secs => $3)"
+    );
+
+    logging::trace!("Raw query prior to variable binding: {query_string}");
+
+    sqlx::query(&query_string)
+        .bind(relation_name)
+        .bind(key)
+        .bind(ttl.as_secs_f64())
+        .execute(connection)
+        .await
+        .map_err(CrudkitError::from)?;
+
+    Ok(())
+}
+
+/// Claim an idempotency key for the given relation, returning whether it was claimed.
+///
+/// The key is stored with the status of a successful CREATE, which is replaced using
+/// [`store_status()`] if the request fails. If another request has claimed the key in a
+/// transaction which is not yet committed, this waits for that transaction to finish, so only one
+/// of several concurrent requests with the same key can claim it.
+pub(crate) async fn claim_key(
+    connection: &mut PgConnection,
+    relation_name: &str,
+    key: &str,
+) -> CrudkitResult<bool> {
+    let query_string = format!(
+        "INSERT INTO {IDEMPOTENCY_SCHEMA_NAME}.{IDEMPOTENCY_RELATION_NAME} \
+        (relation_name, idempotency_key, status_code) VALUES ($1, $2, $3) \
+        ON CONFLICT DO NOTHING RETURNING status_code"
+    );
+
+    logging::trace!("Raw query prior to variable binding: {query_string}");
+
+    let claimed: Option<i16> = sqlx::query_scalar(&query_string)
+        .bind(relation_name)
+        .bind(key)
+        .bind(StatusCode::CREATED.as_u16() as i16)
+        .fetch_optional(connection)
+        .await
+        .map_err(CrudkitError::from)?;
+
+    Ok(claimed.is_some())
+}
+
+/// Get the stored response status for an idempotency key which was claimed by another request.
+pub(crate) async fn get_stored_status(
+    connection: &mut PgConnection,
+    relation_name: &str,
+    key: &str,
+) -> CrudkitResult<StatusCode> {
+    let query_string = format!(
+        "SELECT status_code FROM {IDEMPOTENCY_SCHEMA_NAME}.{IDEMPOTENCY_RELATION_NAME} \
+        WHERE relation_name = $1 AND idempotency_key = $2"
+    );
+
+    logging::trace!("Raw query prior to variable binding: {query_string}");
+
+    let status_code: i16 = sqlx::query_scalar(&query_string)
+        .bind(relation_name)
+        .bind(key)
+        .fetch_one(connection)
+        .await
+        .map_err(CrudkitError::from)?;
+
+    StatusCode::from_u16(status_code as u16).map_err(|_| {
+        CrudkitError::new(
+            ErrorKind::UnexpectedQueryResult,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })
+}
+
+/// Replace the stored response status for an idempotency key claimed using [`claim_key()`].
+pub(crate) async fn store_status(
+    connection: &mut PgConnection,
+    relation_name: &str,
+    key: &str,
+    status_code: StatusCode,
+) -> CrudkitResult<()> {
+    let query_string = format!(
+        "UPDATE {IDEMPOTENCY_SCHEMA_NAME}.{IDEMPOTENCY_RELATION_NAME} SET status_code = $3 \
+        WHERE relation_name = $1 AND idempotency_key = $2"
+    );
+
+    logging::trace!("Raw query prior to variable binding: {query_string}");
+
+    sqlx::query(&query_string)
+        .bind(relation_name)
+        .bind(key)
+        .bind(status_code.as_u16() as i16)
+        .execute(connection)
+        .await
+        .map_err(CrudkitError::from)?;
+
+    Ok(())
+}
+
+impl PgDatabase {
+    /// Create the table used to store processed idempotency keys, if it does not already exist.
+    ///
+    /// This must be called (or the equivalent table created by a migration) before using
+    /// [`WriteRelation::create_one_idempotent_handler()`].
+    pub async fn create_idempotency_table(&self) -> CrudkitResult<()> {
+        let query_string = format!(
+            "CREATE TABLE IF NOT EXISTS {IDEMPOTENCY_SCHEMA_NAME}.{IDEMPOTENCY_RELATION_NAME} (
+                relation_name text NOT NULL,
+                idempotency_key text NOT NULL,
+                status_code smallint NOT NULL,
+                created_at timestamptz NOT NULL DEFAULT now(),
+                PRIMARY KEY (relation_name, idempotency_key)
+            )"
+        );
+
+        logging::debug!("Creating idempotency key table if it does not already exist");
+        logging::trace!("Raw query: {query_string}");
+
+        match sqlx::query(&query_string)
+            .execute(&mut *self.acquire().await?)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(CrudkitError::from(e)),
+        }
+    }
+}
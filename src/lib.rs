@@ -1,19 +1,61 @@
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod cte;
 pub mod database;
 pub mod error;
+pub mod filter;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
+pub mod idempotency;
+pub(crate) mod logging;
+#[cfg(feature = "observer")]
+pub mod observer;
+#[cfg(feature = "openapi")]
+pub mod openapi;
+pub mod pagination;
+pub mod projection;
+pub mod response;
+pub mod router;
+pub mod schema;
+pub mod search;
+pub mod sort;
 pub mod traits;
+pub mod validate;
 
+#[cfg(feature = "arbitrary")]
+pub use arbitrary;
 pub use axum;
 pub use crudkit_derive::*;
 pub use http;
 pub use log;
+pub use serde_json;
+#[cfg(feature = "tracing")]
+pub use tracing;
+#[cfg(feature = "openapi")]
+pub use utoipa;
+#[cfg(feature = "uuid")]
+pub use uuid;
+#[cfg(feature = "validator")]
+pub use validator;
 
 pub mod prelude {
-    pub use super::traits::id_parameter::{GenericIdParameter, IdParameter};
+    pub use super::database::{DatabaseState, PgDatabase, SQL_PARAMETER_BIND_LIMIT};
+    pub use super::error::{Error, ErrorKind};
+    pub use super::response::CrudResponse;
+    pub use super::schema::GenerateTable;
+    pub use super::traits::id_parameter::{
+        CompositeIdParameter, GenericIdParameter, IdListParameter, IdParameter,
+    };
     pub use super::traits::read::{ReadRecord, ReadRelation};
-    pub use super::traits::shared::{IdentifiableRecord, Record, Relation};
-    pub use super::traits::write::{BulkInsert, SingleInsert, WriteRecord, WriteRelation};
-    pub use crudkit_derive::IdParameter;
+    pub use super::traits::shared::{IdentifiableRecord, KeyValueRecord, Record, Relation};
+    pub use super::traits::write::{
+        BulkInsert, FieldUpdate, SingleInsert, UpdateValue, WriteRecord, WriteRelation,
+    };
+    #[cfg(feature = "arbitrary")]
+    pub use crudkit_derive::ArbitraryRecord;
+    pub use crudkit_derive::GenerateTable;
     pub use crudkit_derive::{BulkInsert, SingleInsert, WriteRecord, WriteRelation};
+    pub use crudkit_derive::{CompositeIdParameter, IdParameter};
     pub use crudkit_derive::{IdentifiableRecord, Record, Relation};
     pub use crudkit_derive::{ReadRecord, ReadRelation};
 }
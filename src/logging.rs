@@ -0,0 +1,44 @@
+//! Logging macros which forward to [`log`] by default, or to [`tracing`] when the `tracing` feature
+//! is enabled.
+//!
+//! With the `tracing` feature, each query method also runs within a span carrying the relation
+//! name and operation, so that the queries made for a single request can be correlated.
+//! Query methods are also wrapped here to report their duration with the `observer` feature.
+
+use std::future::Future;
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) use log::{debug, error, trace, warn};
+#[cfg(feature = "tracing")]
+pub(crate) use tracing::{debug, error, trace, warn};
+
+use crate::traits::shared::Relation;
+
+/// Run a query method's future within a span for the given operation on a relation.
+///
+/// With the `tracing` feature, the span carries the operation and relation name. With the
+/// `observer` feature, the duration of the future is also reported to the
+/// [`QueryObserver`](crate::observer::QueryObserver). Without either feature, the future is run
+/// as-is.
+#[allow(clippy::extra_unused_type_parameters)]
+pub(crate) fn instrument<R: Relation, F: Future>(
+    operation: &'static str,
+    future: F,
+) -> impl Future<Output = F::Output> {
+    _ = operation;
+
+    #[cfg(feature = "observer")]
+    let future = crate::observer::observe::<R, F>(operation, future);
+
+    #[cfg(feature = "tracing")]
+    let future = tracing::Instrument::instrument(
+        future,
+        tracing::debug_span!(
+            "crudkit_query",
+            operation,
+            relation = %format_args!("{}.{}", R::SCHEMA_NAME, R::RELATION_NAME),
+        ),
+    );
+
+    future
+}
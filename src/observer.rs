@@ -0,0 +1,54 @@
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::traits::shared::Relation;
+
+static QUERY_OBSERVER: OnceLock<Box<dyn QueryObserver>> = OnceLock::new();
+
+/// A hook which is notified of the duration of each query made by Crudkit, such as to record query
+/// latency metrics.
+///
+/// The observer is set once for the whole application using [`set_query_observer()`]. It is called
+/// after each read/write method of a relation or record, such as
+/// [`ReadRelation::query_all()`](crate::traits::read::ReadRelation::query_all), completes. The
+/// duration covers the whole method, including acquiring a connection and decoding the results,
+/// and is recorded whether the method succeeds or fails. Handlers are not observed separately,
+/// because they call the standard methods.
+pub trait QueryObserver: Send + Sync + 'static {
+    /// Record that an operation on a relation took the given amount of time.
+    ///
+    /// The operation is the name of the method, such as `"query_all"`, and the relation name is
+    /// qualified by its schema, as returned by [`Relation::get_qualified_name()`]. This is called
+    /// on the task which made the query, so it should not block.
+    fn observe(&self, operation: &str, relation_name: &str, elapsed: Duration);
+}
+
+/// Set the [`QueryObserver`] which is notified of each query.
+///
+/// The observer can only be set once. If one has already been set, the given observer is returned
+/// as an error.
+pub fn set_query_observer<O: QueryObserver>(observer: O) -> Result<(), O> {
+    let mut observer = Some(observer);
+    QUERY_OBSERVER.get_or_init(|| Box::new(observer.take().unwrap()));
+    match observer {
+        Some(observer) => Err(observer),
+        None => Ok(()),
+    }
+}
+
+/// Run a query method's future, notifying the [`QueryObserver`] of its duration if one is set.
+pub(crate) async fn observe<R: Relation, F: Future>(
+    operation: &'static str,
+    future: F,
+) -> F::Output {
+    let Some(observer) = QUERY_OBSERVER.get() else {
+        return future.await;
+    };
+
+    let start = Instant::now();
+    let output = future.await;
+    observer.observe(operation, &R::get_qualified_name(), start.elapsed());
+
+    output
+}
@@ -0,0 +1,207 @@
+use utoipa::openapi::path::{
+    HttpMethod, OperationBuilder, ParameterBuilder, ParameterIn, PathItemBuilder,
+};
+use utoipa::openapi::{
+    ArrayBuilder, ContentBuilder, KnownFormat, ObjectBuilder, Paths, PathsBuilder, RefOr, Required,
+    ResponseBuilder, Schema, SchemaFormat, Type,
+};
+
+use crate::traits::id_parameter::{GenericIdParameter, IdParameter};
+use crate::traits::read::ReadRelation;
+use crate::traits::write::{WriteRecord, WriteRelation};
+
+/// The description of a single field of a record or query parameter type, used to generate its
+/// OpenAPI schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldSchema {
+    /// The name of the field, as it is serialized or deserialized.
+    pub name: &'static str,
+    /// The Postgres type corresponding to the field's type, in the same format as
+    /// [`Record::COLUMN_TYPES`](crate::traits::shared::Record::COLUMN_TYPES). If this is [`None`],
+    /// the field is described as accepting any value.
+    pub postgres_type: Option<&'static str>,
+    /// Whether the field must always be present, i.e. whether its type is not an [`Option`].
+    pub required: bool,
+}
+
+/// A trait that describes the fields of a type for OpenAPI generation.
+///
+/// This is implemented by the derive macros for record types, the create and update parameter
+/// types generated by `WriteRecord`, and ID parameter types, when the `openapi` feature is enabled.
+/// Field names are taken from the Rust fields, so `#[serde(rename = "...")]` is not reflected.
+pub trait OpenApiFields {
+    /// The fields of the type, in declaration order.
+    const FIELDS: &'static [FieldSchema];
+}
+
+impl<K> OpenApiFields for GenericIdParameter<K> {
+    const FIELDS: &'static [FieldSchema] = &[FieldSchema {
+        name: "id",
+        postgres_type: None,
+        required: true,
+    }];
+}
+
+/// Generate the OpenAPI path items for the standard CRUD endpoints of a relation, as routed by
+/// [`crud_router()`](crate::router::crud_router).
+///
+/// The paths are `{base_path}` and `{base_path}/one`, so the base path should be the path the
+/// router is nested under, such as `"/customers"`. The create, update, and ID parameters are
+/// described as query parameters, and responses containing records use the schema of
+/// [`Relation::Record`](crate::traits::shared::Relation::Record). Failed requests are described by
+/// the schema of [`ErrorResponse`](crate::response::ErrorResponse). The result can be merged into
+/// an [`utoipa::openapi::OpenApi`] to serve documentation such as Swagger UI.
+pub fn crud_openapi<R, I>(base_path: &str) -> Paths
+where
+    R: ReadRelation + WriteRelation,
+    R::Record: OpenApiFields,
+    <R::WriteRecord as WriteRecord>::CreateQueryParameters: OpenApiFields,
+    <R::WriteRecord as WriteRecord>::UpdateQueryParameters: OpenApiFields,
+    I: IdParameter + OpenApiFields,
+{
+    let relation_name = R::get_qualified_name();
+    let record_schema = object_schema(R::Record::FIELDS);
+    let records_schema = ArrayBuilder::new().items(record_schema.clone()).build();
+    let operation = |operation_id: &str, summary: &str| {
+        OperationBuilder::new()
+            .tag(&relation_name)
+            .operation_id(Some(format!("{operation_id}_{}", R::RELATION_NAME)))
+            .summary(Some(summary))
+            .response("default", error_response())
+    };
+
+    let root_path_item = PathItemBuilder::new()
+        .operation(
+            HttpMethod::Get,
+            operation("query_all", "Query all records")
+                .response("200", json_response("The records", records_schema)),
+        )
+        .operation(
+            HttpMethod::Post,
+            with_query_parameters::<<R::WriteRecord as WriteRecord>::CreateQueryParameters>(
+                operation("create_one", "Create a record"),
+            )
+            .response("201", ResponseBuilder::new().description("Record created")),
+        )
+        .operation(
+            HttpMethod::Patch,
+            with_query_parameters::<<R::WriteRecord as WriteRecord>::UpdateQueryParameters>(
+                operation("update_one", "Update a record"),
+            )
+            .response("200", ResponseBuilder::new().description("Record updated")),
+        )
+        .operation(
+            HttpMethod::Delete,
+            operation("delete_all", "Delete all records")
+                .response("200", ResponseBuilder::new().description("Records deleted")),
+        )
+        .build();
+
+    let one_path_item = PathItemBuilder::new()
+        .operation(
+            HttpMethod::Get,
+            with_query_parameters::<I>(operation("query_one", "Query a record by ID"))
+                .response("200", json_response("The record", record_schema)),
+        )
+        .operation(
+            HttpMethod::Delete,
+            with_query_parameters::<I>(operation("delete_one", "Delete a record by ID"))
+                .response("200", ResponseBuilder::new().description("Record deleted")),
+        )
+        .build();
+
+    let base_path = base_path.trim_end_matches('/');
+    PathsBuilder::new()
+        .path(
+            if base_path.is_empty() { "/" } else { base_path },
+            root_path_item,
+        )
+        .path(format!("{base_path}/one"), one_path_item)
+        .build()
+}
+
+/// Add a query parameter to an operation for each field of a parameter type.
+fn with_query_parameters<P: OpenApiFields>(operation: OperationBuilder) -> OperationBuilder {
+    P::FIELDS.iter().fold(operation, |operation, field| {
+        operation.parameter(
+            ParameterBuilder::new()
+                .name(field.name)
+                .parameter_in(ParameterIn::Query)
+                .required(if field.required {
+                    Required::True
+                } else {
+                    Required::False
+                })
+                .schema(Some(field_schema(field.postgres_type))),
+        )
+    })
+}
+
+/// Get the schema of an object with the given fields.
+fn object_schema(fields: &[FieldSchema]) -> RefOr<Schema> {
+    fields
+        .iter()
+        .fold(ObjectBuilder::new(), |object, field| {
+            let object = object.property(field.name, field_schema(field.postgres_type));
+            match field.required {
+                true => object.required(field.name),
+                false => object,
+            }
+        })
+        .into()
+}
+
+/// Get the schema of a value with the given Postgres type.
+///
+/// The type names are those produced by the derive macros for
+/// [`Record::COLUMN_TYPES`](crate::traits::shared::Record::COLUMN_TYPES).
+fn field_schema(postgres_type: Option<&str>) -> RefOr<Schema> {
+    let (schema_type, format) = match postgres_type {
+        Some("boolean") => (Type::Boolean, None),
+        Some("\"char\"" | "smallint" | "integer") => (Type::Integer, Some(KnownFormat::Int32)),
+        Some("bigint") => (Type::Integer, Some(KnownFormat::Int64)),
+        Some("real") => (Type::Number, Some(KnownFormat::Float)),
+        Some("double precision") => (Type::Number, Some(KnownFormat::Double)),
+        Some("date") => (Type::String, Some(KnownFormat::Date)),
+        Some("time without time zone") => (Type::String, Some(KnownFormat::Time)),
+        Some("timestamp without time zone" | "timestamp with time zone") => {
+            (Type::String, Some(KnownFormat::DateTime))
+        }
+        Some("uuid") => {
+            return ObjectBuilder::new()
+                .schema_type(Type::String)
+                .format(Some(SchemaFormat::Custom("uuid".to_string())))
+                .into();
+        }
+        Some("text" | "numeric") => (Type::String, None),
+        _ => return ObjectBuilder::new().into(),
+    };
+
+    ObjectBuilder::new()
+        .schema_type(schema_type)
+        .format(format.map(SchemaFormat::KnownFormat))
+        .into()
+}
+
+/// Get a response with a JSON body of the given schema.
+fn json_response(description: &str, schema: impl Into<RefOr<Schema>>) -> ResponseBuilder {
+    ResponseBuilder::new().description(description).content(
+        "application/json",
+        ContentBuilder::new().schema(Some(schema)).build(),
+    )
+}
+
+/// Get the response used for failed requests, matching
+/// [`ErrorResponse`](crate::response::ErrorResponse).
+fn error_response() -> ResponseBuilder {
+    let error_details_schema = ObjectBuilder::new()
+        .property("kind", ObjectBuilder::new().schema_type(Type::String))
+        .required("kind")
+        .property("message", ObjectBuilder::new().schema_type(Type::String))
+        .required("message");
+    let error_schema = ObjectBuilder::new()
+        .property("error", error_details_schema)
+        .required("error");
+
+    json_response("The request failed", error_schema)
+}
@@ -0,0 +1,101 @@
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error as CrudkitError, ErrorKind, Result as CrudkitResult};
+use crate::logging;
+#[allow(unused_imports)]
+use crate::traits::read::ReadRelation;
+
+/// The number of records in a page if no limit is given in [`PageParams`].
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+
+/// The query parameters used by [`ReadRelation::query_page_handler()`] to select a page of
+/// records.
+///
+/// Both parameters are optional. If `limit` is not given, it defaults to [`DEFAULT_PAGE_LIMIT`],
+/// and if `offset` is not given, it defaults to zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageParams {
+    /// The maximum number of records in the page.
+    pub limit: Option<i64>,
+    /// The number of records to skip before the start of the page.
+    pub offset: Option<i64>,
+}
+
+impl PageParams {
+    /// Get the limit and offset, using the defaults for any which are not given.
+    pub fn resolve(self) -> (i64, i64) {
+        (
+            self.limit.unwrap_or(DEFAULT_PAGE_LIMIT),
+            self.offset.unwrap_or(0),
+        )
+    }
+
+    /// Get the limit and offset, using the defaults for any which are not given, and check that
+    /// they are within the valid range.
+    ///
+    /// This is the single pagination policy shared by every paginated method and handler. If either
+    /// value is negative or the limit exceeds `max_limit`, which is usually
+    /// [`ReadRelation::MAX_PAGE_LIMIT`], an [`ErrorKind::InvalidQuery`] error with a
+    /// `400 Bad Request` status code is returned.
+    pub fn validate(self, max_limit: i64) -> CrudkitResult<(i64, i64)> {
+        let (limit, offset) = self.resolve();
+        if limit < 0 || offset < 0 || limit > max_limit {
+            logging::debug!(
+                "Rejecting page with limit {limit} and offset {offset}, the maximum limit is \
+                {max_limit}"
+            );
+            return Err(CrudkitError::new(
+                ErrorKind::InvalidQuery,
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        Ok((limit, offset))
+    }
+}
+
+/// The query parameters used by [`ReadRelation::query_after_handler()`] to select a page of
+/// records following a cursor.
+///
+/// Both parameters are optional. If `after` is not given, the first page is selected, and if
+/// `limit` is not given, it defaults to [`DEFAULT_PAGE_LIMIT`]. The key is a [`usize`] by default,
+/// but any other type can be used, the same as with
+/// [`GenericIdParameter`](crate::traits::id_parameter::GenericIdParameter).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CursorParams<K = usize> {
+    /// The primary key of the last record of the previous page.
+    pub after: Option<K>,
+    /// The maximum number of records in the page.
+    pub limit: Option<i64>,
+}
+
+/// A page of records selected using a cursor, as returned by [`ReadRelation::query_after()`].
+///
+/// When serialized, the records of the relation are flattened alongside the cursor, in the form
+/// `{ "records": [...], "next_cursor": ... }`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CursorPage<R, K> {
+    /// The records in the page.
+    #[serde(flatten)]
+    pub relation: R,
+    /// The primary key of the last record in the page, which is passed as the cursor to select the
+    /// next page. This is [`None`] if the page is empty.
+    pub next_cursor: Option<K>,
+}
+
+/// A page of records along with the total number of records matching the same filters, as returned
+/// by [`ReadRelation::query_search()`].
+///
+/// When serialized, this has the form `{ "items": [...], "total": N, "limit": N, "offset": N }`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchPage<R> {
+    /// The records in the page.
+    pub items: Vec<R>,
+    /// The number of records matching the filters, across all pages.
+    pub total: i64,
+    /// The maximum number of records in the page.
+    pub limit: i64,
+    /// The number of records skipped before the start of the page.
+    pub offset: i64,
+}
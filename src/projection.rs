@@ -0,0 +1,83 @@
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, QueryBuilder};
+
+use crate::error::{Error as CrudkitError, ErrorKind, Result as CrudkitResult};
+use crate::logging;
+#[allow(unused_imports)]
+use crate::traits::read::ReadRelation;
+use crate::traits::shared::{Record, Relation};
+
+/// The maximum number of columns passed to a single `jsonb_build_object()` call, since Postgres
+/// functions can take at most 100 arguments and each column takes two.
+const MAX_COLUMNS_PER_OBJECT: usize = 50;
+
+/// The query parameters used by [`ReadRelation::query_all_columns_handler()`] to select columns.
+///
+/// The `fields` parameter is a comma-separated list of column names, such as `?fields=id,name`. If
+/// the parameter is not given, every column in [`Record::COLUMN_NAMES`] is selected.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldsParams {
+    /// The columns to select.
+    pub fields: Option<String>,
+}
+
+impl FieldsParams {
+    /// Parse the `fields` parameter into a list of column names.
+    ///
+    /// Column names are not validated until the query is built.
+    pub fn parse<R: Record>(&self) -> Vec<&str> {
+        match self.fields.as_deref() {
+            Some(fields) => fields.split(',').map(str::trim).collect(),
+            None => R::COLUMN_NAMES.to_vec(),
+        }
+    }
+}
+
+/// Get a query which selects the given columns of every record as a JSON object, serialized as
+/// text.
+///
+/// Records are sorted by the relation's default order. If no columns are given or any column is
+/// not present in [`Record::COLUMN_NAMES`], an [`ErrorKind::InvalidQuery`] error is returned.
+pub(crate) fn build_projection_query<R: Record>(
+    columns: &[&str],
+) -> CrudkitResult<QueryBuilder<'static, Postgres>> {
+    if columns.is_empty() {
+        logging::debug!("Rejecting projection without any columns");
+        return Err(invalid_projection_error());
+    }
+
+    if let Some(column) = columns.iter().find(|c| !R::COLUMN_NAMES.contains(c)) {
+        logging::debug!("Rejecting projection of unknown column {column}");
+        return Err(invalid_projection_error());
+    }
+
+    // * Objects are concatenated so that any number of columns can be selected
+    let mut query_builder = QueryBuilder::new("SELECT (");
+    for (index, chunk) in columns.chunks(MAX_COLUMNS_PER_OBJECT).enumerate() {
+        if index > 0 {
+            query_builder.push(" || ");
+        }
+
+        query_builder.push("jsonb_build_object(");
+        let mut arguments = query_builder.separated(", ");
+        for column in chunk {
+            arguments.push_bind(column.to_string());
+            arguments.push(R::Relation::render_identifier(column));
+        }
+        query_builder.push(")");
+    }
+
+    query_builder.push(format!(
+        ")::text FROM {}{} ORDER BY {}",
+        R::Relation::get_qualified_identifier(),
+        R::Relation::get_soft_delete_clause(" WHERE "),
+        R::Relation::get_order_by_clause(),
+    ));
+
+    Ok(query_builder)
+}
+
+fn invalid_projection_error() -> CrudkitError {
+    CrudkitError::new(ErrorKind::InvalidQuery, StatusCode::BAD_REQUEST)
+}
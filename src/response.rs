@@ -0,0 +1,106 @@
+use axum::extract::Json;
+use axum::response::{IntoResponse, Response};
+use http::StatusCode;
+use serde::Serialize;
+
+use crate::database::DatabaseState;
+use crate::error::{Error as CrudkitError, ErrorKind};
+
+/// A wrapper around the result of a Crudkit operation which can be returned from an Axum handler.
+///
+/// On success, the value is serialized as a JSON response body with a `200 OK` status code. On
+/// failure, the response is an [`ErrorResponse`] with
+/// [`Error::status_code`](CrudkitError::status_code). This is the default mapping used by the
+/// handlers provided by
+/// [`ReadRelation`](crate::traits::read::ReadRelation), so custom handlers built around the
+/// standard methods can simply return
+/// `CrudResponse(Self::query_all(database).await)`. To respect the error mapping of the server
+/// state, see [`CrudResponse::into_response_for()`].
+pub struct CrudResponse<T>(pub Result<T, CrudkitError>);
+
+impl<T: Serialize> CrudResponse<T> {
+    /// Convert the result into a response, using [`DatabaseState::error_response()`] to build the
+    /// response on failure.
+    ///
+    /// This is what the provided handlers use, so custom handlers can use it to respond with the
+    /// same errors.
+    pub fn into_response_for<S: DatabaseState>(self, state: &S, relation_name: &str) -> Response {
+        match self.0 {
+            Ok(value) => Json(value).into_response(),
+            Err(e) => state.error_response(relation_name, e),
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for CrudResponse<T> {
+    fn into_response(self) -> Response {
+        match self.0 {
+            Ok(value) => Json(value).into_response(),
+            Err(e) => e.into_response(),
+        }
+    }
+}
+
+impl<T> From<Result<T, CrudkitError>> for CrudResponse<T> {
+    fn from(result: Result<T, CrudkitError>) -> Self {
+        Self(result)
+    }
+}
+
+/// The JSON body returned by handlers which respond with a number of records, in the form
+/// `{ "count": N }`.
+///
+/// This is used by [`ReadRelation::count_handler()`](crate::traits::read::ReadRelation::count_handler)
+/// and [`WriteRelation::delete_many_handler()`](crate::traits::write::WriteRelation::delete_many_handler).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CountResponse {
+    /// The number of records counted or affected.
+    pub count: i64,
+}
+
+/// The JSON body of the response for an [`Error`](CrudkitError), in the form
+/// `{ "error": { "kind": "InvalidQuery", "message": "invalid query" } }`.
+///
+/// The message describes the [`ErrorKind`], followed by the [`Error::message`](CrudkitError::message)
+/// if there is one. It never includes the query or the error message from the database, which can
+/// contain the values of query parameters. If the status code is `204 No Content` or
+/// `304 Not Modified`, which cannot have a body, the response is sent without one.
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    /// The details of the error.
+    pub error: ErrorDetails,
+}
+
+/// The details of an error in an [`ErrorResponse`].
+#[derive(Debug, Serialize)]
+pub struct ErrorDetails {
+    /// The general category of the error.
+    pub kind: ErrorKind,
+    /// A human-readable description of the error category and, if there is one, the problem.
+    pub message: String,
+}
+
+impl IntoResponse for CrudkitError {
+    fn into_response(self) -> Response {
+        // * These status codes must not have a body, which can happen if the status code is
+        // * overridden by `DatabaseState::error_status_code()`
+        if matches!(
+            self.status_code,
+            StatusCode::NO_CONTENT | StatusCode::NOT_MODIFIED
+        ) {
+            return self.status_code.into_response();
+        }
+
+        let body = ErrorResponse {
+            error: ErrorDetails {
+                message: match self.message {
+                    Some(message) => format!("{}: {message}", self.kind),
+                    None => self.kind.to_string(),
+                },
+                kind: self.kind,
+            },
+        };
+
+        (self.status_code, Json(body)).into_response()
+    }
+}
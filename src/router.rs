@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Router;
+use serde::de::DeserializeOwned;
+
+use crate::database::DatabaseState;
+use crate::traits::id_parameter::IdParameter;
+use crate::traits::read::ReadRelation;
+use crate::traits::write::{WriteRecord, WriteRelation};
+
+/// Create a [`Router`] with the standard CRUD handlers of a relation at conventional paths.
+///
+/// The routes are as follows, where `I` is the [`IdParameter`] used to select a single record:
+/// - `GET /`: [`ReadRelation::query_all_handler()`]
+/// - `GET /one`: [`ReadRelation::query_one_handler()`]
+/// - `POST /`: [`WriteRelation::create_one_handler()`]
+/// - `PATCH /`: [`WriteRelation::update_one_handler()`]
+/// - `DELETE /one`: [`WriteRelation::delete_one_handler()`]
+/// - `DELETE /`: [`WriteRelation::delete_all_handler()`]
+///
+/// The router can be nested under a path for the relation, such as
+/// `Router::new().nest("/customers", crud_router::<CustomersTable, GenericIdParameter, _>())`,
+/// and then given its state using [`Router::with_state()`]. Other routes can be added to it as
+/// usual.
+pub fn crud_router<R, I, S>() -> Router<Arc<S>>
+where
+    R: ReadRelation + WriteRelation + 'static,
+    R::Key: TryFrom<I::Id>,
+    <R::WriteRecord as WriteRecord>::CreateQueryParameters: DeserializeOwned + 'static,
+    <R::WriteRecord as WriteRecord>::UpdateQueryParameters: DeserializeOwned + 'static,
+    I: IdParameter + DeserializeOwned + 'static,
+    S: DatabaseState + 'static,
+{
+    Router::new()
+        .route(
+            "/",
+            get(R::query_all_handler::<S>)
+                .post(R::create_one_handler::<S>)
+                .patch(R::update_one_handler::<S>)
+                .delete(R::delete_all_handler::<S>),
+        )
+        .route(
+            "/one",
+            get(R::query_one_handler::<I, S>).delete(R::delete_one_handler::<I, S>),
+        )
+}
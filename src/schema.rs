@@ -0,0 +1,115 @@
+use serde::Serialize;
+
+#[allow(unused_imports)]
+use crate::database::PgDatabase;
+use crate::traits::shared::{Record, Relation};
+
+/// The differences between a [`Record`] type and the live definition of its relation in the
+/// database, as produced by [`PgDatabase::diff_schema()`].
+///
+/// This is intended for catching schema drift in CI or at startup, rather than finding out about
+/// it from a decoding failure at runtime.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SchemaDiff {
+    /// Columns which are present on the record but do not exist in the database.
+    pub missing_columns: Vec<String>,
+    /// Columns which exist in the database but are not present on the record.
+    pub extra_columns: Vec<String>,
+    /// Columns which exist in both places but whose definitions do not match.
+    pub mismatched_columns: Vec<ColumnMismatch>,
+}
+
+/// A single column whose definition on a [`Record`] does not match the database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum ColumnMismatch {
+    /// The Postgres type of the column is not the one expected for the record's field type.
+    Type {
+        column_name: String,
+        expected: String,
+        found: String,
+    },
+    /// The column is nullable in the database, but the record's field is not an [`Option`], so
+    /// reading a `NULL` value would fail to decode.
+    Nullability { column_name: String },
+}
+
+/// A trait that allows a [`Record`] type to render the DDL which creates its relation.
+///
+/// This is intended for bootstrapping a schema from Rust types, such as in tests, rather than as a
+/// replacement for migrations. In the derive macro, the Postgres type of each column is inferred
+/// from its field type, [`Option`] fields are nullable, and `#[auto_primary_key]` fields become
+/// `serial` (or `uuid` with a `gen_random_uuid()` default) primary keys. Generated and
+/// multi-column fields are not supported, since their definitions cannot be inferred.
+pub trait GenerateTable: Record {
+    /// Get the definition of each column, such as `"name text NOT NULL"`, followed by any table
+    /// constraints.
+    fn column_definitions() -> Vec<String>;
+
+    /// Get a `CREATE TABLE IF NOT EXISTS` statement for the relation.
+    fn create_table_sql() -> String {
+        format!(
+            "CREATE TABLE IF NOT EXISTS {} ({})",
+            Self::Relation::get_qualified_identifier(),
+            Self::column_definitions().join(", ")
+        )
+    }
+}
+
+/// A column definition as reported by `information_schema.columns`.
+#[derive(sqlx::FromRow)]
+pub(crate) struct LiveColumn {
+    pub(crate) column_name: String,
+    pub(crate) data_type: String,
+    pub(crate) is_nullable: String,
+}
+
+impl SchemaDiff {
+    /// Check whether the record and the database definitions match exactly.
+    pub fn is_empty(&self) -> bool {
+        self.missing_columns.is_empty()
+            && self.extra_columns.is_empty()
+            && self.mismatched_columns.is_empty()
+    }
+
+    /// Compare the metadata of a [`Record`] type against the live columns of its relation.
+    pub(crate) fn compare<R: Record>(live_columns: &[LiveColumn]) -> Self {
+        let mut diff = Self::default();
+
+        for (index, column_name) in R::COLUMN_NAMES.iter().enumerate() {
+            let Some(live_column) = live_columns.iter().find(|c| c.column_name == *column_name)
+            else {
+                diff.missing_columns.push(column_name.to_string());
+                continue;
+            };
+
+            if let Some(Some(expected_type)) = R::COLUMN_TYPES.get(index) {
+                if live_column.data_type != *expected_type {
+                    diff.mismatched_columns.push(ColumnMismatch::Type {
+                        column_name: column_name.to_string(),
+                        expected: expected_type.to_string(),
+                        found: live_column.data_type.clone(),
+                    });
+                }
+            }
+
+            if live_column.is_nullable == "YES" && !R::NULLABLE_COLUMN_NAMES.contains(column_name) {
+                diff.mismatched_columns.push(ColumnMismatch::Nullability {
+                    column_name: column_name.to_string(),
+                });
+            }
+        }
+
+        diff.extra_columns = live_columns
+            .iter()
+            .filter(|c| !R::COLUMN_NAMES.contains(&c.column_name.as_str()))
+            .map(|c| c.column_name.clone())
+            .collect();
+
+        diff
+    }
+}
+
+/// The query which fetches the live column definitions of a relation, given its schema and name.
+pub(crate) const LIVE_COLUMNS_QUERY: &str = "SELECT column_name::text, data_type::text, \
+    is_nullable::text FROM information_schema.columns WHERE table_schema = $1 AND table_name = $2 \
+    ORDER BY ordinal_position";
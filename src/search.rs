@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error as CrudkitError, ErrorKind, Result as CrudkitResult};
+use crate::logging;
+use crate::pagination::PageParams;
+use crate::sort::SortParams;
+#[allow(unused_imports)]
+use crate::traits::read::ReadRelation;
+use crate::traits::shared::{Record, Relation};
+
+/// The query parameters used by [`ReadRelation::search_handler()`] to search records.
+///
+/// The `q` parameter is the search term, and the optional `columns` parameter is a comma-separated
+/// list of column names to search, such as `?q=jane&columns=name,email_address`. If `columns` is
+/// not given, every column whose type is known to be `text` is searched.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchParams {
+    /// The term to search for.
+    pub q: String,
+    /// The columns to search.
+    pub columns: Option<String>,
+}
+
+impl SearchParams {
+    /// Parse the `columns` parameter into a list of column names.
+    ///
+    /// If the parameter is not given, the columns with a `text` type in [`Record::COLUMN_TYPES`]
+    /// are returned. Column names are not validated until the query is built.
+    pub fn parse_columns<R: Record>(&self) -> Vec<&str> {
+        match self.columns.as_deref() {
+            Some(columns) => columns.split(',').map(str::trim).collect(),
+            None => R::COLUMN_NAMES
+                .iter()
+                .zip(R::COLUMN_TYPES)
+                .filter(|(_, column_type)| **column_type == Some("text"))
+                .map(|(column_name, _)| *column_name)
+                .collect(),
+        }
+    }
+}
+
+/// The JSON body used by [`ReadRelation::query_search_handler()`] to filter, sort, and paginate
+/// records in a single request.
+///
+/// Every field is optional, and the body has the form
+/// `{ "filters": { "name": "like.J*" }, "sort": "name.asc", "limit": 20, "offset": 40 }`. Each
+/// filter uses the same `<operator>.<value>` syntax as
+/// [`ReadRelation::query_where_handler()`], the sort uses the same syntax as [`SortParams`], and
+/// the limit and offset have the same defaults as in [`PageParams`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchRequest {
+    /// The filters which every record must match, keyed by column name.
+    #[serde(default)]
+    pub filters: HashMap<String, String>,
+    /// The columns to sort by.
+    #[serde(flatten)]
+    pub sort: SortParams,
+    /// The page of records to select.
+    #[serde(flatten)]
+    pub page: PageParams,
+}
+
+/// Get the condition which matches records where any of the given columns contains the search term
+/// bound to `$1`, ignoring case.
+///
+/// If no columns are given or any column is not present in [`Record::COLUMN_NAMES`], an
+/// [`ErrorKind::InvalidQuery`] error is returned.
+pub(crate) fn build_search_condition<R: Record>(columns: &[&str]) -> CrudkitResult<String> {
+    validate_search_columns::<R>(columns)?;
+
+    let conditions: Vec<String> = columns
+        .iter()
+        .map(|column| {
+            format!(
+                "CAST({} AS text) ILIKE '%' || $1 || '%'",
+                R::Relation::render_identifier(column)
+            )
+        })
+        .collect();
+
+    Ok(format!("({})", conditions.join(" OR ")))
+}
+
+/// Get the condition which matches records where the given columns, taken together, match the
+/// full-text search query bound to `$1`.
+///
+/// The query is parsed with `websearch_to_tsquery()`, so it accepts any input, including quoted
+/// phrases, `or`, and `-` to exclude words. If no columns are given or any column is not present in
+/// [`Record::COLUMN_NAMES`], an [`ErrorKind::InvalidQuery`] error is returned.
+pub(crate) fn build_full_text_search_condition<R: Record>(
+    columns: &[&str],
+    config: &str,
+) -> CrudkitResult<String> {
+    validate_search_columns::<R>(columns)?;
+
+    let documents: Vec<String> = columns
+        .iter()
+        .map(|column| format!("CAST({} AS text)", R::Relation::render_identifier(column)))
+        .collect();
+
+    Ok(format!(
+        "to_tsvector('{config}', concat_ws(' ', {})) @@ websearch_to_tsquery('{config}', $1)",
+        documents.join(", ")
+    ))
+}
+
+/// Escape the wildcard characters in a search term so that it is matched literally by `ILIKE`.
+pub(crate) fn escape_like_pattern(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn validate_search_columns<R: Record>(columns: &[&str]) -> CrudkitResult<()> {
+    if columns.is_empty() {
+        logging::debug!("Rejecting search without any columns");
+        return Err(invalid_search_error());
+    }
+
+    if let Some(column) = columns.iter().find(|c| !R::COLUMN_NAMES.contains(c)) {
+        logging::debug!("Rejecting search on unknown column {column}");
+        return Err(invalid_search_error());
+    }
+
+    Ok(())
+}
+
+fn invalid_search_error() -> CrudkitError {
+    CrudkitError::new(ErrorKind::InvalidQuery, StatusCode::BAD_REQUEST)
+}
@@ -0,0 +1,98 @@
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error as CrudkitError, ErrorKind, Result as CrudkitResult};
+use crate::logging;
+#[allow(unused_imports)]
+use crate::traits::read::ReadRelation;
+use crate::traits::shared::{Record, Relation};
+
+/// The direction in which a column is sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    /// `ASC`
+    Ascending,
+    /// `DESC`
+    Descending,
+}
+
+impl SortDirection {
+    /// Get the keyword used for the direction in an `ORDER BY` clause.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ascending => "ASC",
+            Self::Descending => "DESC",
+        }
+    }
+}
+
+/// The query parameters used by [`ReadRelation::query_all_sorted_handler()`] to sort records.
+///
+/// The `sort` parameter is a comma-separated list of columns, each followed by `.asc` or `.desc`,
+/// such as `?sort=name.asc,created_at.desc`. A column without a direction is sorted in ascending
+/// order. If the parameter is not given, records are sorted by the relation's default order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortParams {
+    /// The columns to sort by, in order of precedence.
+    pub sort: Option<String>,
+}
+
+impl SortParams {
+    /// Parse the `sort` parameter into a list of columns and directions.
+    ///
+    /// If a direction other than `asc` or `desc` is given, an [`ErrorKind::InvalidQuery`] error is
+    /// returned. Column names are not validated until the query is built.
+    pub fn parse(&self) -> CrudkitResult<Vec<(&str, SortDirection)>> {
+        let Some(sort) = self.sort.as_deref() else {
+            return Ok(Vec::new());
+        };
+
+        sort.split(',')
+            .map(|column| match column.rsplit_once('.') {
+                None => Ok((column, SortDirection::Ascending)),
+                Some((column, "asc")) => Ok((column, SortDirection::Ascending)),
+                Some((column, "desc")) => Ok((column, SortDirection::Descending)),
+                Some((column, direction)) => {
+                    logging::debug!(
+                        "Rejecting unknown sort direction {direction} on column {column}"
+                    );
+                    Err(invalid_sort_error())
+                }
+            })
+            .collect()
+    }
+}
+
+/// Get the expression used in the `ORDER BY` clause to sort by the given columns.
+///
+/// If no columns are given, the relation's default order is used. If any column is not present in
+/// [`Record::COLUMN_NAMES`], an [`ErrorKind::InvalidQuery`] error is returned.
+pub(crate) fn build_order_by_clause<R: Record>(
+    order_by: &[(&str, SortDirection)],
+) -> CrudkitResult<String> {
+    if order_by.is_empty() {
+        return Ok(R::Relation::get_order_by_clause());
+    }
+
+    let columns: Vec<String> = order_by
+        .iter()
+        .map(|(column, direction)| {
+            if !R::COLUMN_NAMES.contains(column) {
+                logging::debug!("Rejecting sort on unknown column {column}");
+                return Err(invalid_sort_error());
+            }
+
+            Ok(format!(
+                "{} {}",
+                R::Relation::render_identifier(column),
+                direction.as_str()
+            ))
+        })
+        .collect::<CrudkitResult<_>>()?;
+
+    Ok(columns.join(", "))
+}
+
+fn invalid_sort_error() -> CrudkitError {
+    CrudkitError::new(ErrorKind::InvalidQuery, StatusCode::BAD_REQUEST)
+}
@@ -0,0 +1,71 @@
+use std::fmt::Display;
+
+use http::StatusCode;
+use sqlx::query_builder::Separated;
+use sqlx::{Encode, Postgres, Type};
+
+#[allow(unused_imports)]
+use super::read::ReadRelation;
+use super::shared::Relation;
+use crate::error::{Error as CrudkitError, ErrorKind, Result as CrudkitResult};
+use crate::logging;
+
+/// A trait that allows a tuple of values to be used as a composite primary key.
+///
+/// This is implemented for tuples of two to four values which can each be bound as a query
+/// parameter, and it is used by methods such as [`ReadRelation::query_one_composite()`] and
+/// [`ReadRelation::query_many_composite()`] to match rows against a relation's primary key columns,
+/// such as in junction tables.
+pub trait CompositeKey: Send + Sync + 'static {
+    /// The number of columns in the key.
+    const COLUMN_COUNT: usize;
+
+    /// Push each value of the key as a separate parameter binding.
+    ///
+    /// This should only be used within auto-implementations.
+    fn push_bindings<Sep: Display>(self, builder: &mut Separated<'_, '_, Postgres, Sep>);
+}
+
+macro_rules! impl_composite_key {
+    ( $count:literal; $( $type:ident => $index:tt ),+ ) => {
+        impl<$( $type ),+> CompositeKey for ( $( $type, )+ )
+        where
+            $( $type: for<'q> Encode<'q, Postgres> + Type<Postgres> + Send + Sync + 'static, )+
+        {
+            const COLUMN_COUNT: usize = $count;
+
+            fn push_bindings<Sep: Display>(
+                self,
+                builder: &mut Separated<'_, '_, Postgres, Sep>,
+            ) {
+                $( builder.push_bind(self.$index); )+
+            }
+        }
+    };
+}
+
+impl_composite_key!(2; A => 0, B => 1);
+impl_composite_key!(3; A => 0, B => 1, C => 2);
+impl_composite_key!(4; A => 0, B => 1, C => 2, D => 3);
+
+/// Check that the number of primary key columns of a relation matches the number of values in a key.
+///
+/// Keys with a single value are represented by [`IdParameter`](super::id_parameter::IdParameter),
+/// so passing a `key_column_count` of one checks that the relation does not have a composite
+/// primary key.
+pub(crate) fn validate_key_arity<R: Relation>(key_column_count: usize) -> CrudkitResult<()> {
+    let primary_key_column_count = R::get_primary_key_columns().len();
+    if primary_key_column_count != key_column_count {
+        logging::debug!(
+            "Rejecting key with {key_column_count} columns for relation {}, which has \
+            {primary_key_column_count} primary key columns",
+            R::get_qualified_name()
+        );
+        return Err(CrudkitError::new(
+            ErrorKind::InvalidQuery,
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
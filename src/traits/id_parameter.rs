@@ -1,30 +1,128 @@
+use std::str::FromStr;
+
+use http::StatusCode;
 use serde::{Deserialize, Serialize};
 
+use super::composite_key::CompositeKey;
+#[allow(unused_imports)]
+use super::read::ReadRelation;
+use super::shared::Relation;
+use crate::error::{Error as CrudkitError, ErrorKind, Result as CrudkitResult};
+use crate::logging;
+
 /// A trait that allows queries including an ID field to use unique nomenclature if desired.
 ///
 /// The format for the URL will look like
 /// `https://fixwise.io/some/record/endpoint?id_parameter_name=123456`. If the ID parameter is just
 /// named `id` and there are no other parameters needed, simply use [`GenericIdParameter`].
+///
+/// The ID can be of any type which converts to the [`Relation::Key`] of the relation being queried,
+/// such as a [`usize`] for integer keys, a [`String`] for text keys, or a `uuid::Uuid` for UUID
+/// keys. When deriving this trait, [`IdParameter::Id`] is the type of the first field of the
+/// struct, which must implement [`Clone`].
 pub trait IdParameter: Send + Sync {
-    /// Create the parameter with an inner [`usize`].
-    fn new(value: usize) -> Self;
-    /// Get the inner [`usize`] ID parameter.
-    fn id(&self) -> usize;
+    /// The type of the inner ID.
+    type Id: Send + 'static;
+
+    /// Create the parameter with an inner ID.
+    fn new(value: Self::Id) -> Self;
+    /// Get the inner ID parameter.
+    fn id(&self) -> Self::Id;
+}
+
+/// A trait that allows query parameters with multiple fields to select a record by a composite
+/// primary key.
+///
+/// This is the composite-key counterpart of [`IdParameter`], used by handlers such as
+/// [`ReadRelation::query_one_composite_handler()`]. For example, a junction table with the primary
+/// key `(customer_id, product_id)` can be queried with a parameter struct containing both fields,
+/// resulting in a URL like `https://fixwise.io/some/record/endpoint?customer_id=1&product_id=2`.
+/// When deriving this trait, [`CompositeIdParameter::Key`] is a tuple of the types of the fields
+/// of the struct, in declaration order, which must each implement [`Clone`].
+pub trait CompositeIdParameter: Send + Sync {
+    /// The type of the composite key, which is a tuple with a value for each key column.
+    type Key: CompositeKey;
+
+    /// Create the parameter from a composite key.
+    fn new(key: Self::Key) -> Self;
+    /// Get the composite key.
+    fn key(&self) -> Self::Key;
 }
 
 /// A simple query parameter type to be used in handler functions if the only necessary parameter is
-/// a numerical ID.
+/// an ID.
+///
+/// The ID is a [`usize`] by default, but any other type can be used, such as a `uuid::Uuid` for
+/// relations with UUID primary keys.
 #[derive(Clone, Serialize, Deserialize)]
-pub struct GenericIdParameter {
-    id: usize,
+pub struct GenericIdParameter<K = usize> {
+    id: K,
 }
 
-impl IdParameter for GenericIdParameter {
-    fn new(value: usize) -> Self {
+impl<K: Clone + Send + Sync + 'static> IdParameter for GenericIdParameter<K> {
+    type Id = K;
+
+    fn new(value: K) -> Self {
         Self { id: value }
     }
 
-    fn id(&self) -> usize {
-        self.id
+    fn id(&self) -> K {
+        self.id.clone()
     }
 }
+
+/// The query parameters used by [`ReadRelation::query_many_handler()`] to select records by a list of
+/// IDs.
+///
+/// The `ids` parameter is a comma-separated list, such as `?ids=1,2,3`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdListParameter {
+    /// The IDs of the records to select.
+    pub ids: String,
+}
+
+impl IdListParameter {
+    /// Parse the `ids` parameter into a list of ID parameters.
+    ///
+    /// If any ID cannot be parsed, an [`ErrorKind::InvalidQuery`] error is returned. An empty
+    /// parameter results in an empty list.
+    pub fn parse<I: IdParameter>(&self) -> CrudkitResult<Vec<I>>
+    where
+        I::Id: FromStr,
+    {
+        if self.ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.ids
+            .split(',')
+            .map(|id| match id.trim().parse() {
+                Ok(id) => Ok(I::new(id)),
+                Err(_) => {
+                    logging::debug!("Rejecting unparseable ID {id} in ID list");
+                    Err(CrudkitError::new(
+                        ErrorKind::InvalidQuery,
+                        StatusCode::BAD_REQUEST,
+                    ))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Convert an ID parameter to the key type of a relation so that it can be bound to a query.
+///
+/// If the ID does not fit in the key type, such as an ID above [`i32::MAX`] for an [`i32`] key, an
+/// [`ErrorKind::InvalidQuery`] error is returned rather than truncating the ID.
+pub(crate) fn convert_id<R: Relation, K>(id: K) -> CrudkitResult<R::Key>
+where
+    R::Key: TryFrom<K>,
+{
+    R::Key::try_from(id).map_err(|_| {
+        logging::debug!(
+            "Rejecting ID which is out of range for the primary key of relation {}",
+            R::get_qualified_name()
+        );
+        CrudkitError::new(ErrorKind::InvalidQuery, StatusCode::BAD_REQUEST)
+    })
+}
@@ -1,3 +1,4 @@
+pub mod composite_key;
 pub mod id_parameter;
 pub mod read;
 pub mod shared;
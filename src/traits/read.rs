@@ -1,16 +1,41 @@
+use std::collections::HashMap;
 use std::future::Future;
+use std::str::FromStr;
 use std::sync::Arc;
 
+use axum::body::Body;
 use axum::extract::{Json, Query, State};
 use axum::response::{IntoResponse, Response};
-use http::StatusCode;
+use axum::BoxError;
+use futures_util::stream::{self, Stream, StreamExt, TryStreamExt};
+use http::{header, StatusCode};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::postgres::PgHasArrayType;
+use sqlx::query_builder::QueryBuilder;
+use sqlx::{Error as SqlxError, Postgres, Transaction};
 
-use super::id_parameter::IdParameter;
-use super::shared::{Record, Relation};
+use super::composite_key::{validate_key_arity, CompositeKey};
+use super::id_parameter::{convert_id, CompositeIdParameter, IdListParameter, IdParameter};
+use super::shared::{IdentifiableRecord, Record, Relation};
 #[allow(unused_imports)]
 use super::write::{WriteRecord, WriteRelation};
-use crate::database::{DatabaseState, PgDatabase};
+use crate::database::{DatabaseState, PgAcquire, PgDatabase, SQL_PARAMETER_BIND_LIMIT};
+#[allow(unused_imports)]
+use crate::error::ErrorKind;
 use crate::error::{Error as CrudkitError, Result as CrudkitResult};
+use crate::filter::{
+    parse_timestamp_range_parameters, parse_where_parameters, push_visible_where_clause, Filter,
+};
+use crate::logging;
+use crate::pagination::{CursorPage, CursorParams, PageParams, SearchPage};
+use crate::projection::{build_projection_query, FieldsParams};
+use crate::response::{CountResponse, CrudResponse};
+use crate::search::{
+    build_full_text_search_condition, build_search_condition, escape_like_pattern, SearchParams,
+    SearchRequest,
+};
+use crate::sort::{build_order_by_clause, SortDirection, SortParams};
 
 /// A trait that enables readable tables and views to have their records queried from the database.
 ///
@@ -31,115 +56,1365 @@ pub trait ReadRelation: Relation {
     /// issues, but the type it refers to must be the same.
     type ReadRecord: ReadRecord<ReadRelation = Self>;
 
+    /// The maximum number of records which can be requested in a single page by
+    /// [`ReadRelation::query_page()`].
+    const MAX_PAGE_LIMIT: i64 = 1000;
+    /// The number of records fetched from the database at a time by
+    /// [`ReadRelation::query_all_stream()`].
+    const STREAM_BATCH_SIZE: usize = 1000;
+    /// The text search configuration used by [`ReadRelation::search_full_text()`], such as
+    /// `"english"` to match different forms of the same word.
+    const TEXT_SEARCH_CONFIG: &str = "simple";
+
     /// Query (select) a single record from the database using an identifying key.
     ///
-    /// If the record exists in the database, it is returned. Otherwise, [`None`] is returned.
+    /// If the record exists in the database, it is returned. Otherwise, an
+    /// [`ErrorKind::UnexpectedQueryResult`] error with a `404 Not Found` status is returned. To
+    /// handle a missing record without matching on the error, use
+    /// [`ReadRelation::query_one_opt()`] instead.
+    ///
+    /// If the relation has a composite primary key, an [`ErrorKind::InvalidQuery`] error is
+    /// returned. Use [`ReadRelation::query_one_composite()`] instead.
     ///
     /// This is the standard version of this method and should not be used as an Axum route handler.
     /// For the handler method, use [`ReadRelation::query_one_handler()`].
-    fn query_one<I: IdParameter>(
-        database: &PgDatabase,
+    fn query_one<'c, I: IdParameter>(
+        database: impl PgAcquire<'c>,
         id: I,
-    ) -> impl Future<Output = CrudkitResult<Self::ReadRecord>> + Send {
+    ) -> impl Future<Output = CrudkitResult<Self::ReadRecord>> + Send
+    where
+        Self::Key: TryFrom<I::Id>,
+    {
+        logging::instrument::<Self, _>("query_one", async move {
+            validate_key_arity::<Self>(1)?;
+            let id = convert_id::<Self, _>(id.id())?;
+
+            query_one_by_key::<Self>(database, id)
+                .await?
+                .ok_or_else(|| CrudkitError::from(SqlxError::RowNotFound))
+        })
+    }
+
+    /// Query (select) a single record from the database using an identifying key, or [`None`] if
+    /// it does not exist.
+    ///
+    /// This is the same as [`ReadRelation::query_one()`], except that a missing record is not
+    /// treated as an error, so only actual failures are returned as errors.
+    fn query_one_opt<'c, I: IdParameter>(
+        database: impl PgAcquire<'c>,
+        id: I,
+    ) -> impl Future<Output = CrudkitResult<Option<Self::ReadRecord>>> + Send
+    where
+        Self::Key: TryFrom<I::Id>,
+    {
+        logging::instrument::<Self, _>("query_one_opt", async move {
+            validate_key_arity::<Self>(1)?;
+            let id = convert_id::<Self, _>(id.id())?;
+
+            query_one_by_key::<Self>(database, id).await
+        })
+    }
+
+    /// Query (select) a single record from the database using an identifying key.
+    ///
+    /// If the record exists in the database, it is returned. Otherwise, [`None`] is returned.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`ReadRelation::query_one()`].
+    // TODO: Check how this interacts with junction tables
+    fn query_one_handler<I: IdParameter, S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(id_param): Query<I>,
+    ) -> impl Future<Output = Response> + Send
+    where
+        Self::Key: TryFrom<I::Id>,
+    {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by single-SELECT endpoint for relation {relation_name}, calling query
+            dispatcher"
+        );
+
         async move {
+            CrudResponse(Self::query_one(state.get_database(), id_param).await)
+                .into_response_for(&**state, &relation_name)
+        }
+    }
+
+    /// Check whether a record with the given identifying key exists in the database.
+    ///
+    /// This is cheaper than [`ReadRelation::query_one()`] when the record itself is not needed, as
+    /// no columns are fetched.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`ReadRelation::exists_handler()`].
+    fn exists<'c, I: IdParameter>(
+        database: impl PgAcquire<'c>,
+        id: I,
+    ) -> impl Future<Output = CrudkitResult<bool>> + Send
+    where
+        Self::Key: TryFrom<I::Id>,
+    {
+        logging::instrument::<Self, _>("exists", async move {
+            validate_key_arity::<Self>(1)?;
+            let id = convert_id::<Self, _>(id.id())?;
+
             let relation_name = Self::get_qualified_name();
             let query_string = format!(
-                "SELECT * FROM {}.{} WHERE {} = $1",
-                Self::SCHEMA_NAME,
-                Self::RELATION_NAME,
-                Self::PRIMARY_KEY,
+                "SELECT EXISTS(SELECT 1 FROM {} WHERE {} = $1{})",
+                Self::get_qualified_identifier(),
+                Self::get_primary_key_identifier(),
+                Self::get_soft_delete_clause(" AND "),
             );
 
-            log::debug!(
-                "Dispatching single-SELECT query to database, targeting relation {relation_name}"
+            logging::debug!(
+                "Dispatching EXISTS query to database, targeting relation {relation_name}"
             );
-            log::trace!("Raw query prior to variable binding: {query_string}");
+            logging::trace!("Raw query prior to variable binding: {query_string}");
 
-            match sqlx::query_as(&query_string)
-                .bind(id.id() as i32)
-                .fetch_one(&database.connection)
+            match sqlx::query_scalar(&query_string)
+                .bind(id)
+                .fetch_one(&mut *database.acquire().await?)
                 .await
             {
-                Ok(record) => Ok(record),
+                Ok(exists) => Ok(exists),
                 Err(e) => Err(CrudkitError::from(e)),
             }
+        })
+    }
+
+    /// Check whether a record with the given identifying key exists in the database, responding
+    /// with [`StatusCode::OK`] if it does and [`StatusCode::NOT_FOUND`] if it does not. The response
+    /// has no body, so this is suitable for `HEAD` endpoints.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`ReadRelation::exists()`].
+    fn exists_handler<I: IdParameter, S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(id_param): Query<I>,
+    ) -> impl Future<Output = Response> + Send
+    where
+        Self::Key: TryFrom<I::Id>,
+    {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by EXISTS endpoint for relation {relation_name}, calling query \
+            dispatcher"
+        );
+
+        async move {
+            match Self::exists(state.get_database(), id_param).await {
+                Ok(true) => StatusCode::OK.into_response(),
+                Ok(false) => StatusCode::NOT_FOUND.into_response(),
+                Err(e) => state.error_response(&relation_name, e),
+            }
         }
     }
 
-    /// Query (select) a single record from the database using an identifying key.
+    /// Query (select) a single record from the database using a composite primary key.
     ///
-    /// If the record exists in the database, it is returned. Otherwise, [`None`] is returned.
+    /// This is meant for relations with a composite primary key, such as junction tables, where
+    /// [`Relation::PRIMARY_KEY`] is a parenthesized list like `"(column_a, column_b)"`. It generates
+    /// a `WHERE (column_a, column_b) = ($1, $2)` clause, which matches each column against the
+    /// corresponding value of the key.
+    ///
+    /// If the number of values in the key does not match the number of primary key columns, an
+    /// [`ErrorKind::InvalidQuery`] error is returned.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`ReadRelation::query_one_composite_handler()`].
+    fn query_one_composite<'c, K: CompositeKey>(
+        database: impl PgAcquire<'c>,
+        key: K,
+    ) -> impl Future<Output = CrudkitResult<Self::ReadRecord>> + Send {
+        logging::instrument::<Self, _>("query_one_composite", async move {
+            validate_key_arity::<Self>(K::COLUMN_COUNT)?;
+
+            let relation_name = Self::get_qualified_name();
+            let mut query_builder = QueryBuilder::new(format!(
+                "SELECT * FROM {} WHERE {} = (",
+                Self::get_qualified_identifier(),
+                Self::get_primary_key_tuple(),
+            ));
+            key.push_bindings(&mut query_builder.separated(", "));
+            query_builder.push(format!("){}", Self::get_soft_delete_clause(" AND ")));
+
+            logging::debug!(
+                "Dispatching composite-key single-SELECT query to database, targeting relation \
+                {relation_name}"
+            );
+            logging::trace!(
+                "Raw query prior to variable binding: {}",
+                query_builder.sql()
+            );
+
+            match query_builder
+                .build_query_as()
+                .fetch_one(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(record) => Ok(record),
+                Err(e) => Err(CrudkitError::from(e)),
+            }
+        })
+    }
+
+    /// Query (select) a single record from the database using a composite primary key, given as
+    /// query parameters with a field for each key column.
     ///
     /// This is the Axum route handler version of this method. For the standard method, which can be
-    /// called outside of an Axum context, see [`ReadRelation::query_one()`].
-    // TODO: Check how this interacts with junction tables
-    fn query_one_handler<I: IdParameter, S: DatabaseState>(
+    /// called outside of an Axum context, see [`ReadRelation::query_one_composite()`].
+    fn query_one_composite_handler<I: CompositeIdParameter, S: DatabaseState>(
         state: State<Arc<S>>,
-        Query(id_param): Query<I>,
+        Query(key_param): Query<I>,
     ) -> impl Future<Output = Response> + Send {
         let relation_name = Self::get_qualified_name();
-        log::debug!(
-            "Request received by single-SELECT endpoint for relation {relation_name}, calling query
-            dispatcher"
+        logging::debug!(
+            "Request received by composite-key single-SELECT endpoint for relation \
+            {relation_name}, calling query dispatcher"
         );
 
         async move {
-            match Self::query_one(state.get_database(), id_param).await {
-                Ok(record) => Json(record).into_response(),
-                Err(e) => StatusCode::from(e).into_response(),
+            CrudResponse(Self::query_one_composite(state.get_database(), key_param.key()).await)
+                .into_response_for(&**state, &relation_name)
+        }
+    }
+
+    /// Query (select) multiple records from the database using a list of identifying keys.
+    ///
+    /// The keys are bound as a single array, generating a `WHERE pk = ANY($1)` clause, so the
+    /// records are fetched in one round trip. Records are sorted by the relation's default order
+    /// rather than the order of the keys, and keys which do not match any record are ignored.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`ReadRelation::query_many_handler()`].
+    fn query_many<'c, I: IdParameter>(
+        database: impl PgAcquire<'c>,
+        ids: &[I],
+    ) -> impl Future<Output = CrudkitResult<Self>> + Send
+    where
+        Self::Key: TryFrom<I::Id> + PgHasArrayType,
+    {
+        logging::instrument::<Self, _>("query_many", {
+            let ids: CrudkitResult<Vec<Self::Key>> = ids
+                .iter()
+                .map(|id| convert_id::<Self, _>(id.id()))
+                .collect();
+
+            async move {
+                validate_key_arity::<Self>(1)?;
+                let ids = ids?;
+
+                let relation_name = Self::get_qualified_name();
+                let query_string = format!(
+                    "SELECT * FROM {} WHERE {} = ANY($1){} ORDER BY {}",
+                    Self::get_qualified_identifier(),
+                    Self::get_primary_key_identifier(),
+                    Self::get_soft_delete_clause(" AND "),
+                    Self::get_order_by_clause(),
+                );
+
+                logging::debug!(
+                    "Dispatching multi-SELECT query to database, targeting relation \
+                    {relation_name}"
+                );
+                logging::trace!("Raw query prior to variable binding: {query_string}");
+
+                match sqlx::query_as(&query_string)
+                    .bind(ids)
+                    .fetch_all(&mut *database.acquire().await?)
+                    .await
+                {
+                    Ok(records) => Ok(Self::with_records(records)),
+                    Err(e) => Err(CrudkitError::from(e)),
+                }
             }
+        })
+    }
+
+    /// Query (select) multiple records from the database using a comma-separated list of
+    /// identifying keys, such as `?ids=1,2,3`.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`ReadRelation::query_many()`].
+    fn query_many_handler<I: IdParameter, S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(id_list_param): Query<IdListParameter>,
+    ) -> impl Future<Output = Response> + Send
+    where
+        I::Id: FromStr,
+        Self::Key: TryFrom<I::Id> + PgHasArrayType,
+    {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by multi-SELECT endpoint for relation {relation_name}, calling query \
+            dispatcher"
+        );
+
+        async move {
+            let ids = match id_list_param.parse::<I>() {
+                Ok(ids) => ids,
+                Err(e) => return state.error_response(&relation_name, e),
+            };
+
+            CrudResponse(Self::query_many(state.get_database(), &ids).await)
+                .into_response_for(&**state, &relation_name)
         }
     }
 
+    /// Query (select) the records matching a set of composite primary keys from the database.
+    ///
+    /// This is meant for relations with a composite primary key, such as junction tables, where
+    /// [`Relation::PRIMARY_KEY`] is a parenthesized list like `"(column_a, column_b)"`. It generates
+    /// a `WHERE (column_a, column_b) IN (($1, $2), ($3, $4), ...)` clause, splitting the keys into
+    /// multiple queries if they would exceed the parameter bind limit. Records are sorted within
+    /// each query, but not across queries. Keys which do not match any record are ignored.
+    ///
+    /// If the number of values in each key does not match the number of primary key columns, an
+    /// [`ErrorKind::InvalidQuery`] error is returned.
+    fn query_many_composite<'c, K: CompositeKey>(
+        database: impl PgAcquire<'c>,
+        keys: Vec<K>,
+    ) -> impl Future<Output = CrudkitResult<Self>> + Send {
+        logging::instrument::<Self, _>("query_many_composite", async move {
+            validate_key_arity::<Self>(K::COLUMN_COUNT)?;
+
+            let relation_name = Self::get_qualified_name();
+
+            let chunk_size = SQL_PARAMETER_BIND_LIMIT / K::COLUMN_COUNT;
+            let mut records = Vec::with_capacity(keys.len());
+            let mut keys = keys.into_iter().peekable();
+            let mut connection = database.acquire().await?;
+            while keys.peek().is_some() {
+                let mut query_builder = QueryBuilder::new(format!(
+                    "SELECT * FROM {} WHERE {} IN (",
+                    Self::get_qualified_identifier(),
+                    Self::get_primary_key_tuple(),
+                ));
+                for (i, key) in keys.by_ref().take(chunk_size).enumerate() {
+                    if i > 0 {
+                        query_builder.push(", ");
+                    }
+
+                    query_builder.push("(");
+                    key.push_bindings(&mut query_builder.separated(", "));
+                    query_builder.push(")");
+                }
+                query_builder.push(format!(
+                    "){} ORDER BY {}",
+                    Self::get_soft_delete_clause(" AND "),
+                    Self::get_order_by_clause()
+                ));
+
+                logging::debug!(
+                    "Dispatching composite-key multi-SELECT query to database, targeting relation \
+                    {relation_name}"
+                );
+                logging::trace!(
+                    "Raw query prior to variable binding: {}",
+                    query_builder.sql()
+                );
+
+                match query_builder
+                    .build_query_as()
+                    .fetch_all(&mut *connection)
+                    .await
+                {
+                    Ok(chunk_records) => records.extend(chunk_records),
+                    Err(e) => return Err(CrudkitError::from(e)),
+                }
+            }
+
+            Ok(Self::with_records(records))
+        })
+    }
+
     /// Query (select) all records for this relation from the database.
     ///
     /// This is the standard version of this method and should not be used as an Axum route handler.
     /// For the handler method, use [`ReadRelation::query_all_handler()`].
-    fn query_all(database: &PgDatabase) -> impl Future<Output = CrudkitResult<Self>> + Send {
+    fn query_all<'c>(
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<Self>> + Send {
+        logging::instrument::<Self, _>("query_all", {
+            let relation_name = Self::get_qualified_name();
+            let query_string = format!(
+                "SELECT * FROM {}{} ORDER BY {}",
+                Self::get_qualified_identifier(),
+                Self::get_soft_delete_clause(" WHERE "),
+                Self::get_order_by_clause(),
+            );
+
+            logging::debug!(
+                "Dispatching multi-SELECT query to database, targeting relation {relation_name}"
+            );
+            logging::trace!("Raw query prior to variable binding: {query_string}");
+
+            async move {
+                match sqlx::query_as(&query_string)
+                    .fetch_all(&mut *database.acquire().await?)
+                    .await
+                {
+                    Ok(records) => Ok(Self::with_records(records)),
+                    Err(e) => Err(CrudkitError::from(e)),
+                }
+            }
+        })
+    }
+
+    /// Query (select) all records for this relation from the database.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`ReadRelation::query_all()`].
+    fn query_all_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+    ) -> impl Future<Output = Response> + Send {
         let relation_name = Self::get_qualified_name();
-        let query_string = format!(
-            "SELECT * FROM {}.{} ORDER BY {}",
-            Self::SCHEMA_NAME,
-            Self::RELATION_NAME,
-            Self::PRIMARY_KEY,
+        logging::debug!(
+            "Request received by multi-SELECT endpoint for relation {relation_name}, calling query
+            dispatcher"
         );
 
-        log::debug!(
-            "Dispatching multi-SELECT query to database, targeting relation {relation_name}"
+        async move {
+            CrudResponse(Self::query_all(state.get_database()).await)
+                .into_response_for(&**state, &relation_name)
+        }
+    }
+
+    /// Query (select) only the given columns of all records for this relation from the database.
+    ///
+    /// Since the record type cannot be constructed from some of its columns, each record is
+    /// returned as a JSON object mapping the column names to their values. Records are sorted the
+    /// same way as in [`ReadRelation::query_all()`]. If no columns are given or any column is not
+    /// present in [`Record::COLUMN_NAMES`], an [`ErrorKind::InvalidQuery`] error is returned
+    /// without querying the database.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`ReadRelation::query_all_columns_handler()`].
+    fn query_all_columns<'c>(
+        database: impl PgAcquire<'c>,
+        columns: &[&str],
+    ) -> impl Future<Output = CrudkitResult<Vec<Map<String, Value>>>> + Send {
+        logging::instrument::<Self, _>("query_all_columns", async move {
+            let mut query_builder = build_projection_query::<Self::Record>(columns)?;
+
+            let relation_name = Self::get_qualified_name();
+            logging::debug!(
+                "Dispatching projected multi-SELECT query to database, targeting relation \
+                {relation_name}"
+            );
+            logging::trace!(
+                "Raw query prior to variable binding: {}",
+                query_builder.sql()
+            );
+
+            let rows: Vec<String> = query_builder
+                .build_query_scalar()
+                .fetch_all(&mut *database.acquire().await?)
+                .await
+                .map_err(CrudkitError::from)?;
+
+            rows.iter()
+                .map(|row| {
+                    serde_json::from_str(row)
+                        .map_err(|e| CrudkitError::from(SqlxError::Decode(Box::new(e))))
+                })
+                .collect()
+        })
+    }
+
+    /// Query (select) only the given columns of all records for this relation from the database.
+    ///
+    /// The columns are given by the `fields` query parameter, such as `?fields=id,name` (see
+    /// [`FieldsParams`]). If it is not given, every column is selected. Unknown columns result in
+    /// a `400 Bad Request` response.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`ReadRelation::query_all_columns()`].
+    fn query_all_columns_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(fields_params): Query<FieldsParams>,
+    ) -> impl Future<Output = Response> + Send {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by projected multi-SELECT endpoint for relation {relation_name}, \
+            calling query dispatcher"
+        );
+
+        async move {
+            let columns = fields_params.parse::<Self::Record>();
+            CrudResponse(Self::query_all_columns(state.get_database(), &columns).await)
+                .into_response_for(&**state, &relation_name)
+        }
+    }
+
+    /// Query (select) the record with the lowest primary key from the database.
+    ///
+    /// Only a single record is fetched, using `LIMIT 1`. If the relation is empty, [`None`] is
+    /// returned. For composite primary keys, records are sorted by each key column in order.
+    fn query_first<'c>(
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<Option<Self::ReadRecord>>> + Send {
+        logging::instrument::<Self, _>(
+            "query_first",
+            query_one_by_primary_key_order::<Self>(database, SortDirection::Ascending),
+        )
+    }
+
+    /// Query (select) the record with the highest primary key from the database.
+    ///
+    /// Only a single record is fetched, using `LIMIT 1`. If the relation is empty, [`None`] is
+    /// returned. For composite primary keys, records are sorted by each key column in order.
+    fn query_last<'c>(
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<Option<Self::ReadRecord>>> + Send {
+        logging::instrument::<Self, _>(
+            "query_last",
+            query_one_by_primary_key_order::<Self>(database, SortDirection::Descending),
+        )
+    }
+
+    /// Query (select) all records for this relation from the database as a stream, rather than
+    /// collecting them into the relation.
+    ///
+    /// Records are sorted the same way as in [`ReadRelation::query_all()`], but they are fetched
+    /// through a server-side cursor in batches of [`ReadRelation::STREAM_BATCH_SIZE`], so only one
+    /// batch is held in memory at a time. The cursor lives in a transaction which is held open until
+    /// the stream is exhausted or dropped, so the records are a consistent snapshot of the relation.
+    /// For relations which comfortably fit in memory, prefer [`ReadRelation::query_all()`].
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`ReadRelation::query_all_stream_handler()`].
+    fn query_all_stream(
+        database: &PgDatabase,
+    ) -> impl Stream<Item = CrudkitResult<Self::Record>> + Send + 'static
+    where
+        Self: 'static,
+    {
+        let relation_name = Self::get_qualified_name();
+        let declare_string = format!(
+            "DECLARE crudkit_stream NO SCROLL CURSOR FOR SELECT * FROM {}{} ORDER BY {}",
+            Self::get_qualified_identifier(),
+            Self::get_soft_delete_clause(" WHERE "),
+            Self::get_order_by_clause(),
+        );
+        let fetch_string = format!(
+            "FETCH FORWARD {} FROM crudkit_stream",
+            Self::STREAM_BATCH_SIZE
+        );
+
+        logging::debug!(
+            "Dispatching streaming multi-SELECT query to database, targeting relation \
+            {relation_name}"
+        );
+        logging::trace!("Raw query prior to variable binding: {declare_string}");
+
+        let database = database.clone();
+        stream::try_unfold(
+            None,
+            move |transaction: Option<Transaction<'static, Postgres>>| {
+                let database = database.clone();
+                let declare_string = declare_string.clone();
+                let fetch_string = fetch_string.clone();
+
+                async move {
+                    let mut transaction = match transaction {
+                        Some(transaction) => transaction,
+                        None => {
+                            let mut transaction = database.begin().await?;
+                            sqlx::query(&declare_string)
+                                .execute(&mut *transaction)
+                                .await?;
+                            transaction
+                        }
+                    };
+
+                    let records: Vec<Self::Record> = sqlx::query_as(&fetch_string)
+                        .fetch_all(&mut *transaction)
+                        .await?;
+
+                    if records.is_empty() {
+                        transaction.commit().await?;
+                        return CrudkitResult::Ok(None);
+                    }
+
+                    Ok(Some((records, Some(transaction))))
+                }
+            },
+        )
+        .map_ok(|records| stream::iter(records.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Query (select) all records for this relation from the database as a stream, responding
+    /// with newline-delimited JSON (NDJSON).
+    ///
+    /// Each record is written as a single line of JSON as soon as it is fetched, so large relations
+    /// can be exported without buffering them. Since the status code is sent before any records
+    /// are fetched, a database error during the stream ends the response body early rather than
+    /// resulting in an error status code.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`ReadRelation::query_all_stream()`].
+    fn query_all_stream_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+    ) -> impl Future<Output = Response> + Send
+    where
+        Self: 'static,
+    {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by streaming multi-SELECT endpoint for relation {relation_name}, \
+            calling query dispatcher"
+        );
+
+        async move {
+            let lines =
+                Self::query_all_stream(state.get_database()).map(|record| -> Result<_, BoxError> {
+                    let mut line = serde_json::to_vec(&record?)?;
+                    line.push(b'\n');
+                    Ok(line)
+                });
+
+            (
+                [(header::CONTENT_TYPE, "application/x-ndjson")],
+                Body::from_stream(lines),
+            )
+                .into_response()
+        }
+    }
+
+    /// Query (select) all records for this relation from the database, including those which have
+    /// been soft-deleted.
+    ///
+    /// This is the same as [`ReadRelation::query_all()`] for relations without a
+    /// [`Relation::SOFT_DELETE_COLUMN`].
+    fn query_all_including_deleted<'c>(
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<Self>> + Send {
+        logging::instrument::<Self, _>("query_all_including_deleted", {
+            let relation_name = Self::get_qualified_name();
+            let query_string = format!(
+                "SELECT * FROM {} ORDER BY {}",
+                Self::get_qualified_identifier(),
+                Self::get_order_by_clause(),
+            );
+
+            logging::debug!(
+                "Dispatching multi-SELECT query including deleted records to database, targeting \
+                relation {relation_name}"
+            );
+            logging::trace!("Raw query prior to variable binding: {query_string}");
+
+            async move {
+                match sqlx::query_as(&query_string)
+                    .fetch_all(&mut *database.acquire().await?)
+                    .await
+                {
+                    Ok(records) => Ok(Self::with_records(records)),
+                    Err(e) => Err(CrudkitError::from(e)),
+                }
+            }
+        })
+    }
+
+    /// Query (select) all records for this relation from the database, sorted by the given
+    /// columns.
+    ///
+    /// Each column is sorted in the given direction, in order of precedence. If no columns are
+    /// given, records are sorted the same way as in [`ReadRelation::query_all()`]. If a column is not
+    /// in [`Record::COLUMN_NAMES`], an [`ErrorKind::InvalidQuery`] error is returned without querying
+    /// the database.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`ReadRelation::query_all_sorted_handler()`].
+    fn query_all_sorted<'c>(
+        database: impl PgAcquire<'c>,
+        order_by: &[(&str, SortDirection)],
+    ) -> impl Future<Output = CrudkitResult<Self>> + Send {
+        logging::instrument::<Self, _>("query_all_sorted", async move {
+            let relation_name = Self::get_qualified_name();
+            let query_string = format!(
+                "SELECT * FROM {}{} ORDER BY {}",
+                Self::get_qualified_identifier(),
+                Self::get_soft_delete_clause(" WHERE "),
+                build_order_by_clause::<Self::Record>(order_by)?,
+            );
+
+            logging::debug!(
+                "Dispatching sorted multi-SELECT query to database, targeting relation \
+                {relation_name}"
+            );
+            logging::trace!("Raw query prior to variable binding: {query_string}");
+
+            match sqlx::query_as(&query_string)
+                .fetch_all(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(records) => Ok(Self::with_records(records)),
+                Err(e) => Err(CrudkitError::from(e)),
+            }
+        })
+    }
+
+    /// Query (select) all records for this relation from the database, sorted by the columns
+    /// given in the query parameters.
+    ///
+    /// The columns are given by the `sort` query parameter, such as `?sort=name.asc,id.desc` (see
+    /// [`SortParams`]). Unknown columns or directions result in a `400 Bad Request` response.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`ReadRelation::query_all_sorted()`].
+    fn query_all_sorted_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(sort_params): Query<SortParams>,
+    ) -> impl Future<Output = Response> + Send {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by sorted multi-SELECT endpoint for relation {relation_name}, \
+            calling query dispatcher"
         );
-        log::trace!("Raw query prior to variable binding: {query_string}");
 
         async move {
+            let order_by = match sort_params.parse() {
+                Ok(order_by) => order_by,
+                Err(e) => return state.error_response(&relation_name, e),
+            };
+
+            CrudResponse(Self::query_all_sorted(state.get_database(), &order_by).await)
+                .into_response_for(&**state, &relation_name)
+        }
+    }
+
+    /// Query (select) a page of records for this relation from the database.
+    ///
+    /// Records are sorted the same way as in [`ReadRelation::query_all()`], then `offset` records
+    /// are skipped and at most `limit` records are returned. If either value is negative or `limit`
+    /// exceeds [`ReadRelation::MAX_PAGE_LIMIT`], an [`ErrorKind::InvalidQuery`] error is returned
+    /// without querying the database.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`ReadRelation::query_page_handler()`].
+    fn query_page<'c>(
+        database: impl PgAcquire<'c>,
+        limit: i64,
+        offset: i64,
+    ) -> impl Future<Output = CrudkitResult<Self>> + Send {
+        logging::instrument::<Self, _>("query_page", async move {
+            let (limit, offset) = PageParams {
+                limit: Some(limit),
+                offset: Some(offset),
+            }
+            .validate(Self::MAX_PAGE_LIMIT)?;
+
+            let relation_name = Self::get_qualified_name();
+            let query_string = format!(
+                "SELECT * FROM {}{} ORDER BY {} LIMIT $1 OFFSET $2",
+                Self::get_qualified_identifier(),
+                Self::get_soft_delete_clause(" WHERE "),
+                Self::get_order_by_clause(),
+            );
+
+            logging::debug!(
+                "Dispatching paginated multi-SELECT query to database, targeting relation \
+                {relation_name}"
+            );
+            logging::trace!("Raw query prior to variable binding: {query_string}");
+
             match sqlx::query_as(&query_string)
-                .fetch_all(&database.connection)
+                .bind(limit)
+                .bind(offset)
+                .fetch_all(&mut *database.acquire().await?)
                 .await
             {
                 Ok(records) => Ok(Self::with_records(records)),
                 Err(e) => Err(CrudkitError::from(e)),
             }
+        })
+    }
+
+    /// Query (select) a page of records for this relation from the database.
+    ///
+    /// The page is selected using the `limit` and `offset` query parameters, which default to
+    /// [`DEFAULT_PAGE_LIMIT`](crate::pagination::DEFAULT_PAGE_LIMIT) and zero respectively. A
+    /// negative value or a limit above [`ReadRelation::MAX_PAGE_LIMIT`] results in a
+    /// `400 Bad Request` response.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`ReadRelation::query_page()`].
+    fn query_page_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(page_params): Query<PageParams>,
+    ) -> impl Future<Output = Response> + Send {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by paginated multi-SELECT endpoint for relation {relation_name}, \
+            calling query dispatcher"
+        );
+
+        async move {
+            let (limit, offset) = page_params.resolve();
+            CrudResponse(Self::query_page(state.get_database(), limit, offset).await)
+                .into_response_for(&**state, &relation_name)
         }
     }
 
-    /// Query (select) all records for this relation from the database.
+    /// Query (select) a page of records for this relation from the database, starting after the
+    /// record with the given primary key.
+    ///
+    /// Unlike [`ReadRelation::query_page()`], this uses keyset pagination, generating a
+    /// `WHERE pk > $1 ORDER BY pk LIMIT $2` query, so later pages are as fast to select as the
+    /// first. Records are always sorted by the primary key rather than by
+    /// [`Relation::DEFAULT_ORDER`]. If `after` is [`None`], the first page is selected. The returned
+    /// [`CursorPage`] includes the key to pass as `after` to select the next page, and a page with
+    /// fewer than `limit` records is the last one. If `limit` is negative or exceeds
+    /// [`ReadRelation::MAX_PAGE_LIMIT`], an [`ErrorKind::InvalidQuery`] error is returned without
+    /// querying the database.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`ReadRelation::query_after_handler()`].
+    fn query_after<'c, K>(
+        database: impl PgAcquire<'c>,
+        after: Option<K>,
+        limit: i64,
+    ) -> impl Future<Output = CrudkitResult<CursorPage<Self, Self::Key>>> + Send
+    where
+        Self::Key: TryFrom<K>,
+        Self::Record: IdentifiableRecord,
+    {
+        logging::instrument::<Self, _>("query_after", {
+            let after = after.map(convert_id::<Self, _>).transpose();
+
+            async move {
+                validate_key_arity::<Self>(1)?;
+                let (limit, _) = PageParams {
+                    limit: Some(limit),
+                    offset: None,
+                }
+                .validate(Self::MAX_PAGE_LIMIT)?;
+                let after = after?;
+
+                let relation_name = Self::get_qualified_name();
+                let mut query_builder = QueryBuilder::new(format!(
+                    "SELECT * FROM {}",
+                    Self::get_qualified_identifier(),
+                ));
+
+                let mut keyword = " WHERE ";
+                if let Some(after) = after {
+                    query_builder.push(format!(
+                        "{keyword}{} > ",
+                        Self::get_primary_key_identifier()
+                    ));
+                    query_builder.push_bind(after);
+                    keyword = " AND ";
+                }
+                query_builder.push(Self::get_soft_delete_clause(keyword));
+
+                query_builder.push(format!(
+                    " ORDER BY {} LIMIT ",
+                    Self::get_primary_key_identifier()
+                ));
+                query_builder.push_bind(limit);
+
+                logging::debug!(
+                    "Dispatching cursor-paginated multi-SELECT query to database, targeting \
+                    relation {relation_name}"
+                );
+                logging::trace!(
+                    "Raw query prior to variable binding: {}",
+                    query_builder.sql()
+                );
+
+                match query_builder
+                    .build_query_as::<Self::Record>()
+                    .fetch_all(&mut *database.acquire().await?)
+                    .await
+                {
+                    Ok(records) => {
+                        let next_cursor = records.last().and_then(|record| record.id());
+                        Ok(CursorPage {
+                            relation: Self::with_records(records),
+                            next_cursor,
+                        })
+                    }
+                    Err(e) => Err(CrudkitError::from(e)),
+                }
+            }
+        })
+    }
+
+    /// Query (select) a page of records for this relation from the database, starting after the
+    /// record with the given primary key.
+    ///
+    /// The page is selected using the `after` and `limit` query parameters, such as
+    /// `?after=100&limit=20`. The response body contains the records along with a `next_cursor`
+    /// field, which is passed as `after` to select the next page. A limit which is negative or
+    /// above [`ReadRelation::MAX_PAGE_LIMIT`] results in a `400 Bad Request` response.
     ///
     /// This is the Axum route handler version of this method. For the standard method, which can be
-    /// called outside of an Axum context, see [`ReadRelation::query_all()`].
-    fn query_all_handler<S: DatabaseState>(
+    /// called outside of an Axum context, see [`ReadRelation::query_after()`].
+    fn query_after_handler<K: Send, S: DatabaseState>(
         state: State<Arc<S>>,
+        Query(cursor_params): Query<CursorParams<K>>,
+    ) -> impl Future<Output = Response> + Send
+    where
+        Self::Key: TryFrom<K> + Serialize,
+        Self::Record: IdentifiableRecord,
+    {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by cursor-paginated multi-SELECT endpoint for relation \
+            {relation_name}, calling query dispatcher"
+        );
+
+        async move {
+            let (limit, _) = PageParams {
+                limit: cursor_params.limit,
+                offset: None,
+            }
+            .resolve();
+            CrudResponse(Self::query_after(state.get_database(), cursor_params.after, limit).await)
+                .into_response_for(&**state, &relation_name)
+        }
+    }
+
+    /// Query (select) all records for this relation which match every one of the given filters.
+    ///
+    /// Filtered columns may be wrapped in one of a fixed set of SQL functions (see
+    /// [`FilterColumn`](crate::filter::FilterColumn)), so filters like `lower(email) = lower($1)`
+    /// can make use of functional indexes. If a filter targets a column which is not in
+    /// [`Record::COLUMN_NAMES`], an [`ErrorKind::InvalidQuery`] error is returned without querying
+    /// the database.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`ReadRelation::query_where_handler()`].
+    fn query_where<'c>(
+        database: impl PgAcquire<'c>,
+        filters: &[Filter],
+    ) -> impl Future<Output = CrudkitResult<Self>> + Send {
+        logging::instrument::<Self, _>("query_where", async move {
+            let relation_name = Self::get_qualified_name();
+            let mut query_builder = QueryBuilder::new(format!(
+                "SELECT * FROM {}",
+                Self::get_qualified_identifier(),
+            ));
+            push_visible_where_clause::<Self::Record>(&mut query_builder, filters)?;
+            query_builder.push(format!(" ORDER BY {}", Self::get_order_by_clause()));
+
+            logging::debug!(
+                "Dispatching filtered multi-SELECT query to database, targeting relation \
+                {relation_name}"
+            );
+            logging::trace!(
+                "Raw query prior to variable binding: {}",
+                query_builder.sql()
+            );
+
+            match query_builder
+                .build_query_as()
+                .fetch_all(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(records) => Ok(Self::with_records(records)),
+                Err(e) => Err(CrudkitError::from(e)),
+            }
+        })
+    }
+
+    /// Query (select) all records for this relation which match every one of the filters given in
+    /// the query parameters.
+    ///
+    /// Each filter is specified as `<column>=<operator>.<value>`, such as `?name=eq.John` or
+    /// `?id=in.(1,2,3)`, following the syntax used by PostgREST. The supported operators are `eq`,
+    /// `neq`, `gt`, `lt`, `like`, and `in`. Unknown columns or operators result in a
+    /// `400 Bad Request` response.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`ReadRelation::query_where()`].
+    fn query_where_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(filter_params): Query<HashMap<String, String>>,
     ) -> impl Future<Output = Response> + Send {
         let relation_name = Self::get_qualified_name();
-        log::debug!(
-            "Request received by multi-SELECT endpoint for relation {relation_name}, calling query
+        logging::debug!(
+            "Request received by filtered multi-SELECT endpoint for relation {relation_name}, \
+            calling query dispatcher"
+        );
+
+        async move {
+            let filters = match parse_where_parameters::<Self::Record>(filter_params) {
+                Ok(filters) => filters,
+                Err(e) => return state.error_response(&relation_name, e),
+            };
+
+            CrudResponse(Self::query_where(state.get_database(), &filters).await)
+                .into_response_for(&**state, &relation_name)
+        }
+    }
+
+    /// Query (select) the records for this relation where any of the given columns contains the
+    /// search term, ignoring case.
+    ///
+    /// This generates a `WHERE col ILIKE '%' || $1 || '%' OR ...` query, where wildcard characters
+    /// in the term are escaped so that it is matched literally. Columns which are not text are cast
+    /// to text before matching. Records are sorted the same way as in [`ReadRelation::query_all()`].
+    /// If no columns are given or any column is not present in [`Record::COLUMN_NAMES`], an
+    /// [`ErrorKind::InvalidQuery`] error is returned without querying the database.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`ReadRelation::search_handler()`].
+    fn search<'c>(
+        database: impl PgAcquire<'c>,
+        columns: &[&str],
+        term: &str,
+    ) -> impl Future<Output = CrudkitResult<Self>> + Send {
+        logging::instrument::<Self, _>("search", async move {
+            let condition = build_search_condition::<Self::Record>(columns)?;
+            query_matching::<Self>(database, &condition, escape_like_pattern(term)).await
+        })
+    }
+
+    /// Query (select) the records for this relation where any of the given columns contains the
+    /// search term, ignoring case.
+    ///
+    /// The term and columns are given by the `q` and `columns` query parameters, such as
+    /// `?q=jane&columns=name,email_address` (see [`SearchParams`]). Unknown columns result in a
+    /// `400 Bad Request` response.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`ReadRelation::search()`].
+    fn search_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(search_params): Query<SearchParams>,
+    ) -> impl Future<Output = Response> + Send {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by search endpoint for relation {relation_name}, calling query \
             dispatcher"
         );
 
         async move {
-            match Self::query_all(state.get_database()).await {
-                Ok(records) => Json(records).into_response(),
-                Err(e) => StatusCode::from(e).into_response(),
+            let columns = search_params.parse_columns::<Self::Record>();
+            CrudResponse(Self::search(state.get_database(), &columns, &search_params.q).await)
+                .into_response_for(&**state, &relation_name)
+        }
+    }
+
+    /// Query (select) the records for this relation where the given columns, taken together, match
+    /// a full-text search query.
+    ///
+    /// The query is parsed with `websearch_to_tsquery()` using
+    /// [`ReadRelation::TEXT_SEARCH_CONFIG`], so it can contain quoted phrases, `or`, and `-` to
+    /// exclude words, and any input is accepted. For larger relations, the search can be sped up
+    /// with a GIN index on the same `to_tsvector(config, concat_ws(' ', ...))` expression. If no
+    /// columns are given or any column is not present in [`Record::COLUMN_NAMES`], an
+    /// [`ErrorKind::InvalidQuery`] error is returned without querying the database.
+    fn search_full_text<'c>(
+        database: impl PgAcquire<'c>,
+        columns: &[&str],
+        query: &str,
+    ) -> impl Future<Output = CrudkitResult<Self>> + Send {
+        logging::instrument::<Self, _>("search_full_text", async move {
+            let condition = build_full_text_search_condition::<Self::Record>(
+                columns,
+                Self::TEXT_SEARCH_CONFIG,
+            )?;
+            query_matching::<Self>(database, &condition, query.to_owned()).await
+        })
+    }
+
+    /// Count the records in this relation.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`ReadRelation::count_handler()`].
+    fn count<'c>(database: impl PgAcquire<'c>) -> impl Future<Output = CrudkitResult<i64>> + Send {
+        Self::count_where(database, &[])
+    }
+
+    /// Count the records in this relation which match every one of the given filters.
+    ///
+    /// The filters are validated and combined the same way as in [`ReadRelation::query_where()`].
+    /// If no filters are given, every record is counted.
+    fn count_where<'c>(
+        database: impl PgAcquire<'c>,
+        filters: &[Filter],
+    ) -> impl Future<Output = CrudkitResult<i64>> + Send {
+        logging::instrument::<Self, _>("count_where", async move {
+            let relation_name = Self::get_qualified_name();
+            let mut query_builder = QueryBuilder::new(format!(
+                "SELECT COUNT(*) FROM {}",
+                Self::get_qualified_identifier(),
+            ));
+            push_visible_where_clause::<Self::Record>(&mut query_builder, filters)?;
+
+            logging::debug!(
+                "Dispatching COUNT query to database, targeting relation {relation_name}"
+            );
+            logging::trace!(
+                "Raw query prior to variable binding: {}",
+                query_builder.sql()
+            );
+
+            match query_builder
+                .build_query_scalar()
+                .fetch_one(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(count) => Ok(count),
+                Err(e) => Err(CrudkitError::from(e)),
             }
+        })
+    }
+
+    /// Count the records in this relation, responding with `{ "count": N }`.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`ReadRelation::count()`].
+    fn count_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+    ) -> impl Future<Output = Response> + Send {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by COUNT endpoint for relation {relation_name}, calling query \
+            dispatcher"
+        );
+
+        async move {
+            let count = Self::count(state.get_database()).await;
+            CrudResponse(count.map(|count| CountResponse { count }))
+                .into_response_for(&**state, &relation_name)
         }
     }
+
+    /// Query (select) a page of the records for this relation which match every one of the given
+    /// filters, along with the total number of matching records.
+    ///
+    /// The filters are validated and combined the same way as in [`ReadRelation::query_where()`],
+    /// and the records are sorted the same way as in [`ReadRelation::query_all_sorted()`] before
+    /// `offset` records are skipped and at most `limit` records are returned. The total is counted
+    /// on the same connection using [`ReadRelation::count_where()`]. If either page value is
+    /// negative or `limit` exceeds [`ReadRelation::MAX_PAGE_LIMIT`], an [`ErrorKind::InvalidQuery`]
+    /// error is returned without querying the database.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`ReadRelation::query_search_handler()`].
+    fn query_search<'c>(
+        database: impl PgAcquire<'c>,
+        filters: &[Filter],
+        order_by: &[(&str, SortDirection)],
+        limit: i64,
+        offset: i64,
+    ) -> impl Future<Output = CrudkitResult<SearchPage<Self::Record>>> + Send {
+        logging::instrument::<Self, _>("query_search", async move {
+            let (limit, offset) = PageParams {
+                limit: Some(limit),
+                offset: Some(offset),
+            }
+            .validate(Self::MAX_PAGE_LIMIT)?;
+
+            let relation_name = Self::get_qualified_name();
+            let mut query_builder = QueryBuilder::new(format!(
+                "SELECT * FROM {}",
+                Self::get_qualified_identifier(),
+            ));
+            push_visible_where_clause::<Self::Record>(&mut query_builder, filters)?;
+            query_builder.push(format!(
+                " ORDER BY {} LIMIT ",
+                build_order_by_clause::<Self::Record>(order_by)?
+            ));
+            query_builder.push_bind(limit);
+            query_builder.push(" OFFSET ");
+            query_builder.push_bind(offset);
+
+            logging::debug!(
+                "Dispatching filtered and paginated multi-SELECT query to database, targeting \
+                relation {relation_name}"
+            );
+            logging::trace!(
+                "Raw query prior to variable binding: {}",
+                query_builder.sql()
+            );
+
+            let mut connection = database.acquire().await?;
+            let items = query_builder
+                .build_query_as()
+                .fetch_all(&mut *connection)
+                .await
+                .map_err(CrudkitError::from)?;
+            let total = Self::count_where(&mut *connection, filters).await?;
+
+            Ok(SearchPage {
+                items,
+                total,
+                limit,
+                offset,
+            })
+        })
+    }
+
+    /// Query (select) a page of the records for this relation which match every one of the filters
+    /// given in the JSON body, along with the total number of matching records.
+    ///
+    /// The body is a [`SearchRequest`], such as
+    /// `{ "filters": { "name": "like.J*" }, "sort": "name.asc", "limit": 20 }`, and the response
+    /// body has the form `{ "items": [...], "total": N, "limit": N, "offset": N }`. Unknown
+    /// columns, operators, or sort directions, as well as invalid page values, result in a
+    /// `400 Bad Request` response.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`ReadRelation::query_search()`].
+    fn query_search_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+        Json(search_request): Json<SearchRequest>,
+    ) -> impl Future<Output = Response> + Send {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by search-SELECT endpoint for relation {relation_name}, calling \
+            query dispatcher"
+        );
+
+        async move {
+            let SearchRequest {
+                filters,
+                sort,
+                page,
+            } = search_request;
+            let parsed = parse_where_parameters::<Self::Record>(filters)
+                .and_then(|filters| Ok((filters, sort.parse()?)));
+            let (filters, order_by) = match parsed {
+                Ok(parsed) => parsed,
+                Err(e) => return state.error_response(&relation_name, e),
+            };
+
+            let (limit, offset) = page.resolve();
+            CrudResponse(
+                Self::query_search(state.get_database(), &filters, &order_by, limit, offset).await,
+            )
+            .into_response_for(&**state, &relation_name)
+        }
+    }
+
+    /// Query (select) all records for this relation whose timestamp columns fall within the ranges
+    /// given in the query parameters.
+    ///
+    /// Each range is specified using a `<column>_from` and/or `<column>_to` query parameter, such as
+    /// `?created_at_from=2025-01-01T00:00:00Z&created_at_to=2025-02-01T00:00:00Z`. Both bounds are
+    /// inclusive and are cast to `timestamptz`. Unknown columns or unexpected parameters result in a
+    /// `400 Bad Request` response.
+    ///
+    /// This is the Axum route handler version of [`ReadRelation::query_where()`] with
+    /// [`Filter::range()`] filters.
+    fn query_range_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(range_params): Query<HashMap<String, String>>,
+    ) -> impl Future<Output = Response> + Send {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by range-SELECT endpoint for relation {relation_name}, calling query \
+            dispatcher"
+        );
+
+        async move {
+            let filters = match parse_timestamp_range_parameters(range_params) {
+                Ok(filters) => filters,
+                Err(e) => return state.error_response(&relation_name, e),
+            };
+
+            CrudResponse(Self::query_where(state.get_database(), &filters).await)
+                .into_response_for(&**state, &relation_name)
+        }
+    }
+}
+
+/// Query (select) the record of a relation with the given single-column primary key, or [`None`]
+/// if it does not exist.
+///
+/// This is shared by [`ReadRelation::query_one()`] and [`ReadRelation::query_one_opt()`].
+async fn query_one_by_key<'c, R: ReadRelation>(
+    database: impl PgAcquire<'c>,
+    id: R::Key,
+) -> CrudkitResult<Option<R::ReadRecord>> {
+    let relation_name = R::get_qualified_name();
+    let query_string = format!(
+        "SELECT * FROM {} WHERE {} = $1{}",
+        R::get_qualified_identifier(),
+        R::get_primary_key_identifier(),
+        R::get_soft_delete_clause(" AND "),
+    );
+
+    logging::debug!(
+        "Dispatching single-SELECT query to database, targeting relation {relation_name}"
+    );
+    logging::trace!("Raw query prior to variable binding: {query_string}");
+
+    sqlx::query_as(&query_string)
+        .bind(id)
+        .fetch_optional(&mut *database.acquire().await?)
+        .await
+        .map_err(CrudkitError::from)
+}
+
+/// Query (select) the first record of a relation when sorted by its primary key in the given
+/// direction, or [`None`] if the relation is empty.
+///
+/// This is shared by [`ReadRelation::query_first()`] and [`ReadRelation::query_last()`].
+async fn query_one_by_primary_key_order<'c, R: ReadRelation>(
+    database: impl PgAcquire<'c>,
+    direction: SortDirection,
+) -> CrudkitResult<Option<R::ReadRecord>> {
+    let relation_name = R::get_qualified_name();
+    let order_by_clause = R::get_rendered_primary_key_columns()
+        .iter()
+        .map(|column_name| format!("{column_name} {}", direction.as_str()))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let query_string = format!(
+        "SELECT * FROM {}{} ORDER BY {order_by_clause} LIMIT 1",
+        R::get_qualified_identifier(),
+        R::get_soft_delete_clause(" WHERE "),
+    );
+
+    logging::debug!(
+        "Dispatching ordered single-SELECT query to database, targeting relation {relation_name}"
+    );
+    logging::trace!("Raw query prior to variable binding: {query_string}");
+
+    sqlx::query_as(&query_string)
+        .fetch_optional(&mut *database.acquire().await?)
+        .await
+        .map_err(CrudkitError::from)
+}
+
+/// Query (select) the records of a relation which match a condition with a single text parameter.
+///
+/// This is shared by [`ReadRelation::search()`] and [`ReadRelation::search_full_text()`].
+async fn query_matching<'c, R: ReadRelation>(
+    database: impl PgAcquire<'c>,
+    condition: &str,
+    parameter: String,
+) -> CrudkitResult<R> {
+    let relation_name = R::get_qualified_name();
+    let query_string = format!(
+        "SELECT * FROM {} WHERE {condition}{} ORDER BY {}",
+        R::get_qualified_identifier(),
+        R::get_soft_delete_clause(" AND "),
+        R::get_order_by_clause(),
+    );
+
+    logging::debug!(
+        "Dispatching search multi-SELECT query to database, targeting relation {relation_name}"
+    );
+    logging::trace!("Raw query prior to variable binding: {query_string}");
+
+    match sqlx::query_as(&query_string)
+        .bind(parameter)
+        .fetch_all(&mut *database.acquire().await?)
+        .await
+    {
+        Ok(records) => Ok(R::with_records(records)),
+        Err(e) => Err(CrudkitError::from(e)),
+    }
 }
 
 /// A trait that enables readable tables and views to have their records queried from the database.
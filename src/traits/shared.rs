@@ -1,12 +1,28 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use rand::seq::IndexedRandom;
 use rand::{rng, Rng};
 use serde::Serialize;
 use sqlx::postgres::PgRow;
+use sqlx::{Encode, Postgres, Type};
 
 #[allow(unused_imports)]
 use super::read::{ReadRecord, ReadRelation};
 #[allow(unused_imports)]
 use super::write::{WriteRecord, WriteRelation};
 
+/// The schema in which relations exist unless they set [`Relation::SCHEMA_NAME`].
+///
+/// This is "public", the default schema in Postgres, unless the `CRUDKIT_SCHEMA_NAME` environment
+/// variable is set when the crate is compiled. To use a different schema for every relation in an
+/// application, it can be set in the `[env]` section of `.cargo/config.toml`.
+pub const DEFAULT_SCHEMA_NAME: &str = match option_env!("CRUDKIT_SCHEMA_NAME") {
+    Some(schema_name) => schema_name,
+    None => "public",
+};
+
 /// A trait that allows table and view types to interoperate with and be queried from the database.
 ///
 /// This trait does not do a lot on its own but it, along with [`Record`], provides the
@@ -18,16 +34,25 @@ pub trait Relation: Serialize + Sized + Send + Sync {
     /// The record type which this relation contains a collection of.
     ///
     /// This type and the [`Record::Relation`] type are directly interreferential to allow
-    /// convenient "upcasting" so record types can be used interchangeably with relation types.
+    /// convenient "upcasting" so record types can be used interchangeably with relation types. In
+    /// the derive macros, it is the name of the relation with a `Record` suffix, such as
+    /// `CustomersTableRecord` for `CustomersTable`, unless it is given using `record` in the
+    /// `#[relation(...)]` attribute. Either way, it must be the type held in the `records` field.
     type Record: Record<Relation = Self>;
+    /// The type of the relation's primary key, such as [`i32`] for `serial` columns or [`i64`]
+    /// for `bigserial` columns.
+    ///
+    /// This is the type that IDs are bound as when querying a single record, such as in
+    /// [`ReadRelation::query_one()`]. In the derive macros, it defaults to [`i32`] and can be set
+    /// using `key_type` in the `#[relation(...)]` attribute.
+    type Key: for<'q> Encode<'q, Postgres> + Type<Postgres> + Send + Sync + 'static;
 
     /// The name of the schema in which this relation exists in the database.
     ///
-    /// This defaults to "main" but can be changed in case a relation lives in a different schema.
-    /// The main alternate schema which would be used here is "persistent" for items which are not
-    /// deleted each time the application is run. This will be unnecessary once Fixwise is no longer
-    /// in early development/testing.
-    const SCHEMA_NAME: &str = "main";
+    /// This defaults to [`DEFAULT_SCHEMA_NAME`] but can be changed in case a relation lives in a
+    /// different schema. In the derive macros, it can be set using `schema_name` in the
+    /// `#[relation(...)]` attribute.
+    const SCHEMA_NAME: &str = DEFAULT_SCHEMA_NAME;
     /// The name of the relation in the database.
     ///
     /// It is recommended that all [`Relation`] types should have an identical name to the one they
@@ -39,29 +64,278 @@ pub trait Relation: Serialize + Sized + Send + Sync {
     /// This is used directly in the SQL for querying the relation, so it should be in the format
     /// expected by SQL. For most relations, this will be a standalone column name, but for junction
     /// tables, it will be multiple column names written as a parenthesized, comma-separated list,
-    /// such as `"(column_a, column_b, column_c)"`.
+    /// such as `"(column_a, column_b, column_c)"`. Queries which match a composite key are built from
+    /// [`Relation::PRIMARY_KEY_COLUMNS`] instead.
     const PRIMARY_KEY: &str;
+    /// The names of the columns which make up [`Relation::PRIMARY_KEY`].
+    ///
+    /// This is used to build the `WHERE` clause for composite keys and to check the number of
+    /// values in them, such as in [`ReadRelation::query_one_composite()`]. The derive macro fills
+    /// this in from the `primary_key` attribute. If it is left empty, the columns are parsed from
+    /// [`Relation::PRIMARY_KEY`] instead.
+    const PRIMARY_KEY_COLUMNS: &[&str] = &[];
+    /// The column(s) by which the relation is sorted when querying multiple records, such as in
+    /// [`ReadRelation::query_all()`].
+    ///
+    /// This defaults to [`Relation::PRIMARY_KEY`]. Like the primary key, it is used directly in the
+    /// SQL, so it can include a direction, such as `"name DESC"`.
+    const DEFAULT_ORDER: &str = Self::PRIMARY_KEY;
+    /// The placement of `NULL` values when sorting by [`Relation::DEFAULT_ORDER`].
+    ///
+    /// If this is [`None`], the Postgres default is used, which places `NULL` values last for
+    /// ascending order and first for descending order.
+    const NULLS_ORDER: Option<NullsOrder> = None;
+    /// The strategy used to map field names to column names and to render identifiers in SQL.
+    ///
+    /// This defaults to [`IdentifierStrategy::AsIs`], which writes identifiers unquoted so that
+    /// Postgres folds them to lowercase, as it did before identifier strategies existed. This means
+    /// that a relation or column whose name is a SQL keyword, such as `order`, or which contains
+    /// uppercase letters does not work by default and requires opting into
+    /// [`IdentifierStrategy::QuoteAll`], which is set using `identifiers = "quote_all"` in the
+    /// `relation` attribute of the derive macro. The strategy applies to the schema name, relation
+    /// name, and column names, including single-column primary keys and a [`Relation::DEFAULT_ORDER`]
+    /// which is the primary key. Other orderings are SQL fragments and are used exactly as written.
+    const IDENTIFIER_STRATEGY: IdentifierStrategy = IdentifierStrategy::AsIs;
+    /// The name of the timestamp column which marks a record as deleted, if the relation uses soft
+    /// deletes.
+    ///
+    /// If this is set, [`WriteRelation::delete_one()`] and [`WriteRelation::delete_all()`] set the
+    /// column to `NOW()` instead of deleting records, and reads such as
+    /// [`ReadRelation::query_one()`], [`ReadRelation::query_all()`], and
+    /// [`ReadRelation::query_where()`] only return records where the column is `NULL`. Deleted
+    /// records can be read with [`ReadRelation::query_all_including_deleted()`] and restored with
+    /// [`WriteRelation::restore_one()`]. Other deletes, such as [`WriteRelation::delete_where()`],
+    /// still remove records permanently. In the derive macro, this is set using the `soft_delete`
+    /// field of the `relation` attribute.
+    const SOFT_DELETE_COLUMN: Option<&'static str> = None;
 
     /// Create the relation from a collection of records.
-    // TODO: Take `Into<Vec<Self::Record>>` here
-    fn with_records(records: Vec<Self::Record>) -> Self;
+    ///
+    /// Any collection which converts into a [`Vec`] can be used, such as an array or a slice of
+    /// cloneable records. When passing the result of [`Iterator::collect()`], the [`Vec`] type
+    /// must be given explicitly.
+    fn with_records(records: impl Into<Vec<Self::Record>>) -> Self;
     /// Convert the relation into a collection of records.
     fn take_records(self) -> Vec<Self::Record>;
     /// Borrow the relation's records.
     fn records(&self) -> &[Self::Record];
+    /// Iterate over the relation's records by reference.
+    ///
+    /// The derive macro also implements [`IntoIterator`] for the relation and references to it,
+    /// so records can be iterated over using `for record in relation` or `for record in &relation`.
+    fn iter(&self) -> std::slice::Iter<'_, Self::Record> {
+        self.records().iter()
+    }
+    /// Remove all records which do not satisfy the predicate, keeping the rest in their original
+    /// order.
+    fn retain<F: FnMut(&Self::Record) -> bool>(&mut self, f: F);
 
     /// Get the name of the relation, qualified by its schema namespace.
     fn get_qualified_name() -> String {
         format!("{}.{}", Self::SCHEMA_NAME, Self::RELATION_NAME)
     }
 
-    /// Pick a random record from the relation.
+    /// Get the name of the relation, qualified by its schema namespace, as it should be written in
+    /// SQL according to [`Relation::IDENTIFIER_STRATEGY`].
+    fn get_qualified_identifier() -> String {
+        format!(
+            "{}.{}",
+            Self::render_identifier(Self::SCHEMA_NAME),
+            Self::render_identifier(Self::RELATION_NAME)
+        )
+    }
+
+    /// Get the names of the columns which make up [`Relation::PRIMARY_KEY`].
+    ///
+    /// This returns [`Relation::PRIMARY_KEY_COLUMNS`] if it is not empty. Otherwise, for a
+    /// parenthesized, comma-separated list such as `"(column_a, column_b)"`, this returns each
+    /// column name, and for a standalone column name, this returns only that name.
+    fn get_primary_key_columns() -> Vec<&'static str> {
+        if !Self::PRIMARY_KEY_COLUMNS.is_empty() {
+            return Self::PRIMARY_KEY_COLUMNS.to_vec();
+        }
+
+        Self::PRIMARY_KEY
+            .trim()
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .split(',')
+            .map(str::trim)
+            .collect()
+    }
+
+    /// Get the columns of [`Relation::get_primary_key_columns()`] as a parenthesized,
+    /// comma-separated list, such as `"(column_a, column_b)"`, to be matched against a composite
+    /// key.
+    ///
+    /// Each column is rendered according to [`Relation::IDENTIFIER_STRATEGY`].
+    fn get_primary_key_tuple() -> String {
+        format!("({})", Self::get_rendered_primary_key_columns().join(", "))
+    }
+
+    /// Get [`Relation::PRIMARY_KEY`] as it should be written in SQL, with each column rendered
+    /// according to [`Relation::IDENTIFIER_STRATEGY`].
+    ///
+    /// A single-column key is written as a standalone column name, and a composite key is written
+    /// the same way as in [`Relation::get_primary_key_tuple()`].
+    fn get_primary_key_identifier() -> String {
+        match Self::get_rendered_primary_key_columns().as_slice() {
+            [column_name] => column_name.to_string(),
+            _ => Self::get_primary_key_tuple(),
+        }
+    }
+
+    /// Get the columns of [`Relation::get_primary_key_columns()`], rendered according to
+    /// [`Relation::IDENTIFIER_STRATEGY`].
+    fn get_rendered_primary_key_columns() -> Vec<Cow<'static, str>> {
+        Self::get_primary_key_columns()
+            .into_iter()
+            .map(Self::render_identifier)
+            .collect()
+    }
+
+    /// Render a schema, relation, or column name as it should be written in SQL according to
+    /// [`Relation::IDENTIFIER_STRATEGY`].
+    fn render_identifier(identifier: &str) -> Cow<'_, str> {
+        Self::IDENTIFIER_STRATEGY.render(identifier)
+    }
+
+    /// Get the condition which excludes soft-deleted records, if the relation has a
+    /// [`Relation::SOFT_DELETE_COLUMN`].
+    fn get_soft_delete_condition() -> Option<String> {
+        Self::SOFT_DELETE_COLUMN
+            .map(|column_name| format!("{} IS NULL", Self::render_identifier(column_name)))
+    }
+
+    /// Get the condition which excludes soft-deleted records, preceded by the given keyword, such
+    /// as `" WHERE "` or `" AND "`, or an empty string if the relation does not use soft deletes.
+    fn get_soft_delete_clause(keyword: &str) -> String {
+        Self::get_soft_delete_condition()
+            .map(|condition| format!("{keyword}{condition}"))
+            .unwrap_or_default()
+    }
+
+    /// Get the expression used in the `ORDER BY` clause when querying multiple records.
+    ///
+    /// If [`Relation::DEFAULT_ORDER`] is the primary key, which is the default, it is rendered the
+    /// same way as in [`Relation::get_primary_key_identifier()`].
+    fn get_order_by_clause() -> String {
+        let default_order = match Self::DEFAULT_ORDER == Self::PRIMARY_KEY {
+            true => Self::get_primary_key_identifier(),
+            false => Self::DEFAULT_ORDER.to_owned(),
+        };
+
+        match Self::NULLS_ORDER {
+            Some(NullsOrder::First) => format!("{default_order} NULLS FIRST"),
+            Some(NullsOrder::Last) => format!("{default_order} NULLS LAST"),
+            None => default_order,
+        }
+    }
+
+    /// Pick a random record from the relation, or [`None`] if the relation is empty.
     ///
     /// This is used mostly for randomly generating foreign keys, but can be used elsewhere if
-    /// needed.
-    fn pick_random(&self) -> Self::Record {
-        let records = self.records();
-        records[rng().random_range(0..records.len())].clone()
+    /// needed. It uses the thread-local RNG, so for reproducible results, use
+    /// [`Relation::pick_random_with()`] with a seeded RNG instead.
+    fn pick_random(&self) -> Option<Self::Record> {
+        self.pick_random_with(&mut rng())
+    }
+
+    /// Pick a random record from the relation using the given RNG, or [`None`] if the relation is
+    /// empty.
+    fn pick_random_with<G: Rng + ?Sized>(&self, rng: &mut G) -> Option<Self::Record> {
+        self.records().choose(rng).cloned()
+    }
+
+    /// Convert the relation into a map of its records' keys and values.
+    ///
+    /// This is only available for key/value relations, such as configuration tables, whose record
+    /// type implements [`KeyValueRecord`]. If multiple records share a key, the last one wins.
+    fn to_map(
+        &self,
+    ) -> HashMap<<Self::Record as KeyValueRecord>::Key, <Self::Record as KeyValueRecord>::Value>
+    where
+        Self::Record: KeyValueRecord,
+    {
+        self.records()
+            .iter()
+            .cloned()
+            .map(KeyValueRecord::into_key_value)
+            .collect()
+    }
+
+    /// Create the relation from a map of keys and values.
+    ///
+    /// This is only available for key/value relations, such as configuration tables, whose record
+    /// type implements [`KeyValueRecord`].
+    fn from_map(
+        map: HashMap<
+            <Self::Record as KeyValueRecord>::Key,
+            <Self::Record as KeyValueRecord>::Value,
+        >,
+    ) -> Self
+    where
+        Self::Record: KeyValueRecord,
+    {
+        Self::with_records(
+            map.into_iter()
+                .map(|(key, value)| Self::Record::from_key_value(key, value))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+/// The placement of `NULL` values when sorting a relation, as used by [`Relation::NULLS_ORDER`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullsOrder {
+    /// Place `NULL` values before all other values.
+    First,
+    /// Place `NULL` values after all other values.
+    Last,
+}
+
+/// The strategy used to map a record's field names to column names and to render identifiers in
+/// SQL, as used by [`Relation::IDENTIFIER_STRATEGY`].
+///
+/// Postgres folds unquoted identifiers to lowercase, so a field named `emailAddress` would refer to
+/// a column named `emailaddress` unless it is quoted or renamed. Note that [`sqlx::FromRow`] maps
+/// columns to fields independently, so [`IdentifierStrategy::SnakeCase`] should be paired with
+/// `#[sqlx(rename_all = "snake_case")]` on the record type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentifierStrategy {
+    /// Use field names as column names and write all identifiers unquoted.
+    AsIs,
+    /// Use field names as column names and write all identifiers quoted, preserving their case.
+    ///
+    /// This is also needed for relations or columns whose names are SQL keywords, such as a
+    /// relation named `order`, which cannot be written unquoted.
+    QuoteAll,
+    /// Convert field names to snake case to get column names and write all identifiers unquoted.
+    SnakeCase,
+}
+
+impl IdentifierStrategy {
+    /// Select the column name for a field according to the strategy.
+    ///
+    /// The snake case version of the name is computed by the derive macros, so this only chooses
+    /// between the two. This is meant mostly for auto-implementations.
+    pub const fn select_column_name(
+        self,
+        field_name: &'static str,
+        snake_case_name: &'static str,
+    ) -> &'static str {
+        match self {
+            Self::AsIs | Self::QuoteAll => field_name,
+            Self::SnakeCase => snake_case_name,
+        }
+    }
+
+    /// Render an identifier as it should be written in SQL according to the strategy.
+    pub fn render(self, identifier: &str) -> Cow<'_, str> {
+        match self {
+            Self::AsIs | Self::SnakeCase => Cow::Borrowed(identifier),
+            Self::QuoteAll => Cow::Owned(format!("\"{}\"", identifier.replace('"', "\"\""))),
+        }
     }
 }
 
@@ -79,19 +353,82 @@ pub trait Record:
     /// The relation type which contains a collection of this record type.
     ///
     /// This type and the [`Relation::Record`] type are directly interreferential to allow
-    /// convenient "upcasting" so record types can be used interchangeably with relation types.
+    /// convenient "upcasting" so record types can be used interchangeably with relation types. In
+    /// the derive macros, it is the name of the record without its `Record` suffix, unless it is
+    /// given using `relation` in the `#[record(...)]` attribute.
     type Relation: Relation<Record = Self>;
 
     /// The names of all columns in the database table.
     ///
     /// This would have been a member of [`Relation`], but since the derive macro must rely on
     /// knowledge of the record type's field names, it must be emitted as part of [`Record`].
-    // TODO: Maybe add primary key columns array for use with multi-PK query generation
+    ///
+    /// Most fields correspond to a single column, but in the derive macros, a field of a composite
+    /// value type can contribute several columns with `#[columns(names = [...], binder = ...)]`.
+    /// Such fields are usually read with `#[sqlx(flatten)]`.
+    ///
+    /// A field whose column has a different name can be marked with `#[column(rename = "...")]`,
+    /// in which case the given name is used exactly as written. Since the derive macros do not
+    /// control how records are decoded, such fields must also be marked with the matching
+    /// `#[sqlx(rename = "...")]` for [`sqlx::FromRow`].
+    ///
+    /// Fields which are not columns at all, such as values computed in Rust, can be marked with
+    /// `#[skip]` to leave them out of all generated SQL and of the create and update parameters.
+    /// Such fields must implement [`Default`] and be marked with `#[sqlx(skip)]` as well.
     const COLUMN_NAMES: &[&str];
+    /// The names of all columns which are generated by the database, such as columns declared with
+    /// `GENERATED ALWAYS AS (...) STORED`.
+    ///
+    /// These columns are still read from the database, but they are never included in the column
+    /// list of an `INSERT` or the `SET` clause of an `UPDATE`, as Postgres rejects any value
+    /// written to them. In the derive macros, generated columns are marked with
+    /// `#[generated_column]`, and their fields must implement [`Default`] so that records can be
+    /// constructed from create parameters before the database has computed the real value.
+    const GENERATED_COLUMN_NAMES: &[&str] = &[];
+    /// The expected Postgres type of each column, in the same order as [`Record::COLUMN_NAMES`].
+    ///
+    /// The names match those reported by the `data_type` column of `information_schema.columns`,
+    /// such as `"integer"` or `"timestamp with time zone"`. A type is [`None`] if it could not be
+    /// inferred from the record's field type, in which case it is not checked by
+    /// [`PgDatabase::diff_schema()`](crate::database::PgDatabase::diff_schema).
+    const COLUMN_TYPES: &[Option<&str>] = &[];
+    /// The names of all columns which are allowed to be `NULL`, i.e. those whose field type is an
+    /// [`Option`].
+    const NULLABLE_COLUMN_NAMES: &[&str] = &[];
+
+    /// Get the value of a column by its name, serialized as JSON.
+    ///
+    /// This allows generic code, such as audit tooling or dynamic responses, to introspect a record
+    /// without knowing its concrete type. [`None`] is returned if the record has no column with the
+    /// given name or if its value cannot be serialized.
+    fn get_column(&self, name: &str) -> Option<serde_json::Value>;
+}
+
+/// A trait that allows a two-column record to be treated as a key/value pair.
+///
+/// This is derived as part of [`Record`] when one field is marked with `#[map_key]` and the other
+/// with `#[map_value]`, and it enables [`Relation::to_map()`] and [`Relation::from_map()`] for the
+/// corresponding relation. It is mostly useful for configuration or settings tables.
+pub trait KeyValueRecord: Record {
+    /// The type of the column marked with `#[map_key]`.
+    type Key: Eq + Hash;
+    /// The type of the column marked with `#[map_value]`.
+    type Value;
+
+    /// Split the record into its key and value.
+    fn into_key_value(self) -> (Self::Key, Self::Value);
+    /// Create a record from a key and value.
+    fn from_key_value(key: Self::Key, value: Self::Value) -> Self;
 }
 
-// TODO: Add documentation
+/// A trait that allows the primary key of a record to be read, such as for
+/// [`ReadRelation::query_after()`](crate::traits::read::ReadRelation::query_after).
+///
+/// In the derive macro, the key is read from the field marked with `#[auto_primary_key]` or
+/// `#[manual_primary_key]`, so records with a composite primary key are not supported.
 // ? Should this really be an `Option`?
-pub trait IdentifiableRecord {
-    fn id(&self) -> Option<i32>;
+pub trait IdentifiableRecord: Record {
+    /// Get the primary key of the record, or [`None`] if it has not been assigned yet, such as for
+    /// an auto primary key before the record is inserted.
+    fn id(&self) -> Option<<Self::Relation as Relation>::Key>;
 }
@@ -1,28 +1,48 @@
+use std::borrow::Cow;
 use std::future::Future;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::extract::{Query, State};
-use http::StatusCode;
+use axum::extract::{Json, Query, State};
+use axum::response::{IntoResponse, Response};
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::postgres::PgHasArrayType;
 use sqlx::query_builder::{QueryBuilder, Separated};
-use sqlx::Postgres;
+use sqlx::{Acquire, Decode, Postgres};
 
-use super::id_parameter::IdParameter;
+use super::composite_key::{validate_key_arity, CompositeKey};
+use super::id_parameter::{convert_id, CompositeIdParameter, IdListParameter, IdParameter};
 #[allow(unused_imports)]
 use super::read::{ReadRecord, ReadRelation};
 use super::shared::{Record, Relation};
-use crate::database::{DatabaseState, PgDatabase, SQL_PARAMETER_BIND_LIMIT};
-use crate::error::{Error as CrudkitError, Result as CrudkitResult};
+use crate::database::{DatabaseState, PgAcquire, PgDatabase, SQL_PARAMETER_BIND_LIMIT};
+use crate::error::{Error as CrudkitError, ErrorKind, Result as CrudkitResult};
+use crate::filter::{push_visible_where_clause, Filter};
+use crate::idempotency::{
+    claim_key, delete_expired_key, get_idempotency_key, get_stored_status, store_status,
+};
+use crate::logging;
+use crate::response::{CountResponse, CrudResponse};
+use crate::validate::{validate_params, Validate};
 
 /// A trait that enables writable tables to have their records modified in the database.
 ///
 /// This trait and [`ReadRelation`] are separated because because "relations" can be views, which
 /// are read-only. For view types, only [`ReadRelation`] should be implemented. For table types,
-/// both traits can be implemented safely.
+/// both traits can be implemented safely. Deriving [`Relation`] with `#[relation(view)]` implements
+/// the read traits for a view and prevents this trait from being derived for it.
 ///
 /// This trait gets most of the information it needs to function from the upstream [`Relation`]
 /// trait.
 ///
 /// Also see [`WriteRecord`].
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a writable relation",
+    note = "views marked with `#[relation(view)]` are read-only and cannot be written to"
+)]
 pub trait WriteRelation: Relation {
     /// The record type which this relation contains a collection of.
     ///
@@ -33,6 +53,15 @@ pub trait WriteRelation: Relation {
     /// issues, but the type it refers to must be the same.
     type WriteRecord: WriteRecord<WriteRelation = Self>;
 
+    /// How long a processed idempotency key is remembered by
+    /// [`WriteRelation::create_one_idempotent_handler()`].
+    ///
+    /// A repeated request with the same key after this period is processed as a new request.
+    const IDEMPOTENCY_KEY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+    /// The maximum number of records deleted and returned by a single call to
+    /// [`WriteRelation::delete_where_returning()`].
+    const DELETE_RETURNING_LIMIT: usize = 1000;
+
     /// Create a single record in the database.
     ///
     /// In the future, this will return a proper status code. At the moment, it does not return
@@ -42,53 +71,198 @@ pub trait WriteRelation: Relation {
     /// This is the standard version of this method and should not be used as an Axum route handler.
     /// For the handler method, use [`WriteRelation::create_one_handler()`].
     // * This method does not emit any logs because `SingleInsert::insert()` already emits logs.
-    fn create_one(
-        database: &PgDatabase,
+    fn create_one<'c>(
+        database: impl PgAcquire<'c>,
         create_params: <Self::WriteRecord as WriteRecord>::CreateQueryParameters,
     ) -> impl Future<Output = CrudkitResult<()>> + Send {
-        async { create_params.into().insert(database).await }
+        async move {
+            validate_params(&create_params)?;
+            create_params.into().insert(database).await
+        }
     }
 
     /// Create a single record in the database.
     ///
-    /// In the future, this will return a proper status code. At the moment, it just returns a
-    /// placeholder status code because the underlying [`SingleInsert::insert()`] does not implement
-    /// error handling.
+    /// The response has a `201 Created` status code if the record is inserted, or an
+    /// [`ErrorResponse`](crate::response::ErrorResponse) with the status code of the error
+    /// otherwise.
     ///
     /// This is the Axum route handler version of this method. For the standard method, which can be
     /// called outside of an Axum context, see [`WriteRelation::create_one()`].
     fn create_one_handler<S: DatabaseState>(
         state: State<Arc<S>>,
         Query(create_params): Query<<Self::WriteRecord as WriteRecord>::CreateQueryParameters>,
-    ) -> impl Future<Output = StatusCode> + Send {
+    ) -> impl Future<Output = Response> + Send {
         async move {
             let relation_name = Self::get_qualified_name();
-            log::debug!(
+            logging::debug!(
                 "Request received by single-CREATE endpoint for relation {relation_name}, calling
                 query dispatcher"
             );
 
             match Self::create_one(state.get_database(), create_params).await {
-                Ok(_) => StatusCode::CREATED,
-                Err(e) => StatusCode::from(e),
+                Ok(_) => StatusCode::CREATED.into_response(),
+                Err(e) => state.error_response(&relation_name, e),
             }
         }
     }
 
-    /// Update a single record in the database.
+    /// Create a single record in the database from a JSON request body.
+    ///
+    /// This is the same as [`WriteRelation::create_one_handler()`], but the parameters are given as
+    /// a JSON object in the request body rather than in the query string, which is more convenient
+    /// for records with many fields. A body which is not valid JSON for the parameters is rejected
+    /// by Axum before the database is queried.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`WriteRelation::create_one()`].
+    fn create_one_json_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+        Json(create_params): Json<<Self::WriteRecord as WriteRecord>::CreateQueryParameters>,
+    ) -> impl Future<Output = Response> + Send {
+        Self::create_one_handler(state, Query(create_params))
+    }
+
+    /// Create a single record in the database, returning the record as it was inserted.
+    ///
+    /// This avoids a follow-up query when the caller needs values assigned by the database, such
+    /// as the primary key of a table using `#[auto_primary_key]`.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`WriteRelation::create_one_returning_handler()`].
+    // * This method does not emit any logs because `SingleInsert::insert_returning()` already emits
+    // * logs.
+    fn create_one_returning<'c>(
+        database: impl PgAcquire<'c>,
+        create_params: <Self::WriteRecord as WriteRecord>::CreateQueryParameters,
+    ) -> impl Future<Output = CrudkitResult<Self::WriteRecord>> + Send {
+        async move {
+            validate_params(&create_params)?;
+            create_params.into().insert_returning(database).await
+        }
+    }
+
+    /// Create a single record in the database, responding with the record as it was inserted.
+    ///
+    /// On success, this responds with `201 Created` and the inserted record as JSON.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`WriteRelation::create_one_returning()`].
+    fn create_one_returning_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(create_params): Query<<Self::WriteRecord as WriteRecord>::CreateQueryParameters>,
+    ) -> impl Future<Output = Response> + Send {
+        async move {
+            let relation_name = Self::get_qualified_name();
+            logging::debug!(
+                "Request received by single-CREATE endpoint with RETURNING clause for relation \
+                {relation_name}, calling query dispatcher"
+            );
+
+            match Self::create_one_returning(state.get_database(), create_params).await {
+                Ok(record) => (StatusCode::CREATED, Json(record)).into_response(),
+                Err(e) => state.error_response(&relation_name, e),
+            }
+        }
+    }
+
+    /// Create a single record in the database, using the `Idempotency-Key` request header to make
+    /// retries safe.
+    ///
+    /// If a request with the same key has already been processed for this relation within
+    /// [`WriteRelation::IDEMPOTENCY_KEY_TTL`], the stored status code is returned without a body and
+    /// no record is inserted. Otherwise, the key is claimed and the record is inserted in the same
+    /// transaction, and the resulting status code is stored under the key. Concurrent requests with
+    /// the same key wait for the claim to be committed, so only one of them inserts the record.
+    /// Server errors are not stored, so that the request can be retried. Requests without the
+    /// header behave exactly like [`WriteRelation::create_one_handler()`].
+    ///
+    /// The idempotency key table must exist before this handler is used, see
+    /// [`PgDatabase::create_idempotency_table()`].
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`WriteRelation::create_one()`].
+    fn create_one_idempotent_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+        headers: HeaderMap,
+        Query(create_params): Query<<Self::WriteRecord as WriteRecord>::CreateQueryParameters>,
+    ) -> impl Future<Output = Response> + Send {
+        async move {
+            let relation_name = Self::get_qualified_name();
+            logging::debug!(
+                "Request received by idempotent single-CREATE endpoint for relation \
+                {relation_name}, calling query dispatcher"
+            );
+
+            let database = state.get_database();
+            let Some(key) = get_idempotency_key(&headers) else {
+                return match Self::create_one(database, create_params).await {
+                    Ok(_) => StatusCode::CREATED.into_response(),
+                    Err(e) => state.error_response(&relation_name, e),
+                };
+            };
+
+            let result = async {
+                let mut transaction = database.begin().await?;
+                let ttl = Self::IDEMPOTENCY_KEY_TTL;
+                delete_expired_key(&mut transaction, &relation_name, &key, ttl).await?;
+
+                if !claim_key(&mut transaction, &relation_name, &key).await? {
+                    logging::debug!(
+                        "Idempotency key has already been processed, returning stored status"
+                    );
+                    return get_stored_status(&mut transaction, &relation_name, &key).await;
+                }
+
+                // * The record is inserted within a savepoint, so that a failed insert does not
+                // * abort the transaction holding the claim
+                let mut savepoint = transaction.begin().await?;
+                match Self::create_one(&mut *savepoint, create_params).await {
+                    Ok(()) => {
+                        savepoint.commit().await?;
+                        transaction.commit().await?;
+                        Ok(StatusCode::CREATED)
+                    }
+                    Err(e) => {
+                        let status_code = state.error_status_code(&relation_name, &e);
+                        savepoint.rollback().await?;
+
+                        // * Dropping the transaction releases the claim, so that a request which
+                        // * failed with a server error can be retried
+                        if !status_code.is_server_error() {
+                            store_status(&mut transaction, &relation_name, &key, status_code)
+                                .await?;
+                            transaction.commit().await?;
+                        }
+
+                        Err(e)
+                    }
+                }
+            }
+            .await;
+
+            match result {
+                Ok(status_code) => status_code.into_response(),
+                Err(e) => state.error_response(&relation_name, e),
+            }
+        }
+    }
+
+    /// Update a single record in the database, returning the number of records which were updated.
     ///
-    /// In the future, this will return a proper status code. At the moment, it just returns a
-    /// placeholder status code because the underlying [`WriteRecord::update_one()`] does not
-    /// implement error handling.
+    /// If no record matches the primary key, zero is returned rather than an error, so no-op
+    /// updates can be detected by the caller. For records with a
+    /// [`WriteRecord::VERSION_COLUMN_NAME`], a mismatched version results in an
+    /// [`ErrorKind::Conflict`] error instead.
     ///
     /// This is the standard version of this method and should not be used as an Axum route handler.
     /// For the handler method, use [`WriteRelation::update_one_handler()`].
-    fn update_one(
-        database: &PgDatabase,
+    fn update_one<'c>(
+        database: impl PgAcquire<'c>,
         update_params: <Self::WriteRecord as WriteRecord>::UpdateQueryParameters,
-    ) -> impl Future<Output = CrudkitResult<()>> + Send {
+    ) -> impl Future<Output = CrudkitResult<u64>> + Send {
         let relation_name = Self::get_qualified_name();
-        log::debug!(
+        logging::debug!(
             "Dispatching single-UPDATE query to database, targeting relation {relation_name}"
         );
 
@@ -97,310 +271,1771 @@ pub trait WriteRelation: Relation {
 
     /// Update a single record in the database.
     ///
-    /// In the future, this will return a proper status code. At the moment, it just returns a
-    /// placeholder status code because the underlying [`WriteRecord::update_one()`] does not
-    /// implement error handling.
+    /// The response has a `200 OK` status code if the update succeeds, even if no record matched
+    /// the primary key, or an [`ErrorResponse`](crate::response::ErrorResponse) with the status
+    /// code of the error otherwise.
     ///
     /// This is the Axum route handler version of this method. For the standard method, which can be
     /// called outside of an Axum context, see [`WriteRelation::update_one()`].
     fn update_one_handler<S: DatabaseState>(
         state: State<Arc<S>>,
         Query(update_params): Query<<Self::WriteRecord as WriteRecord>::UpdateQueryParameters>,
-    ) -> impl Future<Output = StatusCode> + Send {
+    ) -> impl Future<Output = Response> + Send {
         async move {
             let relation_name = Self::get_qualified_name();
-            log::debug!(
+            logging::debug!(
                 "Request received by single-UPDATE endpoint for relation {relation_name}, calling
                 query dispatcher"
             );
 
             match Self::update_one(state.get_database(), update_params).await {
-                Ok(_) => StatusCode::OK,
-                Err(e) => StatusCode::from(e),
+                Ok(_) => StatusCode::OK.into_response(),
+                Err(e) => state.error_response(&relation_name, e),
             }
         }
     }
 
-    /// Delete a single record from the database using an identifying key.
+    /// Update a single record in the database from a JSON request body.
+    ///
+    /// This is the same as [`WriteRelation::update_one_handler()`], but the parameters are given as
+    /// a JSON object in the request body rather than in the query string. Unlike a query string,
+    /// a JSON body can set a nullable column to `NULL`, see [`FieldUpdate::SetNull`].
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`WriteRelation::update_one()`].
+    fn update_one_json_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+        Json(update_params): Json<<Self::WriteRecord as WriteRecord>::UpdateQueryParameters>,
+    ) -> impl Future<Output = Response> + Send {
+        Self::update_one_handler(state, Query(update_params))
+    }
+
+    /// Update the same columns of many records in the database using a list of identifying keys,
+    /// returning the number of records which were updated.
+    ///
+    /// The columns are given the same way as in [`WriteRelation::update_one()`], but the primary key
+    /// in the update parameters is ignored, and the records are instead matched using a single
+    /// `WHERE pk = ANY($1)` clause. Keys which do not match any record are ignored, as are
+    /// soft-deleted records. If the record has a [`WriteRecord::VERSION_COLUMN_NAME`], the version
+    /// of each record is incremented, but it is not checked against the version in the update
+    /// parameters.
+    ///
+    /// If the relation has a composite primary key, an [`ErrorKind::InvalidQuery`] error is
+    /// returned.
+    fn update_many<'c, I: IdParameter>(
+        database: impl PgAcquire<'c>,
+        ids: &[I],
+        update_params: <Self::WriteRecord as WriteRecord>::UpdateQueryParameters,
+    ) -> impl Future<Output = CrudkitResult<u64>> + Send
+    where
+        Self::Key: TryFrom<I::Id> + PgHasArrayType,
+    {
+        logging::instrument::<Self, _>("update_many", {
+            let ids: CrudkitResult<Vec<Self::Key>> = ids
+                .iter()
+                .map(|id| convert_id::<Self, _>(id.id()))
+                .collect();
+
+            async move {
+                validate_key_arity::<Self>(1)?;
+                let ids = ids?;
+                validate_params(&update_params)?;
+
+                let relation_name = Self::get_qualified_name();
+                let mut query_builder =
+                    <Self::WriteRecord as WriteRecord>::build_update_many_query(update_params)?;
+                query_builder.push(format!(
+                    " WHERE {} = ANY(",
+                    Self::get_primary_key_identifier()
+                ));
+                query_builder.push_bind(ids);
+                query_builder.push(format!("){}", Self::get_soft_delete_clause(" AND ")));
+
+                logging::debug!(
+                    "Dispatching multi-UPDATE query to database, targeting relation \
+                    {relation_name}"
+                );
+                logging::trace!(
+                    "Raw query prior to variable binding: {}",
+                    query_builder.sql()
+                );
+
+                match query_builder
+                    .build()
+                    .execute(&mut *database.acquire().await?)
+                    .await
+                {
+                    Ok(result) => Ok(result.rows_affected()),
+                    Err(e) => Err(CrudkitError::from(e)),
+                }
+            }
+        })
+    }
+
+    /// Update a single record in the database, returning the record as it is after the update.
     ///
-    /// If the record is successfully deleted from the database, this method returns `true`. If an
-    /// error occurs, such as if the record does not exist in the database, `false` is returned.
+    /// This avoids a follow-up query when the caller needs the current state of the record. If no
+    /// record matches the primary key, an error with a `404 Not Found` status code is returned. If
+    /// none of the non-key columns are given, the database is not queried and an error with a
+    /// `400 Bad Request` status code is returned, the same as in [`WriteRelation::update_one()`].
     ///
     /// This is the standard version of this method and should not be used as an Axum route handler.
-    /// For the handler method, use [`WriteRelation::delete_one_handler()`].
-    fn delete_one<I: IdParameter>(
-        database: &PgDatabase,
-        id: I,
-    ) -> impl Future<Output = CrudkitResult<()>> + Send {
+    /// For the handler method, use [`WriteRelation::update_one_returning_handler()`].
+    fn update_one_returning<'c>(
+        database: impl PgAcquire<'c>,
+        update_params: <Self::WriteRecord as WriteRecord>::UpdateQueryParameters,
+    ) -> impl Future<Output = CrudkitResult<Self::WriteRecord>> + Send {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Dispatching single-UPDATE query with RETURNING clause to database, targeting relation \
+            {relation_name}"
+        );
+
+        <Self::WriteRecord as WriteRecord>::update_one_returning(database, update_params)
+    }
+
+    /// Update a single record in the database, responding with the record as it is after the
+    /// update.
+    ///
+    /// On success, this responds with `200 OK` and the updated record as JSON.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`WriteRelation::update_one_returning()`].
+    fn update_one_returning_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(update_params): Query<<Self::WriteRecord as WriteRecord>::UpdateQueryParameters>,
+    ) -> impl Future<Output = Response> + Send {
         async move {
             let relation_name = Self::get_qualified_name();
-            let query_string = format!(
-                "DELETE FROM {}.{} WHERE {} = $1",
-                Self::SCHEMA_NAME,
-                Self::RELATION_NAME,
-                Self::PRIMARY_KEY,
+            logging::debug!(
+                "Request received by single-UPDATE endpoint with RETURNING clause for relation \
+                {relation_name}, calling query dispatcher"
             );
 
-            log::debug!(
+            CrudResponse(Self::update_one_returning(state.get_database(), update_params).await)
+                .into_response_for(&**state, &relation_name)
+        }
+    }
+
+    /// Delete a single record from the database using an identifying key, returning the number of
+    /// records which were deleted.
+    ///
+    /// If no record matches the key, zero is returned rather than an error.
+    ///
+    /// If the relation has a composite primary key, an [`ErrorKind::InvalidQuery`] error is
+    /// returned. Use [`WriteRelation::delete_one_composite()`] instead. If the relation has a
+    /// [`Relation::SOFT_DELETE_COLUMN`], the record is marked as deleted instead of being removed.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`WriteRelation::delete_one_handler()`].
+    fn delete_one<'c, I: IdParameter>(
+        database: impl PgAcquire<'c>,
+        id: I,
+    ) -> impl Future<Output = CrudkitResult<u64>> + Send
+    where
+        Self::Key: TryFrom<I::Id>,
+    {
+        logging::instrument::<Self, _>("delete_one", async move {
+            validate_key_arity::<Self>(1)?;
+            let id = convert_id::<Self, _>(id.id())?;
+
+            let relation_name = Self::get_qualified_name();
+            let query_string = build_delete_by_key_query::<Self>("$1");
+
+            logging::debug!(
                 "Dispatching single-DELETE query to database, targeting relation {relation_name}"
             );
-            log::trace!("Raw query: {query_string}");
+            logging::trace!("Raw query: {query_string}");
 
             match sqlx::query(&query_string)
-                .bind(id.id() as i32)
-                .execute(&database.connection)
+                .bind(id)
+                .execute(&mut *database.acquire().await?)
                 .await
             {
-                Ok(_) => Ok(()),
+                Ok(result) => Ok(result.rows_affected()),
                 Err(e) => Err(CrudkitError::from(e)),
             }
-        }
+        })
+    }
+
+    /// Delete a single record from the database using an identifying key, returning the record as
+    /// it was before the deletion.
+    ///
+    /// This avoids reading the record before deleting it, which could race with other writes. If no
+    /// record matches the key, an error with a `404 Not Found` status code is returned. The same as
+    /// in [`WriteRelation::delete_one()`], relations with a composite primary key result in an
+    /// [`ErrorKind::InvalidQuery`] error, and soft-deleted relations have the record marked as
+    /// deleted instead, in which case the returned record includes the new
+    /// [`Relation::SOFT_DELETE_COLUMN`] value.
+    fn delete_one_returning<'c, I: IdParameter>(
+        database: impl PgAcquire<'c>,
+        id: I,
+    ) -> impl Future<Output = CrudkitResult<Self::Record>> + Send
+    where
+        Self::Key: TryFrom<I::Id>,
+    {
+        logging::instrument::<Self, _>("delete_one_returning", async move {
+            validate_key_arity::<Self>(1)?;
+            let id = convert_id::<Self, _>(id.id())?;
+
+            let relation_name = Self::get_qualified_name();
+            let query_string = format!("{} RETURNING *", build_delete_by_key_query::<Self>("$1"));
+
+            logging::debug!(
+                "Dispatching single-DELETE query with RETURNING clause to database, targeting \
+                relation {relation_name}"
+            );
+            logging::trace!("Raw query: {query_string}");
+
+            match sqlx::query_as(&query_string)
+                .bind(id)
+                .fetch_one(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(record) => Ok(record),
+                Err(e) => Err(CrudkitError::from(e)),
+            }
+        })
     }
 
     /// Delete a single record from the database using an identifying key.
     ///
-    /// If the record is successfully deleted from the database, this method returns `true`. If an
-    /// error occurs, such as if the record does not exist in the database, `false` is returned.
+    /// The response has a `200 OK` status code if the deletion succeeds, even if no record matched
+    /// the key, or an [`ErrorResponse`](crate::response::ErrorResponse) with the status code of
+    /// the error otherwise.
     ///
     /// This is the Axum route handler version of this method. For the standard method, which can be
     /// called outside of an Axum context, see [`WriteRelation::delete_one()`].
     fn delete_one_handler<I: IdParameter, S: DatabaseState>(
         state: State<Arc<S>>,
         Query(id_param): Query<I>,
-    ) -> impl Future<Output = StatusCode> + Send {
+    ) -> impl Future<Output = Response> + Send
+    where
+        Self::Key: TryFrom<I::Id>,
+    {
         async move {
             let relation_name = Self::get_qualified_name();
-            log::debug!(
+            logging::debug!(
                 "Request received by single-DELETE endpoint for relation {relation_name}, calling
                 query dispatcher"
             );
 
             match Self::delete_one(state.get_database(), id_param).await {
-                Ok(_) => StatusCode::OK,
-                Err(e) => StatusCode::from(e),
+                Ok(_) => StatusCode::OK.into_response(),
+                Err(e) => state.error_response(&relation_name, e),
             }
         }
     }
 
-    /// Delete all records for this relation from the database.
+    /// Restore a single soft-deleted record using an identifying key, clearing its
+    /// [`Relation::SOFT_DELETE_COLUMN`].
     ///
-    /// If the records are successfully deleted from the database, this method returns `true`. If an
-    /// error occurs, `false` is returned.
+    /// If the relation does not use soft deletes or has a composite primary key, an
+    /// [`ErrorKind::InvalidQuery`] error is returned.
     ///
     /// This is the standard version of this method and should not be used as an Axum route handler.
-    /// For the handler method, use [`WriteRelation::delete_all_handler()`].
-    fn delete_all(database: &PgDatabase) -> impl Future<Output = CrudkitResult<()>> + Send {
-        async move {
+    /// For the handler method, use [`WriteRelation::restore_one_handler()`].
+    fn restore_one<'c, I: IdParameter>(
+        database: impl PgAcquire<'c>,
+        id: I,
+    ) -> impl Future<Output = CrudkitResult<()>> + Send
+    where
+        Self::Key: TryFrom<I::Id>,
+    {
+        logging::instrument::<Self, _>("restore_one", async move {
+            validate_key_arity::<Self>(1)?;
+            let id = convert_id::<Self, _>(id.id())?;
+
             let relation_name = Self::get_qualified_name();
-            let query_string = format!("DELETE FROM {}.{}", Self::SCHEMA_NAME, Self::RELATION_NAME);
+            let Some(soft_delete_column) = Self::SOFT_DELETE_COLUMN else {
+                logging::debug!(
+                    "Rejecting restore of record in relation {relation_name}, which does not use \
+                    soft deletes"
+                );
+                return Err(CrudkitError::new(
+                    ErrorKind::InvalidQuery,
+                    StatusCode::BAD_REQUEST,
+                ));
+            };
 
-            log::debug!(
-                "Dispatching multi-DELETE query to database, targeting relation {relation_name}"
+            let query_string = format!(
+                "UPDATE {} SET {} = NULL WHERE {} = $1",
+                Self::get_qualified_identifier(),
+                Self::render_identifier(soft_delete_column),
+                Self::get_primary_key_identifier(),
+            );
+
+            logging::debug!(
+                "Dispatching single-RESTORE query to database, targeting relation {relation_name}"
             );
-            log::trace!("Raw query: {query_string}");
+            logging::trace!("Raw query: {query_string}");
 
             match sqlx::query(&query_string)
-                .execute(&database.connection)
+                .bind(id)
+                .execute(&mut *database.acquire().await?)
                 .await
             {
                 Ok(_) => Ok(()),
                 Err(e) => Err(CrudkitError::from(e)),
             }
-        }
+        })
     }
 
-    /// Delete all records for this relation from the database.
-    ///
-    /// If the records are successfully deleted from the database, this method returns `true`. If an
-    /// error occurs, `false` is returned.
+    /// Restore a single soft-deleted record using an identifying key.
     ///
     /// This is the Axum route handler version of this method. For the standard method, which can be
-    /// called outside of an Axum context, see [`WriteRelation::delete_all()`].
-    fn delete_all_handler<S: DatabaseState>(
+    /// called outside of an Axum context, see [`WriteRelation::restore_one()`].
+    fn restore_one_handler<I: IdParameter, S: DatabaseState>(
         state: State<Arc<S>>,
-    ) -> impl Future<Output = StatusCode> + Send {
+        Query(id_param): Query<I>,
+    ) -> impl Future<Output = Response> + Send
+    where
+        Self::Key: TryFrom<I::Id>,
+    {
         async move {
             let relation_name = Self::get_qualified_name();
-            log::debug!(
-                "Request received by multi-DELETE endpoint for relation {relation_name}, calling
-                query dispatcher"
+            logging::debug!(
+                "Request received by single-RESTORE endpoint for relation {relation_name}, \
+                calling query dispatcher"
             );
 
-            match Self::delete_all(state.get_database()).await {
-                Ok(_) => StatusCode::OK,
-                Err(e) => StatusCode::from(e),
+            match Self::restore_one(state.get_database(), id_param).await {
+                Ok(_) => StatusCode::OK.into_response(),
+                Err(e) => state.error_response(&relation_name, e),
             }
         }
     }
-}
 
-/// A trait that enables writable tables to have their records modified in the database.
-///
-/// This trait and [`ReadRecord`] are separated because because "relations" can be views, which
-/// are read-only. For view record types, only [`ReadRecord`] should be implemented. For table
-/// record types, both traits can be implemented safely.
-///
-/// This trait gets most of the information it needs to function from the upstream [`Relation`]
-/// trait, using the associated type [`Record::Relation`].
-///
-/// Also see [`WriteRelation`].
-pub trait WriteRecord: Record<Relation: WriteRelation> + SingleInsert {
-    /// The relation type which contains a collection of this record type.
+    /// Delete multiple records from the database using a list of identifying keys, returning the
+    /// number of records which were deleted.
     ///
-    /// This type and the [`WriteRelation::WriteRecord`] type are directly interreferential to allow
-    /// convenient "upcasting" so record types can be used interchangeably with relation types.
+    /// The keys are bound as a single array, generating a `WHERE pk = ANY($1)` clause, so the
+    /// records are deleted in one statement. Keys which do not match any record are ignored. To
+    /// treat a deletion which matches no records as an error, use
+    /// [`WriteRelation::delete_many_strict()`]. As with [`WriteRelation::delete_one()`], relations
+    /// with a composite primary key result in an [`ErrorKind::InvalidQuery`] error, and relations
+    /// with a [`Relation::SOFT_DELETE_COLUMN`] have their records marked as deleted instead.
     ///
-    /// This type is declared separately from [`Record::Relation`] because of cyclic dependency
-    /// issues, but the type it refers to must be the same.
-    type WriteRelation: WriteRelation<WriteRecord = Self>;
-    // * Both of the following types require a `Clone` and `Deserialize` implementation to work, but
-    // * since `Deserialize` requires lifetime annotations to be added everywhere, they are left out
-    // * of the trait bounds and instead simply added to the `WriteRecord` derive macro.
-    /// A type used for deserializing the query parameters in a request to a CREATE endpoint, which
-    /// includes all of the table's columns as fields except ID fields that are auto-generated in
-    /// the database.
-    type CreateQueryParameters: Into<Self> + Send + Sync;
-    /// A type used for deserializing the query parameters in a request to an UPDATE endpoint, which
-    /// includes all of the table's columns as optional fields except ID fields that must be
-    /// specified for the database to determine which record to update.
-    type UpdateQueryParameters: Send + Sync;
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`WriteRelation::delete_many_handler()`].
+    fn delete_many<'c, I: IdParameter>(
+        database: impl PgAcquire<'c>,
+        ids: &[I],
+    ) -> impl Future<Output = CrudkitResult<u64>> + Send
+    where
+        Self::Key: TryFrom<I::Id> + PgHasArrayType,
+    {
+        logging::instrument::<Self, _>("delete_many", {
+            let ids: CrudkitResult<Vec<Self::Key>> = ids
+                .iter()
+                .map(|id| convert_id::<Self, _>(id.id()))
+                .collect();
 
-    /// Update a single record in the database.
-    ///
-    /// This method is used by [`WriteRelation::update_one()`] because the [`WriteRelation`] derive
-    /// macro does not have access to the field names and primary keys of the record type, which it
-    /// would need to generate this implementation. In the future, this will likely be fixed by
-    /// using a module-wide macro rather than multiple type-level macros. For now, it is recommended
-    /// to use [`WriteRelation`]'s version of these methods.
-    fn update_one(
-        database: &PgDatabase,
-        update_params: Self::UpdateQueryParameters,
-    ) -> impl Future<Output = CrudkitResult<()>> + Send;
-}
+            async move {
+                validate_key_arity::<Self>(1)?;
+                let ids = ids?;
 
-/// A trait that allows a single record to be inserted to the database.
-///
-/// Though it would be possible to make this trait generic over [`Record`], it is only meant to be
-/// implemented on [`WriteRecord`] types, as items cannot be inserted into a database view.
-///
-/// For bulk-insertion of records, see the related [`BulkInsert`] trait.
-pub trait SingleInsert: Record {
-    /// Get the [`QueryBuilder`] necessary to insert one or more records of data into the database.
-    ///
-    /// This is used by both [`SingleInsert`] and [`BulkInsert`] and is meant mostly for
-    /// auto-implementations.
-    fn get_query_builder<'a>() -> QueryBuilder<'a, Postgres> {
-        QueryBuilder::new(&format!(
-            "INSERT INTO {}.{} ({}) ",
-            Self::Relation::SCHEMA_NAME,
-            Self::Relation::RELATION_NAME,
-            Self::COLUMN_NAMES.join(", ")
-        ))
+                let relation_name = Self::get_qualified_name();
+                let query_string = build_delete_by_key_query::<Self>("ANY($1)");
+
+                logging::debug!(
+                    "Dispatching multi-DELETE query to database, targeting relation \
+                    {relation_name}"
+                );
+                logging::trace!("Raw query: {query_string}");
+
+                match sqlx::query(&query_string)
+                    .bind(ids)
+                    .execute(&mut *database.acquire().await?)
+                    .await
+                {
+                    Ok(result) => Ok(result.rows_affected()),
+                    Err(e) => Err(CrudkitError::from(e)),
+                }
+            }
+        })
     }
 
-    /// Push the record's data into the [`QueryBuilder`] so it can be built and executed against the
-    /// database.
+    /// Delete multiple records from the database using a list of identifying keys, returning the
+    /// number of records which were deleted.
     ///
-    /// This method is used as a function parameter for [`QueryBuilder::push_values`] and should
-    /// only be used within auto-implementations.
-    fn push_column_bindings(builder: Separated<Postgres, &str>, record: Self);
+    /// This is the same as [`WriteRelation::delete_many()`], except that if no records were
+    /// deleted, an [`ErrorKind::UnexpectedQueryResult`] error with a `404 Not Found` status code is
+    /// returned.
+    fn delete_many_strict<'c, I: IdParameter>(
+        database: impl PgAcquire<'c>,
+        ids: &[I],
+    ) -> impl Future<Output = CrudkitResult<u64>> + Send
+    where
+        Self::Key: TryFrom<I::Id> + PgHasArrayType,
+    {
+        let deletion = Self::delete_many(database, ids);
 
-    /// Insert the record into the database.
-    ///
-    /// This should not be used repeatedly for a collection of records. Inserting multiple records
-    /// can be done much more efficiently using [`BulkInsert::insert_all`], which should be
-    /// implemented for any database table type.
-    fn insert(self, database: &PgDatabase) -> impl Future<Output = CrudkitResult<()>> + Send {
         async move {
-            let relation_name = Self::Relation::get_qualified_name();
-            log::debug!(
-                "Dispatching single-INSERT query to database, targeting relation {relation_name}"
-            );
-
-            let mut query_builder = Self::get_query_builder();
-            query_builder.push_values(std::iter::once(self), Self::push_column_bindings);
-
-            let query_string = query_builder.sql();
-            log::trace!("Raw query: {query_string}");
-
-            match query_builder.build().execute(&database.connection).await {
-                Ok(_) => {
-                    log::debug!("Data has been successfully inserted");
-                    Ok(())
-                }
-                Err(e) => {
-                    log::debug!("Failed to insert data to relation {relation_name}");
-                    Err(CrudkitError::from(e))
+            match deletion.await? {
+                0 => {
+                    logging::debug!(
+                        "Strict multi-DELETE query for relation {} did not match any records",
+                        Self::get_qualified_name()
+                    );
+                    Err(CrudkitError::new(
+                        ErrorKind::UnexpectedQueryResult,
+                        StatusCode::NOT_FOUND,
+                    ))
                 }
+                deleted_count => Ok(deleted_count),
             }
         }
     }
-}
 
-/// A trait that allows an entire table of records to be inserted to the database in large batches.
-///
-/// Bulk-inserting items removes the need for establishing a network connection to the database
-/// repeatedly. In initial testing, this proved to be about 20x more efficient than single insertion
-/// when working with large tables. Of course, this is mostly used with synthetic data for testing
-/// purposes, as it is relatively rare for a significant number of records to be inserted at once
-/// during normal operation.
-///
-/// For single-insertion of records, see the related [`SingleInsert`] trait.
-pub trait BulkInsert: WriteRelation<Record: SingleInsert> {
-    /// The amount of records that can be inserted per batch/chunk.
+    /// Delete multiple records from the database using a comma-separated list of identifying keys,
+    /// such as `?ids=1,2,3`, responding with `{ "count": N }`.
     ///
-    /// The batch limit is determined by the number of columns in a table. This is because a single
-    /// SQL statement only supports up to [`u16::MAX`] parameter bindings, and each column takes up
-    /// one parameter. Effectively, this means that tables with more columns are split into more
-    /// batches, making bulk insertion take longer.
-    const CHUNK_SIZE: usize = SQL_PARAMETER_BIND_LIMIT / Self::Record::COLUMN_NAMES.len();
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`WriteRelation::delete_many()`].
+    fn delete_many_handler<I: IdParameter, S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(id_list_param): Query<IdListParameter>,
+    ) -> impl Future<Output = Response> + Send
+    where
+        I::Id: FromStr,
+        Self::Key: TryFrom<I::Id> + PgHasArrayType,
+    {
+        let relation_name = Self::get_qualified_name();
+        logging::debug!(
+            "Request received by multi-DELETE endpoint for relation {relation_name}, calling query \
+            dispatcher"
+        );
 
-    /// Convert a table of records into a series of batches to be inserted to the database.
-    ///
-    /// This method should only be used within auto-implementations.
-    fn into_chunks(self) -> impl Iterator<Item = Vec<Self::Record>> + Send + Sync {
-        let mut iter = self.take_records().into_iter();
-        // TODO: Annotate this code or something, I have very little idea what it does
-        // * This was done because `itertools::IntoChunks` was causing issues with the axum handlers
-        std::iter::from_fn(move || Some(iter.by_ref().take(Self::CHUNK_SIZE).collect()))
-            .take_while(|v: &Vec<_>| !v.is_empty())
+        async move {
+            let ids = match id_list_param.parse::<I>() {
+                Ok(ids) => ids,
+                Err(e) => return state.error_response(&relation_name, e),
+            };
+
+            let deleted_count = Self::delete_many(state.get_database(), &ids).await;
+            CrudResponse(deleted_count.map(|count| CountResponse {
+                count: count as i64,
+            }))
+            .into_response_for(&**state, &relation_name)
+        }
     }
 
-    /// Insert the entire table into the database in a series of batches (or "chunks").
+    /// Delete a single record from the database using a composite primary key, returning the number
+    /// of records which were deleted.
     ///
-    /// This can insert tables of arbitrary size, but each batch is limited in size by number of
-    /// parameters (table column count * record count).
-    fn insert_all(self, database: &PgDatabase) -> impl Future<Output = CrudkitResult<()>> + Send {
-        async move {
+    /// The key is matched the same way as in [`ReadRelation::query_one_composite()`]. If the number
+    /// of values in the key does not match the number of primary key columns, an
+    /// [`ErrorKind::InvalidQuery`] error is returned. If the relation has a
+    /// [`Relation::SOFT_DELETE_COLUMN`], the record is marked as deleted instead of being removed.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`WriteRelation::delete_one_composite_handler()`].
+    fn delete_one_composite<'c, K: CompositeKey>(
+        database: impl PgAcquire<'c>,
+        key: K,
+    ) -> impl Future<Output = CrudkitResult<u64>> + Send {
+        logging::instrument::<Self, _>("delete_one_composite", async move {
+            validate_key_arity::<Self>(K::COLUMN_COUNT)?;
+
             let relation_name = Self::get_qualified_name();
-            log::debug!(
-                "Dispatching multi-INSERT query to database, targeting relation {relation_name}"
-            );
+            let mut query_builder = QueryBuilder::new(format!(
+                "{} WHERE {} = (",
+                build_delete_statement::<Self>(),
+                Self::get_primary_key_tuple(),
+            ));
+            key.push_bindings(&mut query_builder.separated(", "));
+            query_builder.push(format!("){}", Self::get_soft_delete_clause(" AND ")));
 
-            let chunk_count = self.records().len() / Self::CHUNK_SIZE;
-            for (i, chunk) in self.into_chunks().enumerate() {
-                log::debug!("Inserting data chunk {i} of {chunk_count}");
+            logging::debug!(
+                "Dispatching composite-key single-DELETE query to database, targeting relation \
+                {relation_name}"
+            );
+            logging::trace!(
+                "Raw query prior to variable binding: {}",
+                query_builder.sql()
+            );
 
-                let mut query_builder = Self::Record::get_query_builder();
-                query_builder.push_values(chunk, Self::Record::push_column_bindings);
+            match query_builder
+                .build()
+                .execute(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(result) => Ok(result.rows_affected()),
+                Err(e) => Err(CrudkitError::from(e)),
+            }
+        })
+    }
+
+    /// Delete a single record from the database using a composite primary key, given as query
+    /// parameters with a field for each key column.
+    ///
+    /// The response has a `200 OK` status code if the deletion succeeds, even if no record matched
+    /// the key, or an [`ErrorResponse`](crate::response::ErrorResponse) with the status code of
+    /// the error otherwise.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`WriteRelation::delete_one_composite()`].
+    fn delete_one_composite_handler<I: CompositeIdParameter, S: DatabaseState>(
+        state: State<Arc<S>>,
+        Query(key_param): Query<I>,
+    ) -> impl Future<Output = Response> + Send {
+        async move {
+            let relation_name = Self::get_qualified_name();
+            logging::debug!(
+                "Request received by composite-key single-DELETE endpoint for relation \
+                {relation_name}, calling query dispatcher"
+            );
+
+            match Self::delete_one_composite(state.get_database(), key_param.key()).await {
+                Ok(_) => StatusCode::OK.into_response(),
+                Err(e) => state.error_response(&relation_name, e),
+            }
+        }
+    }
+
+    /// Delete all records matching a set of filters from the database, returning the number of
+    /// records deleted.
+    ///
+    /// The filters are validated and combined the same way as in
+    /// [`ReadRelation::query_where()`]. To avoid accidentally deleting an entire relation, an
+    /// empty set of filters results in an [`ErrorKind::InvalidQuery`] error. To delete all records,
+    /// use [`WriteRelation::delete_all()`] instead. If the relation has a
+    /// [`Relation::SOFT_DELETE_COLUMN`], the matching records which have not already been deleted
+    /// are marked as deleted instead.
+    fn delete_where<'c>(
+        database: impl PgAcquire<'c>,
+        filters: &[Filter],
+    ) -> impl Future<Output = CrudkitResult<u64>> + Send {
+        logging::instrument::<Self, _>("delete_where", async move {
+            let relation_name = Self::get_qualified_name();
+            if filters.is_empty() {
+                logging::debug!("Rejecting conditional DELETE without filters for {relation_name}");
+                return Err(CrudkitError::new(
+                    ErrorKind::InvalidQuery,
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+
+            let mut query_builder = QueryBuilder::new(build_delete_statement::<Self>());
+            push_visible_where_clause::<Self::Record>(&mut query_builder, filters)?;
+
+            logging::debug!(
+                "Dispatching conditional DELETE query to database, targeting relation \
+                {relation_name}"
+            );
+            logging::trace!(
+                "Raw query prior to variable binding: {}",
+                query_builder.sql()
+            );
+
+            match query_builder
+                .build()
+                .execute(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(result) => Ok(result.rows_affected()),
+                Err(e) => Err(CrudkitError::from(e)),
+            }
+        })
+    }
+
+    /// Delete records matching a set of filters from the database, returning the deleted records.
+    ///
+    /// At most [`WriteRelation::DELETE_RETURNING_LIMIT`] records are deleted per call, chosen in the
+    /// order of [`Relation::DEFAULT_ORDER`], so that the response is never unbounded. If exactly
+    /// that many records are returned, more matching records may remain, and the method can be
+    /// called again to delete them. Like [`WriteRelation::delete_where()`], an empty set of filters
+    /// results in an [`ErrorKind::InvalidQuery`] error, and records are marked as deleted instead if
+    /// the relation has a [`Relation::SOFT_DELETE_COLUMN`].
+    fn delete_where_returning<'c>(
+        database: impl PgAcquire<'c>,
+        filters: &[Filter],
+    ) -> impl Future<Output = CrudkitResult<Vec<Self::Record>>> + Send {
+        logging::instrument::<Self, _>("delete_where_returning", async move {
+            let relation_name = Self::get_qualified_name();
+            if filters.is_empty() {
+                logging::debug!("Rejecting conditional DELETE without filters for {relation_name}");
+                return Err(CrudkitError::new(
+                    ErrorKind::InvalidQuery,
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+
+            let qualified_identifier = Self::get_qualified_identifier();
+            let primary_key_columns = Self::get_rendered_primary_key_columns().join(", ");
+            // * `RETURNING` does not preserve the order of the subquery, so the deleted records are
+            // * sorted again once they are returned
+            let mut query_builder = QueryBuilder::new(format!(
+                "WITH deleted AS ({} WHERE ({primary_key_columns}) IN (SELECT \
+                {primary_key_columns} FROM {qualified_identifier}",
+                build_delete_statement::<Self>(),
+            ));
+            push_visible_where_clause::<Self::Record>(&mut query_builder, filters)?;
+            query_builder.push(format!(
+                " ORDER BY {0} LIMIT {1}) RETURNING *) SELECT * FROM deleted ORDER BY {0}",
+                Self::get_order_by_clause(),
+                Self::DELETE_RETURNING_LIMIT
+            ));
+
+            logging::debug!(
+                "Dispatching conditional DELETE query with RETURNING clause to database, \
+                targeting relation {relation_name}"
+            );
+            logging::trace!(
+                "Raw query prior to variable binding: {}",
+                query_builder.sql()
+            );
+
+            match query_builder
+                .build_query_as()
+                .fetch_all(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(records) => Ok(records),
+                Err(e) => Err(CrudkitError::from(e)),
+            }
+        })
+    }
+
+    /// Delete all records for this relation from the database, returning the number of records
+    /// which were deleted.
+    ///
+    /// If the relation has a [`Relation::SOFT_DELETE_COLUMN`],
+    /// every record which has not already been deleted is marked as deleted instead.
+    ///
+    /// This is the standard version of this method and should not be used as an Axum route handler.
+    /// For the handler method, use [`WriteRelation::delete_all_handler()`].
+    fn delete_all<'c>(
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<u64>> + Send {
+        logging::instrument::<Self, _>("delete_all", async move {
+            let relation_name = Self::get_qualified_name();
+            let query_string = build_delete_all_query::<Self>();
+
+            logging::debug!(
+                "Dispatching multi-DELETE query to database, targeting relation {relation_name}"
+            );
+            logging::trace!("Raw query: {query_string}");
+
+            match sqlx::query(&query_string)
+                .execute(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(result) => Ok(result.rows_affected()),
+                Err(e) => Err(CrudkitError::from(e)),
+            }
+        })
+    }
+
+    /// Delete all records for this relation from the database, returning the deleted records.
+    ///
+    /// The records are returned in the order of [`Relation::DEFAULT_ORDER`]. Unlike
+    /// [`WriteRelation::delete_where_returning()`], the number of records is not limited, so every
+    /// record in the relation is held in memory at once. If the relation has a
+    /// [`Relation::SOFT_DELETE_COLUMN`], only the records which had not already been deleted are
+    /// marked as deleted and returned.
+    fn delete_all_returning<'c>(
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<Vec<Self::Record>>> + Send {
+        logging::instrument::<Self, _>("delete_all_returning", async move {
+            let relation_name = Self::get_qualified_name();
+            // * `RETURNING` does not guarantee any order, so the deleted records are sorted once
+            // * they are returned
+            let query_string = format!(
+                "WITH deleted AS ({} RETURNING *) SELECT * FROM deleted ORDER BY {}",
+                build_delete_all_query::<Self>(),
+                Self::get_order_by_clause(),
+            );
+
+            logging::debug!(
+                "Dispatching multi-DELETE query with RETURNING clause to database, targeting \
+                relation {relation_name}"
+            );
+            logging::trace!("Raw query: {query_string}");
+
+            match sqlx::query_as(&query_string)
+                .fetch_all(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(records) => Ok(records),
+                Err(e) => Err(CrudkitError::from(e)),
+            }
+        })
+    }
+
+    /// Delete all records for this relation from the database.
+    ///
+    /// The response has a `200 OK` status code if the deletion succeeds, or an
+    /// [`ErrorResponse`](crate::response::ErrorResponse) with the status code of the error
+    /// otherwise.
+    ///
+    /// This is the Axum route handler version of this method. For the standard method, which can be
+    /// called outside of an Axum context, see [`WriteRelation::delete_all()`].
+    fn delete_all_handler<S: DatabaseState>(
+        state: State<Arc<S>>,
+    ) -> impl Future<Output = Response> + Send {
+        async move {
+            let relation_name = Self::get_qualified_name();
+            logging::debug!(
+                "Request received by multi-DELETE endpoint for relation {relation_name}, calling
+                query dispatcher"
+            );
+
+            match Self::delete_all(state.get_database()).await {
+                Ok(_) => StatusCode::OK.into_response(),
+                Err(e) => state.error_response(&relation_name, e),
+            }
+        }
+    }
+}
+
+/// A trait that enables writable tables to have their records modified in the database.
+///
+/// This trait and [`ReadRecord`] are separated because because "relations" can be views, which
+/// are read-only. For view record types, only [`ReadRecord`] should be implemented. For table
+/// record types, both traits can be implemented safely.
+///
+/// This trait gets most of the information it needs to function from the upstream [`Relation`]
+/// trait, using the associated type [`Record::Relation`].
+///
+/// Also see [`WriteRelation`].
+pub trait WriteRecord: Record<Relation: WriteRelation> + SingleInsert {
+    /// The relation type which contains a collection of this record type.
+    ///
+    /// This type and the [`WriteRelation::WriteRecord`] type are directly interreferential to allow
+    /// convenient "upcasting" so record types can be used interchangeably with relation types.
+    ///
+    /// This type is declared separately from [`Record::Relation`] because of cyclic dependency
+    /// issues, but the type it refers to must be the same.
+    type WriteRelation: WriteRelation<WriteRecord = Self>;
+    // * Both of the following types require a `Clone` and `Deserialize` implementation to work, but
+    // * since `Deserialize` requires lifetime annotations to be added everywhere, they are left out
+    // * of the trait bounds and instead simply added to the `WriteRecord` derive macro.
+    /// A type used for deserializing the query parameters in a request to a CREATE endpoint, which
+    /// includes all of the table's columns as fields except ID fields that are auto-generated in
+    /// the database, generated columns, and timestamp columns managed by the library.
+    ///
+    /// In the derive macros, managed timestamp columns are marked with `#[created_timestamp]` or
+    /// `#[updated_timestamp]`. Both are set to `NOW()` when a record is inserted, and updated
+    /// timestamps are set to `NOW()` again whenever any other column of the record is updated.
+    /// Their fields must implement [`Default`], the same as generated columns.
+    ///
+    /// The parameters are checked using [`Validate`] before the record is inserted.
+    type CreateQueryParameters: Into<Self> + Validate + Send + Sync;
+    /// A type used for deserializing the query parameters in a request to an UPDATE endpoint, which
+    /// includes all of the table's columns as optional fields except ID fields that must be
+    /// specified for the database to determine which record to update. Generated columns and
+    /// managed timestamp columns are not included.
+    ///
+    /// Auto-generated primary keys keep their [`Option`] type so that the parameters can also be
+    /// used by [`WriteRelation::update_many()`], which ignores them. Updating a single record
+    /// without one results in an [`ErrorKind::InvalidQuery`] error rather than a panic.
+    ///
+    /// In the derive macros, the type of each updatable field depends on the column:
+    /// - Non-nullable columns use `Option<T>`, where [`None`] leaves the column unchanged.
+    /// - Nullable columns, whose record fields are `Option<T>`, use [`FieldUpdate<T>`], which can
+    ///   also set the column to `NULL`.
+    /// - Columns marked with `#[defaultable]` use [`UpdateValue<T>`], which can also reset the
+    ///   column to its default value in the database.
+    ///
+    /// Fields marked with `#[columns(...)]` are always wrapped in an [`Option`], since all of their
+    /// columns are assigned together.
+    ///
+    /// The parameters are checked using [`Validate`] before the record is updated.
+    type UpdateQueryParameters: Validate + Send + Sync;
+
+    /// The name of the column used for optimistic concurrency control, if the record has one.
+    ///
+    /// In the derive macros, this column is marked with `#[version]`. The expected version must be
+    /// given in the update parameters, and an update only succeeds if it matches the version in the
+    /// database, after which the version is incremented. If it does not match, or the record does
+    /// not exist, an [`ErrorKind::Conflict`] error with a `409 Conflict` status code is returned.
+    /// The version column is not included in the create parameters, so its field must implement
+    /// [`Default`], which is used as the initial version.
+    const VERSION_COLUMN_NAME: Option<&'static str> = None;
+
+    /// Get the [`QueryBuilder`] necessary to update a record in the database.
+    ///
+    /// This only contains the `UPDATE ... SET ` prefix of the query. The `SET` and `WHERE` clauses
+    /// are pushed afterwards with all of their values bound as parameters, the same way
+    /// [`SingleInsert::get_query_builder()`] is used for inserts. This is meant mostly for
+    /// auto-implementations.
+    fn get_update_query_builder<'a>() -> QueryBuilder<'a, Postgres> {
+        QueryBuilder::new(format!(
+            "UPDATE {} SET ",
+            Self::Relation::get_qualified_identifier(),
+        ))
+    }
+
+    /// Build the complete `UPDATE` query for a set of update parameters, starting from
+    /// [`WriteRecord::get_update_query_builder()`].
+    ///
+    /// This is implemented by the derive macro because the [`WriteRelation`] derive macro does not
+    /// have access to the field names and primary keys of the record type, which it would need to
+    /// generate the `SET` and `WHERE` clauses. If none of the non-key columns are being updated, or
+    /// if an auto-generated primary key is [`None`] in the update parameters, an
+    /// [`ErrorKind::InvalidQuery`] error with a `400 Bad Request` status code is returned instead,
+    /// since there would be nothing to write or no record to match. This is meant mostly for
+    /// auto-implementations.
+    fn build_update_query(
+        update_params: Self::UpdateQueryParameters,
+    ) -> CrudkitResult<QueryBuilder<'static, Postgres>>;
+
+    /// Build an `UPDATE` query which sets the same columns on many records, starting from
+    /// [`WriteRecord::get_update_query_builder()`].
+    ///
+    /// This is the same as [`WriteRecord::build_update_query()`], except that the primary key and
+    /// version in the update parameters are ignored and no `WHERE` clause is pushed, so that the
+    /// caller can match the records itself. Version columns are still incremented. This is used by
+    /// [`WriteRelation::update_many()`] and is meant mostly for auto-implementations.
+    fn build_update_many_query(
+        update_params: Self::UpdateQueryParameters,
+    ) -> CrudkitResult<QueryBuilder<'static, Postgres>>;
+
+    /// Update a single record in the database, returning the number of records which were updated.
+    ///
+    /// This method is used by [`WriteRelation::update_one()`]. It is recommended to use
+    /// [`WriteRelation`]'s version of these methods.
+    fn update_one<'c>(
+        database: impl PgAcquire<'c>,
+        update_params: Self::UpdateQueryParameters,
+    ) -> impl Future<Output = CrudkitResult<u64>> + Send {
+        logging::instrument::<Self::Relation, _>("update_one", async move {
+            validate_params(&update_params)?;
+            let mut query_builder = Self::build_update_query(update_params)?;
+            logging::trace!(
+                "Raw query prior to variable binding: {}",
+                query_builder.sql()
+            );
+
+            match query_builder
+                .build()
+                .execute(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(result) => check_version_match::<Self>(result.rows_affected()),
+                Err(e) => Err(CrudkitError::from(e)),
+            }
+        })
+    }
+
+    /// Update a single record in the database, returning the record as it is after the update.
+    ///
+    /// If no record matches the primary key, an error with a `404 Not Found` status code is
+    /// returned, unless the record has a [`WriteRecord::VERSION_COLUMN_NAME`], in which case an
+    /// [`ErrorKind::Conflict`] error is returned instead. This method is used by
+    /// [`WriteRelation::update_one_returning()`]. It is recommended to use [`WriteRelation`]'s
+    /// version of these methods.
+    fn update_one_returning<'c>(
+        database: impl PgAcquire<'c>,
+        update_params: Self::UpdateQueryParameters,
+    ) -> impl Future<Output = CrudkitResult<Self>> + Send {
+        logging::instrument::<Self::Relation, _>("update_one_returning", async move {
+            validate_params(&update_params)?;
+            let mut query_builder = Self::build_update_query(update_params)?;
+            query_builder.push(" RETURNING *");
+            logging::trace!(
+                "Raw query prior to variable binding: {}",
+                query_builder.sql()
+            );
+
+            match query_builder
+                .build_query_as()
+                .fetch_optional(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(Some(record)) => Ok(record),
+                Ok(None) => {
+                    check_version_match::<Self>(0)?;
+                    Err(CrudkitError::new(
+                        ErrorKind::UnexpectedQueryResult,
+                        StatusCode::NOT_FOUND,
+                    ))
+                }
+                Err(e) => Err(CrudkitError::from(e)),
+            }
+        })
+    }
+}
+
+/// Get the query used to delete records whose primary key is equal to the given expression, such
+/// as `$1` for a single record or `ANY($1)` for a list of records.
+///
+/// If the relation has a [`Relation::SOFT_DELETE_COLUMN`], the records are marked as deleted
+/// instead, except for those which have already been deleted, which are left unchanged.
+fn build_delete_by_key_query<R: Relation>(key_expression: &str) -> String {
+    format!(
+        "{} WHERE {} = {key_expression}{}",
+        build_delete_statement::<R>(),
+        R::get_primary_key_identifier(),
+        R::get_soft_delete_clause(" AND "),
+    )
+}
+
+/// Get the query which deletes every record of a relation, or marks every record which has not
+/// already been deleted if the relation has a [`Relation::SOFT_DELETE_COLUMN`].
+fn build_delete_all_query<R: Relation>() -> String {
+    format!(
+        "{}{}",
+        build_delete_statement::<R>(),
+        R::get_soft_delete_clause(" WHERE "),
+    )
+}
+
+/// Get the start of a query which deletes records of a relation, to be followed by a `WHERE`
+/// clause which matches them.
+///
+/// If the relation has a [`Relation::SOFT_DELETE_COLUMN`], the records are marked as deleted
+/// instead, so the clause should also exclude the records which have already been deleted, using
+/// [`Relation::get_soft_delete_clause()`] or [`push_visible_where_clause()`].
+fn build_delete_statement<R: Relation>() -> String {
+    match R::SOFT_DELETE_COLUMN {
+        Some(soft_delete_column) => format!(
+            "UPDATE {} SET {} = NOW()",
+            R::get_qualified_identifier(),
+            R::render_identifier(soft_delete_column),
+        ),
+        None => format!("DELETE FROM {}", R::get_qualified_identifier()),
+    }
+}
+
+/// Check that an update of a record with a [`WriteRecord::VERSION_COLUMN_NAME`] affected a row.
+///
+/// If no rows were affected, the expected version did not match, so an [`ErrorKind::Conflict`]
+/// error is returned. Records without a version column are not checked. Otherwise, the number of
+/// affected rows is returned unchanged.
+fn check_version_match<R: WriteRecord>(rows_affected: u64) -> CrudkitResult<u64> {
+    if let Some(version_column_name) = R::VERSION_COLUMN_NAME {
+        if rows_affected == 0 {
+            logging::debug!(
+                "Rejecting update of relation {} because column {version_column_name} did not \
+                match the expected version",
+                R::Relation::get_qualified_name()
+            );
+            return Err(CrudkitError::new(ErrorKind::Conflict, StatusCode::CONFLICT));
+        }
+    }
+
+    Ok(rows_affected)
+}
+
+/// Get the [`BulkInsert::CHUNK_SIZE`] of a relation, checking at compile time that it is usable.
+///
+/// The derive macro checks the default chunk size itself, but an overridden chunk size can only be
+/// checked where it is used, so this should be used instead of reading the constant directly.
+fn checked_chunk_size<B: BulkInsert>() -> usize {
+    const {
+        assert!(
+            B::CHUNK_SIZE > 0,
+            "`BulkInsert::CHUNK_SIZE` must not be zero"
+        );
+        assert!(
+            B::CHUNK_SIZE
+                <= SQL_PARAMETER_BIND_LIMIT / <B::Record as SingleInsert>::MAX_BINDINGS_PER_RECORD,
+            "`BulkInsert::CHUNK_SIZE` binds more parameters than a single query supports"
+        );
+        B::CHUNK_SIZE
+    }
+}
+
+/// A trait that allows a single record to be inserted to the database.
+///
+/// Though it would be possible to make this trait generic over [`Record`], it is only meant to be
+/// implemented on [`WriteRecord`] types, as items cannot be inserted into a database view.
+///
+/// For bulk-insertion of records, see the related [`BulkInsert`] trait.
+///
+/// In the derive macros, the value of each field is bound directly, so every field type must
+/// implement [`sqlx::Type<Postgres>`] and [`sqlx::Encode`], as well as [`sqlx::Decode`] to be
+/// read back. This includes enums with `#[derive(sqlx::Type)]`, which are encoded as the Postgres
+/// enum named by their `#[sqlx(type_name = "...")]`, or as text with `#[sqlx(type_name = "text")]`.
+/// If the type of a bound value does not match its column, the field can be marked with
+/// `#[column(pg_type = "...")]` to cast each value, such as `$1::order_status`. The same applies to
+/// the values written by [`WriteRecord::update_one()`], and the type is also used by
+/// [`GenerateTable`](crate::schema::GenerateTable).
+pub trait SingleInsert: Record {
+    /// The names of all columns which are kept as they are when an existing record is updated by
+    /// an upsert, such as [`SingleInsert::upsert()`].
+    ///
+    /// These columns are still written when a record is inserted. In the derive macros, they are
+    /// marked with `#[no_overwrite]`, which is useful for columns like `created_at`. Columns marked
+    /// with `#[created_timestamp]` are always included.
+    const NO_OVERWRITE_COLUMN_NAMES: &[&str] = &[];
+
+    /// The maximum number of parameters bound when inserting a single record.
+    ///
+    /// Each column returned by [`SingleInsert::insert_columns()`] is bound as at most one
+    /// parameter. Defaultable columns which are [`None`] are written as `DEFAULT` and bind no
+    /// parameter, so the actual number can be lower, but this is the worst case where every column
+    /// is given. Generated columns are never written, so they are not counted. This is never less
+    /// than one, so that it can always be divided by.
+    const MAX_BINDINGS_PER_RECORD: usize = {
+        let binding_count = Self::COLUMN_NAMES.len() - Self::GENERATED_COLUMN_NAMES.len();
+        if binding_count == 0 {
+            1
+        } else {
+            binding_count
+        }
+    };
+
+    /// Get the names of the columns which are written by an `INSERT`, in the order their values
+    /// are pushed by [`SingleInsert::push_column_bindings()`].
+    ///
+    /// This is every column in [`Record::COLUMN_NAMES`] except those in
+    /// [`Record::GENERATED_COLUMN_NAMES`]. Defaultable columns are still included, since their
+    /// value is written as `DEFAULT` when it is not given. Since an `INSERT` without any columns is
+    /// not valid SQL, the derive macros reject records where this would be empty at compile time.
+    fn insert_columns() -> Vec<&'static str> {
+        Self::COLUMN_NAMES
+            .iter()
+            .copied()
+            .filter(|column_name| !Self::GENERATED_COLUMN_NAMES.contains(column_name))
+            .collect()
+    }
+
+    /// Get the [`QueryBuilder`] necessary to insert one or more records of data into the database.
+    ///
+    /// This is used by both [`SingleInsert`] and [`BulkInsert`] and is meant mostly for
+    /// auto-implementations.
+    fn get_query_builder<'a>() -> QueryBuilder<'a, Postgres> {
+        let column_names: Vec<Cow<str>> = Self::insert_columns()
+            .into_iter()
+            .map(Self::Relation::render_identifier)
+            .collect();
+
+        QueryBuilder::new(&format!(
+            "INSERT INTO {} ({}) ",
+            Self::Relation::get_qualified_identifier(),
+            column_names.join(", ")
+        ))
+    }
+
+    /// Get the `ON CONFLICT ... DO UPDATE` clause which turns an `INSERT` into an upsert.
+    ///
+    /// The conflict target is the set of primary key columns. Every inserted column which is not
+    /// part of the primary key or listed in [`SingleInsert::NO_OVERWRITE_COLUMN_NAMES`] is set to
+    /// its newly inserted value. This is meant mostly for auto-implementations.
+    fn get_upsert_clause() -> String {
+        let primary_key_columns = Self::Relation::get_primary_key_columns();
+        let update_assignments: Vec<String> = Self::insert_columns()
+            .into_iter()
+            .filter(|column_name| {
+                !primary_key_columns.contains(column_name)
+                    && !Self::NO_OVERWRITE_COLUMN_NAMES.contains(column_name)
+            })
+            .map(|column_name| {
+                let column_name = Self::Relation::render_identifier(column_name);
+                format!("{column_name} = EXCLUDED.{column_name}")
+            })
+            .collect();
+        // * A conflicting row is only returned by `RETURNING` if it is updated, so a no-op
+        // * assignment is needed when there are no other columns to update
+        let update_clause = match update_assignments.is_empty() {
+            true => {
+                let column_name = Self::Relation::render_identifier(primary_key_columns[0]);
+                format!("{column_name} = EXCLUDED.{column_name}")
+            }
+            false => update_assignments.join(", "),
+        };
+
+        format!(
+            " ON CONFLICT ({}) DO UPDATE SET {update_clause}",
+            Self::Relation::get_rendered_primary_key_columns().join(", ")
+        )
+    }
+
+    /// Push the record's data into the [`QueryBuilder`] so it can be built and executed against the
+    /// database.
+    ///
+    /// This method is used as a function parameter for [`QueryBuilder::push_values`] and should
+    /// only be used within auto-implementations.
+    ///
+    /// In the derive macros, fields marked with `#[columns(names = [...], binder = ...)]` are pushed
+    /// by calling the binder function, which must have the signature
+    /// `fn<Sep: Display>(&mut Separated<'_, '_, Postgres, Sep>, T)` and push exactly one binding
+    /// per column name, in the same order.
+    fn push_column_bindings(builder: Separated<Postgres, &str>, record: Self);
+
+    /// Insert the record into the database.
+    ///
+    /// This should not be used repeatedly for a collection of records. Inserting multiple records
+    /// can be done much more efficiently using [`BulkInsert::insert_all`], which should be
+    /// implemented for any database table type.
+    fn insert<'c>(
+        self,
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<()>> + Send {
+        logging::instrument::<Self::Relation, _>("insert", async move {
+            let relation_name = Self::Relation::get_qualified_name();
+            logging::debug!(
+                "Dispatching single-INSERT query to database, targeting relation {relation_name}"
+            );
+
+            let mut query_builder = Self::get_query_builder();
+            query_builder.push_values(std::iter::once(self), Self::push_column_bindings);
+
+            let query_string = query_builder.sql();
+            logging::trace!("Raw query: {query_string}");
+
+            match query_builder
+                .build()
+                .execute(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(_) => {
+                    logging::debug!("Data has been successfully inserted");
+                    Ok(())
+                }
+                Err(e) => {
+                    logging::debug!("Failed to insert data to relation {relation_name}");
+                    Err(CrudkitError::from(e))
+                }
+            }
+        })
+    }
+
+    /// Insert a collection of records into the database using multi-row `INSERT` statements.
+    ///
+    /// This is the same as [`BulkInsert::insert_all()`], but it takes the records directly rather
+    /// than a relation, which is convenient for records assembled at runtime. The records are split
+    /// into batches of at most [`SQL_PARAMETER_BIND_LIMIT`] parameters, which are all inserted
+    /// within a single transaction, so either every record is inserted or none of them are. If
+    /// there are no records, nothing is sent to the database.
+    fn insert_many<'c>(
+        records: Vec<Self>,
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<()>> + Send {
+        logging::instrument::<Self::Relation, _>("insert_many", async move {
+            let relation_name = Self::Relation::get_qualified_name();
+            if records.is_empty() {
+                logging::debug!("No records to insert to relation {relation_name}");
+                return Ok(());
+            }
+
+            logging::debug!(
+                "Dispatching multi-INSERT query to database, targeting relation {relation_name}"
+            );
+
+            let mut transaction = database.begin().await?;
+
+            let chunk_size = SQL_PARAMETER_BIND_LIMIT / Self::MAX_BINDINGS_PER_RECORD;
+            let mut records = records.into_iter();
+            loop {
+                let chunk: Vec<Self> = records.by_ref().take(chunk_size).collect();
+                if chunk.is_empty() {
+                    break;
+                }
+
+                let mut query_builder = Self::get_query_builder();
+                query_builder.push_values(chunk, Self::push_column_bindings);
+
+                let query_string = query_builder.sql();
+                logging::trace!("Raw query: {query_string}");
+
+                if let Err(e) = query_builder.build().execute(&mut *transaction).await {
+                    logging::debug!(
+                        "Failed to insert data to relation {relation_name}, rolling back"
+                    );
+                    if let Err(e) = transaction.rollback().await {
+                        logging::error!("Failed to roll back multi-row insertion: {e}");
+                    }
+                    return Err(CrudkitError::from(e));
+                }
+            }
+
+            transaction.commit().await.map_err(CrudkitError::from)?;
+
+            logging::debug!("Data has been successfully inserted");
+
+            Ok(())
+        })
+    }
+
+    /// Insert the record into the database, or update the existing record if one already exists
+    /// with the same primary key.
+    ///
+    /// When a record is updated, every column except the primary key and those in
+    /// [`SingleInsert::NO_OVERWRITE_COLUMN_NAMES`] is overwritten, see
+    /// [`SingleInsert::get_upsert_clause()`].
+    fn upsert<'c>(
+        self,
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<()>> + Send {
+        logging::instrument::<Self::Relation, _>("upsert", async move {
+            let relation_name = Self::Relation::get_qualified_name();
+            logging::debug!(
+                "Dispatching single-UPSERT query to database, targeting relation {relation_name}"
+            );
+
+            let mut query_builder = Self::get_query_builder();
+            query_builder.push_values(std::iter::once(self), Self::push_column_bindings);
+            query_builder.push(Self::get_upsert_clause());
+
+            let query_string = query_builder.sql();
+            logging::trace!("Raw query: {query_string}");
+
+            match query_builder
+                .build()
+                .execute(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(_) => {
+                    logging::debug!("Data has been successfully upserted");
+                    Ok(())
+                }
+                Err(e) => {
+                    logging::debug!("Failed to upsert data to relation {relation_name}");
+                    Err(CrudkitError::from(e))
+                }
+            }
+        })
+    }
+
+    /// Insert the record into the database, returning the record as it was inserted.
+    ///
+    /// The returned record includes any values assigned by the database, such as defaultable or
+    /// generated columns.
+    fn insert_returning<'c>(
+        self,
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<Self>> + Send {
+        logging::instrument::<Self::Relation, _>("insert_returning", async move {
+            let relation_name = Self::Relation::get_qualified_name();
+            logging::debug!(
+                "Dispatching single-INSERT query with RETURNING clause to database, targeting \
+                relation {relation_name}"
+            );
+
+            let mut query_builder = Self::get_query_builder();
+            query_builder.push_values(std::iter::once(self), Self::push_column_bindings);
+            query_builder.push(" RETURNING *");
+
+            let query_string = query_builder.sql();
+            logging::trace!("Raw query: {query_string}");
+
+            match query_builder
+                .build_query_as()
+                .fetch_one(&mut *database.acquire().await?)
+                .await
+            {
+                Ok(record) => {
+                    logging::debug!("Data has been successfully inserted");
+                    Ok(record)
+                }
+                Err(e) => {
+                    logging::debug!("Failed to insert data to relation {relation_name}");
+                    Err(CrudkitError::from(e))
+                }
+            }
+        })
+    }
+}
+
+/// A trait that allows an entire table of records to be inserted to the database in large batches.
+///
+/// Bulk-inserting items removes the need for establishing a network connection to the database
+/// repeatedly. In initial testing, this proved to be about 20x more efficient than single insertion
+/// when working with large tables. Of course, this is mostly used with synthetic data for testing
+/// purposes, as it is relatively rare for a significant number of records to be inserted at once
+/// during normal operation.
+///
+/// For single-insertion of records, see the related [`SingleInsert`] trait.
+pub trait BulkInsert: WriteRelation<Record: SingleInsert> {
+    /// The amount of records that can be inserted per batch/chunk.
+    ///
+    /// The batch limit is determined by the number of columns in a table. This is because a single
+    /// SQL statement only supports up to [`u16::MAX`] parameter bindings, and each written column
+    /// takes up at most one parameter, as counted by [`SingleInsert::MAX_BINDINGS_PER_RECORD`].
+    /// Effectively, this means that tables with more columns are split into more batches, making
+    /// bulk insertion take longer. The derive macro checks at compile time that this is not zero,
+    /// and that the records have at least one column.
+    ///
+    /// Some setups need smaller statements than Postgres allows, such as when connecting through
+    /// a pooler like PgBouncer. In that case, the trait can be implemented by hand instead of
+    /// derived, overriding this with a smaller value:
+    ///
+    /// ```ignore
+    /// impl BulkInsert for CustomersTable {
+    ///     const CHUNK_SIZE: usize = 1000;
+    /// }
+    /// ```
+    ///
+    /// The value must be at least one, and must not exceed the default, since larger chunks would
+    /// bind more parameters than a statement supports. This is checked at compile time wherever the
+    /// bulk insertion methods are used.
+    const CHUNK_SIZE: usize = SQL_PARAMETER_BIND_LIMIT / Self::Record::MAX_BINDINGS_PER_RECORD;
+
+    /// Convert a table of records into a series of batches to be inserted to the database.
+    ///
+    /// This method should only be used within auto-implementations.
+    fn into_chunks(self) -> impl Iterator<Item = Vec<Self::Record>> + Send + Sync {
+        let mut iter = self.take_records().into_iter();
+        // TODO: Annotate this code or something, I have very little idea what it does
+        // * This was done because `itertools::IntoChunks` was causing issues with the axum handlers
+        std::iter::from_fn(move || Some(iter.by_ref().take(checked_chunk_size::<Self>()).collect()))
+            .take_while(|v: &Vec<_>| !v.is_empty())
+    }
+
+    /// Insert the entire table into the database in a series of batches (or "chunks").
+    ///
+    /// This can insert tables of arbitrary size, but each batch is limited in size by number of
+    /// parameters (table column count * record count). Every batch is inserted within a single
+    /// transaction, which is only committed once all of them have succeeded. If any batch fails, the
+    /// transaction is rolled back, so either the whole table is inserted or none of it is.
+    ///
+    /// To track the progress of a large insertion, use [`BulkInsert::insert_all_with_progress()`].
+    fn insert_all<'c>(
+        self,
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<()>> + Send {
+        self.insert_all_with_progress(database, |_, _| {})
+    }
+
+    /// Insert the entire table into the database in a series of batches, calling `on_chunk` after
+    /// each batch is inserted.
+    ///
+    /// The batches are inserted the same way as in [`BulkInsert::insert_all()`]. The callback is
+    /// given the number of batches which have been inserted so far and the total number of
+    /// batches, so it can be used to render a progress bar. Since every batch is inserted within a
+    /// single transaction, none of the records are visible to other connections until the last
+    /// batch has been inserted and the transaction is committed. If a batch fails, the callback is
+    /// not called for it.
+    fn insert_all_with_progress<'c>(
+        self,
+        database: impl PgAcquire<'c>,
+        mut on_chunk: impl FnMut(usize, usize) + Send,
+    ) -> impl Future<Output = CrudkitResult<()>> + Send {
+        logging::instrument::<Self, _>("insert_all", async move {
+            let relation_name = Self::get_qualified_name();
+            logging::debug!(
+                "Dispatching multi-INSERT query to database, targeting relation {relation_name}"
+            );
+
+            let mut transaction = database.begin().await?;
+
+            let chunk_count = self.records().len().div_ceil(checked_chunk_size::<Self>());
+            for (i, chunk) in self.into_chunks().enumerate() {
+                logging::debug!("Inserting data chunk {} of {chunk_count}", i + 1);
+
+                let mut query_builder = Self::Record::get_query_builder();
+                query_builder.push_values(chunk, Self::Record::push_column_bindings);
+
+                let query_string = query_builder.sql();
+                logging::trace!("Raw query: {query_string}");
+
+                if let Err(e) = query_builder.build().execute(&mut *transaction).await {
+                    logging::error!(
+                        "Failed to insert data chunk {} of {chunk_count} to relation \
+                        {relation_name}, rolling back all chunks",
+                        i + 1
+                    );
+                    // * Dropping the transaction would also roll it back, but doing so explicitly
+                    // * returns the connection to the pool in a clean state immediately
+                    if let Err(e) = transaction.rollback().await {
+                        logging::error!("Failed to roll back bulk insertion: {e}");
+                    }
+                    return Err(CrudkitError::from(e));
+                }
+
+                logging::debug!("Data chunk has been successfully inserted");
+                on_chunk(i + 1, chunk_count);
+            }
+
+            transaction.commit().await.map_err(CrudkitError::from)?;
+
+            logging::debug!("All data chunks have been successfully inserted");
+
+            Ok(())
+        })
+    }
+
+    /// Insert the entire table into the database in a series of batches, dispatching up to
+    /// `max_concurrency` batches at a time.
+    ///
+    /// This uses the same batches as [`BulkInsert::insert_all()`], but each batch is inserted using
+    /// its own connection from the pool, so the concurrency is also limited to the maximum size of
+    /// the pool. A concurrency of zero is treated as one.
+    ///
+    /// Unlike [`BulkInsert::insert_all()`], the batches are not inserted within a transaction and the
+    /// order in which they are inserted is not guaranteed. If any batch fails, no further batches
+    /// are dispatched and the error is returned, but any batches which have already been inserted
+    /// remain in the database. If atomicity is needed, use [`BulkInsert::insert_all()`] instead.
+    fn insert_all_concurrent(
+        self,
+        database: &PgDatabase,
+        max_concurrency: usize,
+    ) -> impl Future<Output = CrudkitResult<()>> + Send {
+        logging::instrument::<Self, _>("insert_all_concurrent", async move {
+            let relation_name = Self::get_qualified_name();
+            let max_connections = database.connection.options().get_max_connections() as usize;
+            let max_concurrency = max_concurrency.clamp(1, max_connections.max(1));
+            logging::debug!(
+                "Dispatching concurrent multi-INSERT query to database with a concurrency of \
+                {max_concurrency}, targeting relation {relation_name}"
+            );
+
+            let relation_name = &relation_name;
+            let chunk_count = self.records().len().div_ceil(checked_chunk_size::<Self>());
+            stream::iter(self.into_chunks().enumerate())
+                .map(|(i, chunk)| async move {
+                    logging::debug!("Inserting data chunk {} of {chunk_count}", i + 1);
+
+                    let mut query_builder = Self::Record::get_query_builder();
+                    query_builder.push_values(chunk, Self::Record::push_column_bindings);
+
+                    let query_string = query_builder.sql();
+                    logging::trace!("Raw query: {query_string}");
+
+                    if let Err(e) = query_builder
+                        .build()
+                        .execute(&mut *database.acquire().await?)
+                        .await
+                    {
+                        logging::error!(
+                            "Failed to insert data chunk {} of {chunk_count} to relation \
+                            {relation_name}",
+                            i + 1
+                        );
+                        return Err(CrudkitError::from(e));
+                    }
+
+                    logging::debug!("Data chunk {} has been successfully inserted", i + 1);
+
+                    Ok(())
+                })
+                .buffer_unordered(max_concurrency)
+                .try_collect::<()>()
+                .await?;
+
+            logging::debug!("All data chunks have been successfully inserted");
+
+            Ok(())
+        })
+    }
+
+    /// Insert or update the entire table in the database in a series of batches.
+    ///
+    /// Records are inserted the same way as in [`BulkInsert::insert_all()`], so every batch is
+    /// written within a single transaction and either the whole table is upserted or none of it is.
+    /// If a record conflicts with an existing record on [`Relation::PRIMARY_KEY`], every other
+    /// column of the existing record is updated instead, except for those in
+    /// [`SingleInsert::NO_OVERWRITE_COLUMN_NAMES`], see [`SingleInsert::get_upsert_clause()`]. This
+    /// makes it possible to re-run a data load without failing on records which already exist.
+    ///
+    /// As with any `INSERT ... ON CONFLICT DO UPDATE`, a chunk which contains the same primary key
+    /// more than once fails to be written. To find out which records were inserted and which were
+    /// updated, use [`BulkInsert::upsert_all_returning()`] instead.
+    fn upsert_all<'c>(
+        self,
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<()>> + Send {
+        logging::instrument::<Self, _>("upsert_all", async move {
+            let relation_name = Self::get_qualified_name();
+            logging::debug!(
+                "Dispatching multi-UPSERT query to database, targeting relation {relation_name}"
+            );
+
+            let upsert_clause = Self::Record::get_upsert_clause();
+
+            let mut transaction = database.begin().await?;
+
+            let chunk_count = self.records().len().div_ceil(checked_chunk_size::<Self>());
+            for (i, chunk) in self.into_chunks().enumerate() {
+                logging::debug!("Upserting data chunk {} of {chunk_count}", i + 1);
+
+                let mut query_builder = Self::Record::get_query_builder();
+                query_builder.push_values(chunk, Self::Record::push_column_bindings);
+                query_builder.push(&upsert_clause);
 
                 let query_string = query_builder.sql();
-                log::trace!("Raw query: {query_string}");
+                logging::trace!("Raw query: {query_string}");
 
-                if let Err(e) = query_builder.build().execute(&database.connection).await {
-                    log::error!(
-                        "Failed to insert data chunk {i} of {chunk_count} to relation 
-                        {relation_name}"
+                if let Err(e) = query_builder.build().execute(&mut *transaction).await {
+                    logging::error!(
+                        "Failed to upsert data chunk {} of {chunk_count} to relation \
+                        {relation_name}, rolling back all chunks",
+                        i + 1
                     );
+                    if let Err(e) = transaction.rollback().await {
+                        logging::error!("Failed to roll back bulk upsertion: {e}");
+                    }
                     return Err(CrudkitError::from(e));
                 }
 
-                log::debug!("Data chunk has been successfully inserted");
+                logging::debug!("Data chunk has been successfully upserted");
             }
 
-            log::debug!("All data chunks have been successfully inserted");
+            transaction.commit().await.map_err(CrudkitError::from)?;
+
+            logging::debug!("All data chunks have been successfully upserted");
 
             Ok(())
+        })
+    }
+
+    /// Insert or update the entire table in the database in a series of batches, returning the
+    /// primary key of each record along with whether it was inserted.
+    ///
+    /// Records are written the same way as in [`BulkInsert::upsert_all()`]. Each returned pair
+    /// contains the primary key of a record and `true` if it was inserted or `false` if it was
+    /// updated, determined using `RETURNING (xmax = 0)`. Pairs are returned in the order in which
+    /// the database wrote them.
+    ///
+    /// This is only supported for relations with a single primary key column. For composite
+    /// primary keys, an [`ErrorKind::InvalidQuery`] error is returned.
+    fn upsert_all_returning<'c>(
+        self,
+        database: impl PgAcquire<'c>,
+    ) -> impl Future<Output = CrudkitResult<Vec<(Self::Key, bool)>>> + Send
+    where
+        Self::Key: for<'r> Decode<'r, Postgres> + Unpin,
+    {
+        logging::instrument::<Self, _>("upsert_all_returning", async move {
+            validate_key_arity::<Self>(1)?;
+
+            let relation_name = Self::get_qualified_name();
+            logging::debug!(
+                "Dispatching multi-UPSERT query to database, targeting relation {relation_name}"
+            );
+
+            let upsert_clause = Self::Record::get_upsert_clause();
+
+            let mut transaction = database.begin().await?;
+
+            let mut upserted_keys = Vec::with_capacity(self.records().len());
+            let chunk_count = self.records().len().div_ceil(checked_chunk_size::<Self>());
+            for (i, chunk) in self.into_chunks().enumerate() {
+                logging::debug!("Upserting data chunk {} of {chunk_count}", i + 1);
+
+                let mut query_builder = Self::Record::get_query_builder();
+                query_builder.push_values(chunk, Self::Record::push_column_bindings);
+                query_builder.push(format!(
+                    "{upsert_clause} RETURNING {}, (xmax = 0) AS inserted",
+                    Self::get_primary_key_identifier(),
+                ));
+
+                let query_string = query_builder.sql();
+                logging::trace!("Raw query: {query_string}");
+
+                match query_builder
+                    .build_query_as::<(Self::Key, bool)>()
+                    .fetch_all(&mut *transaction)
+                    .await
+                {
+                    Ok(chunk_keys) => upserted_keys.extend(chunk_keys),
+                    Err(e) => {
+                        logging::error!(
+                            "Failed to upsert data chunk {} of {chunk_count} to relation \
+                            {relation_name}, rolling back all chunks",
+                            i + 1
+                        );
+                        if let Err(e) = transaction.rollback().await {
+                            logging::error!("Failed to roll back bulk upsertion: {e}");
+                        }
+                        return Err(CrudkitError::from(e));
+                    }
+                }
+
+                logging::debug!("Data chunk has been successfully upserted");
+            }
+
+            transaction.commit().await.map_err(CrudkitError::from)?;
+
+            logging::debug!("All data chunks have been successfully upserted");
+
+            Ok(upserted_keys)
+        })
+    }
+
+    /// Insert the entire table into the database on a best-effort basis, skipping any records
+    /// which fail to insert rather than aborting the whole operation.
+    ///
+    /// All inserts are performed inside a single transaction. Each unit of work, either a chunk or a
+    /// single record depending on the [`SavepointGranularity`], is wrapped in a savepoint. If a
+    /// unit fails to insert, only that unit is rolled back and the remaining units are still
+    /// inserted. The returned [`BulkInsertReport`] lists every unit which was skipped.
+    ///
+    /// Errors which are not caused by an individual unit, such as failing to begin or commit the
+    /// transaction, still cause the entire operation to fail, in which case nothing is inserted.
+    fn insert_all_best_effort<'c>(
+        self,
+        database: impl PgAcquire<'c>,
+        granularity: SavepointGranularity,
+    ) -> impl Future<Output = CrudkitResult<BulkInsertReport>> + Send {
+        logging::instrument::<Self, _>("insert_all_best_effort", async move {
+            let relation_name = Self::get_qualified_name();
+            logging::debug!(
+                "Dispatching best-effort multi-INSERT query to database, targeting relation \
+                {relation_name}"
+            );
+
+            let units: Vec<Vec<Self::Record>> = match granularity {
+                SavepointGranularity::Chunk => self.into_chunks().collect(),
+                SavepointGranularity::Record => self
+                    .take_records()
+                    .into_iter()
+                    .map(|record| vec![record])
+                    .collect(),
+            };
+
+            let mut transaction = database.begin().await?;
+            let mut report = BulkInsertReport::default();
+            let mut first_record_index = 0;
+
+            for unit in units {
+                let record_count = unit.len();
+
+                sqlx::query("SAVEPOINT crudkit_bulk_insert")
+                    .execute(&mut *transaction)
+                    .await
+                    .map_err(CrudkitError::from)?;
+
+                let mut query_builder = Self::Record::get_query_builder();
+                query_builder.push_values(unit, Self::Record::push_column_bindings);
+
+                let query_string = query_builder.sql();
+                logging::trace!("Raw query: {query_string}");
+
+                match query_builder.build().execute(&mut *transaction).await {
+                    Ok(_) => {
+                        sqlx::query("RELEASE SAVEPOINT crudkit_bulk_insert")
+                            .execute(&mut *transaction)
+                            .await
+                            .map_err(CrudkitError::from)?;
+                        report.inserted_count += record_count;
+                    }
+                    Err(e) => {
+                        logging::debug!(
+                            "Failed to insert records {first_record_index} through {} to \
+                            relation {relation_name}, rolling back to savepoint",
+                            first_record_index + record_count - 1
+                        );
+
+                        sqlx::query("ROLLBACK TO SAVEPOINT crudkit_bulk_insert")
+                            .execute(&mut *transaction)
+                            .await
+                            .map_err(CrudkitError::from)?;
+                        report.skipped.push(SkippedInsert {
+                            first_record_index,
+                            record_count,
+                            error: CrudkitError::from(e),
+                        });
+                    }
+                }
+
+                first_record_index += record_count;
+            }
+
+            transaction.commit().await.map_err(CrudkitError::from)?;
+
+            logging::debug!(
+                "Best-effort insertion finished with {} records inserted and {} units skipped",
+                report.inserted_count,
+                report.skipped.len()
+            );
+
+            Ok(report)
+        })
+    }
+}
+
+/// The new value of a nullable column in [`WriteRecord::UpdateQueryParameters`].
+///
+/// When deserialized, a missing value is [`FieldUpdate::Unchanged`], a null value is
+/// [`FieldUpdate::SetNull`], and any other value is [`FieldUpdate::Set`]. Query strings cannot
+/// contain null values, so [`FieldUpdate::SetNull`] is only deserialized from formats such as JSON,
+/// see [`WriteRelation::update_one_json_handler()`]. When serialized, a value is only written for
+/// [`FieldUpdate::Set`], and the other variants are written as null.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FieldUpdate<T> {
+    /// Leave the column unchanged.
+    #[default]
+    Unchanged,
+    /// Set the column to the given value.
+    Set(T),
+    /// Set the column to `NULL`.
+    SetNull,
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for FieldUpdate<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(value) => Self::Set(value),
+            None => Self::SetNull,
+        })
+    }
+}
+
+impl<T: Serialize> Serialize for FieldUpdate<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Set(value) => serializer.serialize_some(value),
+            _ => serializer.serialize_none(),
         }
     }
 }
+
+/// The new value of a defaultable column in [`WriteRecord::UpdateQueryParameters`].
+///
+/// When deserialized, a given value is [`UpdateValue::Set`] and a missing value is
+/// [`UpdateValue::Keep`]. Since the column's default value can also be a valid value for its type,
+/// [`UpdateValue::Default`] can only be used when constructing the parameters directly. When
+/// serialized, a value is only written for [`UpdateValue::Set`], and the other variants are written
+/// as null.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum UpdateValue<T> {
+    /// Leave the column unchanged.
+    #[default]
+    Keep,
+    /// Set the column to the given value.
+    Set(T),
+    /// Set the column to its default value using `DEFAULT`.
+    Default,
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for UpdateValue<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::Set)
+    }
+}
+
+impl<T: Serialize> Serialize for UpdateValue<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Set(value) => serializer.serialize_some(value),
+            _ => serializer.serialize_none(),
+        }
+    }
+}
+
+/// The unit of work which is wrapped in a savepoint by [`BulkInsert::insert_all_best_effort()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SavepointGranularity {
+    /// Wrap each chunk of up to [`BulkInsert::CHUNK_SIZE`] records in a savepoint.
+    ///
+    /// This is much faster, but a single failing record causes its entire chunk to be skipped.
+    Chunk,
+    /// Wrap each record in a savepoint, so that only the failing records themselves are skipped.
+    Record,
+}
+
+/// The outcome of a best-effort bulk insertion using [`BulkInsert::insert_all_best_effort()`].
+#[derive(Debug, Default)]
+pub struct BulkInsertReport {
+    /// The number of records which were successfully inserted.
+    pub inserted_count: usize,
+    /// The units which failed to insert and were rolled back.
+    pub skipped: Vec<SkippedInsert>,
+}
+
+/// A unit of records which failed to insert and was skipped during a best-effort bulk insertion.
+#[derive(Debug)]
+pub struct SkippedInsert {
+    /// The index of the first skipped record, in the order the records were held by the relation.
+    pub first_record_index: usize,
+    /// The number of records which were skipped, starting from [`SkippedInsert::first_record_index`].
+    pub record_count: usize,
+    /// The error which caused the unit to be skipped.
+    pub error: CrudkitError,
+}
@@ -0,0 +1,154 @@
+use http::StatusCode;
+
+use crate::error::{Error as CrudkitError, ErrorKind, Result as CrudkitResult};
+use crate::logging;
+#[allow(unused_imports)]
+use crate::traits::write::{WriteRecord, WriteRelation};
+
+/// A trait for checking the parameters of a write before it is sent to the database.
+///
+/// This is required for [`WriteRecord::CreateQueryParameters`] and
+/// [`WriteRecord::UpdateQueryParameters`], and is checked by the methods of [`WriteRelation`]
+/// which create or update records, such as [`WriteRelation::create_one()`]. Invalid parameters
+/// result in an [`ErrorKind::InvalidQuery`] error with a `400 Bad Request` status code, and the
+/// description of the problem as its [`CrudkitError::message`].
+///
+/// In the derive macros, the implementation accepts all parameters. With the `validator` feature,
+/// fields of the record can instead be annotated with [`validator`] attributes such as
+/// `#[validate(email)]`, which are checked for both parameter types. For fields in the update
+/// parameters, only values which are being set are checked. To write the implementations by hand
+/// instead, mark the record with `#[custom_validation]`.
+pub trait Validate {
+    /// Check the parameters, returning a description of the problem if they are invalid.
+    ///
+    /// The description is returned to the client, so it should not contain the values of the
+    /// parameters.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Check the parameters of a write, converting a failure into an [`ErrorKind::InvalidQuery`]
+/// error.
+pub(crate) fn validate_params<P: Validate>(params: &P) -> CrudkitResult<()> {
+    params.validate().map_err(|message| {
+        logging::debug!("Rejecting invalid write parameters: {message}");
+        CrudkitError::new(ErrorKind::InvalidQuery, StatusCode::BAD_REQUEST).with_message(message)
+    })
+}
+
+/// Describe the errors found by [`validator::Validate`], for use in an implementation of
+/// [`Validate`].
+///
+/// Each invalid field is listed along with the messages of its errors, or their codes if they have
+/// no message, such as `email_address: email`. The values of the fields are not included.
+#[cfg(feature = "validator")]
+pub fn describe_validation_errors(
+    result: Result<(), validator::ValidationErrors>,
+) -> Result<(), String> {
+    let Err(errors) = result else {
+        return Ok(());
+    };
+
+    let mut field_errors: Vec<_> = errors.field_errors().into_iter().collect();
+    field_errors.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let description = field_errors
+        .into_iter()
+        .map(|(field_name, errors)| {
+            let reasons: Vec<&str> = errors
+                .iter()
+                .map(|error| error.message.as_deref().unwrap_or(&error.code))
+                .collect();
+            format!("{field_name}: {}", reasons.join(", "))
+        })
+        .collect::<Vec<String>>()
+        .join("; ");
+
+    Err(description)
+}
+
+// * The wrappers used for the update parameters only hold a value to check when it is being set, so
+// * the validations skip them otherwise, the same way they skip an `Option` which is `None`
+#[cfg(feature = "validator")]
+macro_rules! impl_validator_traits {
+    ( $wrapper:ident ) => {
+        impl<T: validator::ValidateEmail> validator::ValidateEmail
+            for crate::traits::write::$wrapper<T>
+        {
+            fn as_email_string(&self) -> Option<std::borrow::Cow<'_, str>> {
+                match self {
+                    Self::Set(value) => value.as_email_string(),
+                    _ => None,
+                }
+            }
+        }
+
+        impl<T: validator::ValidateUrl> validator::ValidateUrl
+            for crate::traits::write::$wrapper<T>
+        {
+            fn as_url_string(&self) -> Option<std::borrow::Cow<'_, str>> {
+                match self {
+                    Self::Set(value) => value.as_url_string(),
+                    _ => None,
+                }
+            }
+        }
+
+        impl<T: validator::ValidateLength<u64>> validator::ValidateLength<u64>
+            for crate::traits::write::$wrapper<T>
+        {
+            fn length(&self) -> Option<u64> {
+                match self {
+                    Self::Set(value) => value.length(),
+                    _ => None,
+                }
+            }
+        }
+
+        impl<T: validator::ValidateRange<T>> validator::ValidateRange<T>
+            for crate::traits::write::$wrapper<T>
+        {
+            fn greater_than(&self, max: T) -> Option<bool> {
+                match self {
+                    Self::Set(value) => value.greater_than(max),
+                    _ => None,
+                }
+            }
+
+            fn less_than(&self, min: T) -> Option<bool> {
+                match self {
+                    Self::Set(value) => value.less_than(min),
+                    _ => None,
+                }
+            }
+        }
+
+        impl<T: validator::ValidateContains> validator::ValidateContains
+            for crate::traits::write::$wrapper<T>
+        {
+            fn validate_contains(&self, needle: &str) -> bool {
+                match self {
+                    Self::Set(value) => value.validate_contains(needle),
+                    _ => true,
+                }
+            }
+        }
+
+        impl<T: validator::ValidateRegex> validator::ValidateRegex
+            for crate::traits::write::$wrapper<T>
+        {
+            fn validate_regex(&self, regex: impl validator::AsRegex) -> bool {
+                match self {
+                    Self::Set(value) => value.validate_regex(regex),
+                    _ => true,
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "validator")]
+impl_validator_traits!(FieldUpdate);
+#[cfg(feature = "validator")]
+impl_validator_traits!(UpdateValue);
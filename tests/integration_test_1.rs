@@ -3,9 +3,22 @@
 #[path = "./database_connection.rs"]
 mod database_connection;
 
+use std::sync::Arc;
+
 use serde::Serialize;
 
+use crudkit::axum::extract::{Json, Query, State};
+use crudkit::axum::response::{IntoResponse, Response};
+use crudkit::cte::CteQuery;
+use crudkit::error::Error as CrudkitError;
+use crudkit::filter::{Filter, FilterColumn};
+use crudkit::http::{HeaderMap, HeaderValue, StatusCode};
+use crudkit::idempotency::IDEMPOTENCY_KEY_HEADER;
+use crudkit::pagination::{PageParams, DEFAULT_PAGE_LIMIT};
 use crudkit::prelude::*;
+use crudkit::serde_json;
+use crudkit::sort::{SortDirection, SortParams};
+use crudkit::traits::write::SavepointGranularity;
 use database_connection::get_database;
 use serial_test::serial;
 
@@ -25,6 +38,7 @@ pub struct CustomersTable {
     Clone,
     Serialize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(ArbitraryRecord))]
 pub struct CustomersTableRecord {
     #[auto_primary_key]
     #[defaultable]
@@ -35,10 +49,24 @@ pub struct CustomersTableRecord {
     pub street_address: Option<String>,
 }
 
+/// Create customers records with the given IDs, each with a name in the form `John Doe <id>` and no
+/// other details.
+fn customers(ids: impl IntoIterator<Item = i32>) -> Vec<CustomersTableRecord> {
+    ids.into_iter()
+        .map(|i| CustomersTableRecord {
+            id: Some(i),
+            name: format!("John Doe {i}"),
+            email_address: None,
+            phone_number: None,
+            street_address: None,
+        })
+        .collect()
+}
+
 #[tokio::test]
 #[serial(customers_table)]
 async fn insert_query_one_and_delete_one_should_work() {
-    let id = 1;
+    let id: usize = 1;
     let id_parameter = GenericIdParameter::new(id);
     let new_record = CustomersTableRecord {
         id: Some(id as i32),
@@ -70,7 +98,7 @@ async fn insert_query_one_and_delete_one_should_work() {
 #[tokio::test]
 #[serial(customers_table)]
 async fn update_one_should_work() {
-    let id = 2;
+    let id: usize = 2;
     let id_parameter = GenericIdParameter::new(id);
     let new_record = CustomersTableRecord {
         id: Some(id as i32),
@@ -98,11 +126,11 @@ async fn update_one_should_work() {
     assert_eq!(record.street_address, None);
 
     let updated_record = CustomersTableRecordUpdateQueryParameters {
-        id: record.id,             // ID is required else `update_one` will fail
-        name: None,                // Do not change name
-        email_address: Some(None), // Set email to `None`
-        phone_number: Some(Some("1234567890".to_string())), // Add value
-        street_address: Some(Some("123 Some street East".to_string())), // Add value
+        id: record.id,                       // ID is required else `update_one` will fail
+        name: None,                          // Do not change name
+        email_address: FieldUpdate::SetNull, // Set email to `NULL`
+        phone_number: FieldUpdate::Set("1234567890".to_string()), // Add value
+        street_address: FieldUpdate::Set("123 Some street East".to_string()), // Add value
     };
     CustomersTable::update_one(&database, updated_record)
         .await
@@ -126,13 +154,88 @@ async fn update_one_should_work() {
         .expect("customers record deletion failed");
 }
 
+#[tokio::test]
+#[serial(customers_table)]
+async fn field_update_should_distinguish_unchanged_set_and_null() {
+    let id: usize = 238;
+    let id_parameter = GenericIdParameter::new(id);
+    let database = get_database().await;
+
+    CustomersTableRecord {
+        id: Some(id as i32),
+        name: "Jane Doe".to_string(),
+        email_address: Some("janedoe@gmail.com".to_string()),
+        phone_number: Some("1234567890".to_string()),
+        street_address: None,
+    }
+    .insert(&database)
+    .await
+    .expect("customers record creation failed");
+
+    let update_params = CustomersTableRecordUpdateQueryParameters {
+        id: Some(id as i32),
+        name: None,
+        email_address: FieldUpdate::Unchanged,
+        phone_number: FieldUpdate::SetNull,
+        street_address: FieldUpdate::Set("123 Some street East".to_string()),
+    };
+    CustomersTable::update_one(&database, update_params)
+        .await
+        .expect("customers record update failed");
+
+    let record = CustomersTable::query_one(&database, id_parameter.clone())
+        .await
+        .expect("customers record query failed");
+
+    assert_eq!(record.email_address, Some("janedoe@gmail.com".to_string()));
+    assert_eq!(record.phone_number, None);
+    assert_eq!(
+        record.street_address,
+        Some("123 Some street East".to_string())
+    );
+
+    let update_params: CustomersTableRecordUpdateQueryParameters =
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "email_address": null,
+            "phone_number": "0987654321",
+        }))
+        .expect("customers update parameters should deserialize");
+
+    assert_eq!(update_params.email_address, FieldUpdate::SetNull);
+    assert_eq!(
+        update_params.phone_number,
+        FieldUpdate::Set("0987654321".to_string())
+    );
+    assert_eq!(update_params.street_address, FieldUpdate::Unchanged);
+
+    CustomersTable::update_one(&database, update_params)
+        .await
+        .expect("customers record update failed");
+
+    let record = CustomersTable::query_one(&database, id_parameter.clone())
+        .await
+        .expect("customers record query failed");
+
+    assert_eq!(record.email_address, None);
+    assert_eq!(record.phone_number, Some("0987654321".to_string()));
+    assert_eq!(
+        record.street_address,
+        Some("123 Some street East".to_string())
+    );
+
+    CustomersTable::delete_one(&database, id_parameter)
+        .await
+        .expect("customers record deletion failed");
+}
+
 #[tokio::test]
 #[serial(customers_table)]
 async fn bulk_insert_query_all_and_delete_all_should_work() {
     let customers = (0..10)
         .map(|i| CustomersTableRecord {
             id: Some(i),
-            name: format!("John Doe {i}").to_string(),
+            name: format!("John Doe {i}"),
             email_address: Some("jdoe@email.com".to_string()),
             phone_number: Some("1234567890".to_string()),
             street_address: Some("123 Some street East".to_string()),
@@ -162,3 +265,2637 @@ async fn bulk_insert_query_all_and_delete_all_should_work() {
         .await
         .expect("customers table deletion failed");
 }
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_where_with_column_function_should_work() {
+    let customers = vec![
+        CustomersTableRecord {
+            id: Some(3),
+            name: "John Doe".to_string(),
+            email_address: Some("JDoe@Email.com".to_string()),
+            phone_number: None,
+            street_address: None,
+        },
+        CustomersTableRecord {
+            id: Some(4),
+            name: "Jane Doe".to_string(),
+            email_address: Some("janedoe@gmail.com".to_string()),
+            phone_number: None,
+            street_address: None,
+        },
+    ];
+    let customers_table = CustomersTable { records: customers };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let filters = [Filter::equals(
+        FilterColumn::lower("email_address"),
+        "jdoe@email.com".to_string(),
+    )];
+    let records = CustomersTable::query_where(&database, &filters)
+        .await
+        .expect("customers table filtered query failed")
+        .records;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].id, Some(3));
+
+    let filters = [Filter::equals("not_a_column", 1)];
+    let error = CustomersTable::query_where(&database, &filters)
+        .await
+        .err()
+        .expect("filter on unknown column should fail");
+
+    assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+async fn diff_schema_should_match_migration() {
+    let database = get_database().await;
+
+    let diff = database
+        .diff_schema::<CustomersTableRecord>()
+        .await
+        .expect("customers schema introspection failed");
+
+    assert!(diff.is_empty(), "unexpected schema diff: {diff:?}");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn insert_all_best_effort_should_skip_failing_records() {
+    let customers_table = CustomersTable {
+        records: customers([20, 21, 20, 22]),
+    };
+
+    let database = get_database().await;
+
+    let report = customers_table
+        .insert_all_best_effort(&database, SavepointGranularity::Record)
+        .await
+        .expect("customers table best-effort creation failed");
+
+    assert_eq!(report.inserted_count, 3);
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].first_record_index, 2);
+    assert_eq!(report.skipped[0].record_count, 1);
+
+    let records = CustomersTable::query_all(&database)
+        .await
+        .expect("customers table query failed")
+        .records;
+
+    assert_eq!(records.len(), 3);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn insert_all_should_roll_back_every_chunk_on_failure() {
+    // * The duplicate ID falls in the second chunk, after the first chunk has been inserted
+    let chunk_size = <CustomersTable as BulkInsert>::CHUNK_SIZE as i32;
+    let customers_table = CustomersTable {
+        records: customers((200..200 + chunk_size).chain([200])),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect_err("customers table creation should fail");
+
+    let count = CustomersTable::count(&database)
+        .await
+        .expect("customers table count failed");
+
+    assert_eq!(count, 0);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn insert_all_with_progress_should_report_every_chunk() {
+    let chunk_size = <CustomersTable as BulkInsert>::CHUNK_SIZE as i32;
+    let customers_table = CustomersTable {
+        records: customers(200..200 + chunk_size * 2 + 1),
+    };
+
+    let database = get_database().await;
+
+    let mut progress = Vec::new();
+    customers_table
+        .insert_all_with_progress(&database, |done, total| progress.push((done, total)))
+        .await
+        .expect("customers table creation failed");
+
+    assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn insert_all_concurrent_should_insert_every_chunk() {
+    let chunk_size = <CustomersTable as BulkInsert>::CHUNK_SIZE as i32;
+    let customers_table = CustomersTable {
+        records: customers(200..200 + chunk_size * 2 + 10),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all_concurrent(&database, 4)
+        .await
+        .expect("customers table concurrent creation failed");
+
+    let count = CustomersTable::count(&database)
+        .await
+        .expect("customers table count failed");
+
+    assert_eq!(count, i64::from(chunk_size * 2 + 10));
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[test]
+fn retain_should_keep_matching_records() {
+    let mut customers_table = CustomersTable {
+        records: customers(0..5),
+    };
+
+    customers_table.retain(|record| record.id.is_some_and(|id| id % 2 == 0));
+
+    let ids: Vec<Option<i32>> = customers_table
+        .records
+        .iter()
+        .map(|record| record.id)
+        .collect();
+    assert_eq!(ids, vec![Some(0), Some(2), Some(4)]);
+}
+
+#[test]
+fn with_records_should_accept_any_vec_conversion() {
+    let customers: [_; 2] = customers([0, 1])
+        .try_into()
+        .ok()
+        .expect("two customers records should be created");
+
+    let from_slice = CustomersTable::with_records(&customers[..]);
+    let from_array = CustomersTable::with_records(customers);
+
+    assert_eq!(from_slice.records.len(), 2);
+    assert_eq!(from_array.records[1].id, Some(1));
+}
+
+#[test]
+fn relation_should_iterate_over_records() {
+    let customers_table = CustomersTable::with_records(customers([0, 1, 2]));
+
+    let names: Vec<&str> = customers_table
+        .iter()
+        .map(|record| record.name.as_str())
+        .collect();
+    assert_eq!(names, ["John Doe 0", "John Doe 1", "John Doe 2"]);
+
+    let mut borrowed_ids = Vec::new();
+    for record in &customers_table {
+        borrowed_ids.push(record.id);
+    }
+
+    let mut owned_ids = Vec::new();
+    for record in customers_table {
+        owned_ids.push(record.id);
+    }
+
+    assert_eq!(borrowed_ids, [Some(0), Some(1), Some(2)]);
+    assert_eq!(owned_ids, borrowed_ids);
+}
+
+#[test]
+fn get_column_should_return_json_values() {
+    let record = CustomersTableRecord {
+        id: Some(30),
+        name: "John Doe".to_string(),
+        email_address: None,
+        phone_number: Some("1234567890".to_string()),
+        street_address: None,
+    };
+
+    assert_eq!(record.get_column("id"), Some(serde_json::json!(30)));
+    assert_eq!(
+        record.get_column("name"),
+        Some(serde_json::json!("John Doe"))
+    );
+    assert_eq!(
+        record.get_column("email_address"),
+        Some(serde_json::Value::Null)
+    );
+    assert_eq!(record.get_column("not_a_column"), None);
+}
+
+#[derive(Relation, ReadRelation, Serialize)]
+#[relation(
+    relation_name = "customers",
+    primary_key = "id",
+    default_order = "email_address",
+    nulls = "first"
+)]
+pub struct CustomersByEmailTable {
+    records: Vec<CustomersByEmailTableRecord>,
+}
+
+#[derive(Record, ReadRecord, sqlx::FromRow, Clone, Serialize)]
+pub struct CustomersByEmailTableRecord {
+    pub id: Option<i32>,
+    pub name: String,
+    pub email_address: Option<String>,
+    pub phone_number: Option<String>,
+    pub street_address: Option<String>,
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_all_with_default_order_should_place_nulls_first() {
+    let customers = [
+        (40, Some("b@email.com")),
+        (41, None),
+        (42, Some("a@email.com")),
+    ]
+    .into_iter()
+    .map(|(i, email_address)| CustomersTableRecord {
+        id: Some(i),
+        name: format!("John Doe {i}"),
+        email_address: email_address.map(str::to_string),
+        phone_number: None,
+        street_address: None,
+    })
+    .collect();
+    let customers_table = CustomersTable { records: customers };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let ids: Vec<Option<i32>> = CustomersByEmailTable::query_all(&database)
+        .await
+        .expect("customers table query failed")
+        .records
+        .iter()
+        .map(|record| record.id)
+        .collect();
+
+    assert_eq!(ids, vec![Some(41), Some(42), Some(40)]);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[derive(Clone)]
+struct TestState {
+    database: PgDatabase,
+}
+
+impl DatabaseState for TestState {
+    fn get_database(&self) -> &PgDatabase {
+        &self.database
+    }
+
+    fn get_database_connection(&self) -> &sqlx::PgPool {
+        &self.database.connection
+    }
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn create_one_idempotent_handler_should_not_insert_twice() {
+    let database = get_database().await;
+    database
+        .create_idempotency_table()
+        .await
+        .expect("idempotency table creation failed");
+    sqlx::query("DELETE FROM main.crudkit_idempotency_keys")
+        .execute(&database.connection)
+        .await
+        .expect("idempotency table cleanup failed");
+
+    let state = Arc::new(TestState { database });
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        IDEMPOTENCY_KEY_HEADER,
+        HeaderValue::from_static("create-customer-50"),
+    );
+
+    for _ in 0..2 {
+        let create_params = CustomersTableRecordCreateQueryParameters {
+            name: "John Doe".to_string(),
+            email_address: None,
+            phone_number: None,
+            street_address: None,
+        };
+
+        let status_code = CustomersTable::create_one_idempotent_handler(
+            State(state.clone()),
+            headers.clone(),
+            Query(create_params),
+        )
+        .await
+        .status();
+
+        assert_eq!(status_code, StatusCode::CREATED);
+    }
+
+    let records = CustomersTable::query_all(&state.database)
+        .await
+        .expect("customers table query failed")
+        .records;
+
+    assert_eq!(records.len(), 1);
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn create_one_idempotent_handler_should_not_insert_concurrent_retries_twice() {
+    let database = get_database().await;
+    database
+        .create_idempotency_table()
+        .await
+        .expect("idempotency table creation failed");
+    sqlx::query("DELETE FROM main.crudkit_idempotency_keys")
+        .execute(&database.connection)
+        .await
+        .expect("idempotency table cleanup failed");
+
+    let state = Arc::new(TestState { database });
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        IDEMPOTENCY_KEY_HEADER,
+        HeaderValue::from_static("create-customer-51"),
+    );
+
+    let retries: Vec<_> = (0..8)
+        .map(|_| {
+            let create_params = CustomersTableRecordCreateQueryParameters {
+                name: "John Doe".to_string(),
+                email_address: None,
+                phone_number: None,
+                street_address: None,
+            };
+
+            tokio::spawn(CustomersTable::create_one_idempotent_handler(
+                State(state.clone()),
+                headers.clone(),
+                Query(create_params),
+            ))
+        })
+        .collect();
+
+    for retry in retries {
+        let status_code = retry
+            .await
+            .expect("idempotent customer creation task panicked")
+            .status();
+
+        assert_eq!(status_code, StatusCode::CREATED);
+    }
+
+    let records = CustomersTable::query_all(&state.database)
+        .await
+        .expect("customers table query failed")
+        .records;
+
+    assert_eq!(records.len(), 1);
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[cfg(feature = "cache")]
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_all_cached_should_serve_cached_result_until_invalidated() {
+    use std::time::Duration;
+
+    use crudkit::cache::CachedReadRelation;
+
+    let database = get_database().await;
+    let cache = CachedReadRelation::<CustomersTable>::new(Duration::from_secs(60));
+
+    let relation = cache
+        .query_all_cached(&database)
+        .await
+        .expect("customers table cached query failed");
+
+    assert!(relation.records.is_empty());
+
+    CustomersTableRecord {
+        id: Some(60),
+        name: "John Doe".to_string(),
+        email_address: None,
+        phone_number: None,
+        street_address: None,
+    }
+    .insert(&database)
+    .await
+    .expect("customers record creation failed");
+
+    let relation = cache
+        .query_all_cached(&database)
+        .await
+        .expect("customers table cached query failed");
+
+    assert!(relation.records.is_empty());
+
+    cache.invalidate();
+    let relation = cache
+        .query_all_cached(&database)
+        .await
+        .expect("customers table cached query failed");
+
+    assert_eq!(relation.records.len(), 1);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn update_one_returning_should_return_updated_record() {
+    let new_record = CustomersTableRecord {
+        id: Some(70),
+        name: "Jane Doe".to_string(),
+        email_address: None,
+        phone_number: None,
+        street_address: None,
+    };
+
+    let database = get_database().await;
+
+    new_record
+        .insert(&database)
+        .await
+        .expect("customers record creation failed");
+
+    let updated_record = CustomersTableRecordUpdateQueryParameters {
+        id: Some(70),
+        name: Some("Jane Smith".to_string()),
+        email_address: FieldUpdate::Unchanged,
+        phone_number: FieldUpdate::Unchanged,
+        street_address: FieldUpdate::Unchanged,
+    };
+    let record = CustomersTable::update_one_returning(&database, updated_record)
+        .await
+        .expect("customers record update failed");
+
+    assert_eq!(record.id, Some(70));
+    assert_eq!(record.name, "Jane Smith".to_string());
+
+    let missing_record = CustomersTableRecordUpdateQueryParameters {
+        id: Some(71),
+        name: Some("John Smith".to_string()),
+        email_address: FieldUpdate::Unchanged,
+        phone_number: FieldUpdate::Unchanged,
+        street_address: FieldUpdate::Unchanged,
+    };
+    let error = CustomersTable::update_one_returning(&database, missing_record)
+        .await
+        .err()
+        .expect("update of missing record should fail");
+
+    assert_eq!(error.status_code, StatusCode::NOT_FOUND);
+
+    let empty_update = CustomersTableRecordUpdateQueryParameters {
+        id: Some(70),
+        name: None,
+        email_address: FieldUpdate::Unchanged,
+        phone_number: FieldUpdate::Unchanged,
+        street_address: FieldUpdate::Unchanged,
+    };
+    let error = CustomersTable::update_one_returning(&database, empty_update)
+        .await
+        .err()
+        .expect("empty update should fail");
+
+    assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+    assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+
+    let state = Arc::new(TestState { database });
+    let updated_record = CustomersTableRecordUpdateQueryParameters {
+        id: Some(70),
+        name: Some("Jane Jones".to_string()),
+        email_address: FieldUpdate::Unchanged,
+        phone_number: FieldUpdate::Unchanged,
+        street_address: FieldUpdate::Unchanged,
+    };
+    let response =
+        CustomersTable::update_one_returning_handler(State(state.clone()), Query(updated_record))
+            .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body should be readable");
+    let body: serde_json::Value =
+        serde_json::from_slice(&body).expect("response body should be JSON");
+    assert_eq!(body["name"], "Jane Jones");
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn update_one_without_primary_key_should_fail() {
+    let update_params = || CustomersTableRecordUpdateQueryParameters {
+        id: None,
+        name: Some("Jane Smith".to_string()),
+        email_address: FieldUpdate::Unchanged,
+        phone_number: FieldUpdate::Unchanged,
+        street_address: FieldUpdate::Unchanged,
+    };
+
+    let database = get_database().await;
+
+    let error = CustomersTable::update_one(&database, update_params())
+        .await
+        .expect_err("update without a primary key should fail");
+
+    assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+    assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+
+    let state = Arc::new(TestState { database });
+    let response =
+        CustomersTable::update_one_handler(State(state.clone()), Query(update_params())).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(
+        body,
+        serde_json::json!({ "error": { "kind": "InvalidQuery", "message": "invalid query" } })
+    );
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn json_handlers_should_read_parameters_from_body() {
+    let state = Arc::new(TestState {
+        database: get_database().await,
+    });
+
+    let create_params = serde_json::from_value(serde_json::json!({
+        "name": "John Doe",
+        "email_address": "jdoe@email.com",
+    }))
+    .expect("create parameters should deserialize");
+    let status = CustomersTable::create_one_json_handler(State(state.clone()), Json(create_params))
+        .await
+        .status();
+
+    assert_eq!(status, StatusCode::CREATED);
+
+    let records = CustomersTable::query_all(&state.database)
+        .await
+        .expect("customers table query failed")
+        .take_records();
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].email_address, Some("jdoe@email.com".to_string()));
+
+    let update_params = serde_json::from_value(serde_json::json!({
+        "id": records[0].id,
+        "name": "Jane Doe",
+        "email_address": null,
+    }))
+    .expect("update parameters should deserialize");
+    let status = CustomersTable::update_one_json_handler(State(state.clone()), Json(update_params))
+        .await
+        .status();
+
+    assert_eq!(status, StatusCode::OK);
+
+    let records = CustomersTable::query_all(&state.database)
+        .await
+        .expect("customers table query failed")
+        .take_records();
+
+    assert_eq!(records[0].name, "Jane Doe".to_string());
+    assert_eq!(records[0].email_address, None);
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+// * `sqlx::FromRow` binds each field to a local variable of the same name, so the lint must be
+// * allowed for the whole module rather than only on the record type
+#[allow(non_snake_case)]
+mod camel_case_customers {
+    use super::*;
+
+    #[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+    #[relation(
+        relation_name = "customers",
+        primary_key = "id",
+        identifiers = "snake_case"
+    )]
+    pub struct CamelCaseCustomersTable {
+        records: Vec<CamelCaseCustomersTableRecord>,
+    }
+
+    #[derive(Record, ReadRecord, WriteRecord, SingleInsert, sqlx::FromRow, Clone, Serialize)]
+    #[sqlx(rename_all = "snake_case")]
+    pub struct CamelCaseCustomersTableRecord {
+        #[manual_primary_key]
+        pub id: i32,
+        pub name: String,
+        pub emailAddress: Option<String>,
+        pub phoneNumber: Option<String>,
+        pub streetAddress: Option<String>,
+    }
+    #[tokio::test]
+    #[serial(customers_table)]
+    async fn snake_case_identifier_strategy_should_map_field_names() {
+        assert_eq!(
+            CamelCaseCustomersTableRecord::COLUMN_NAMES,
+            &[
+                "id",
+                "name",
+                "email_address",
+                "phone_number",
+                "street_address"
+            ]
+        );
+
+        let new_record = CamelCaseCustomersTableRecord {
+            id: 80,
+            name: "John Doe".to_string(),
+            emailAddress: Some("jdoe@email.com".to_string()),
+            phoneNumber: None,
+            streetAddress: None,
+        };
+
+        let database = get_database().await;
+
+        new_record
+            .insert(&database)
+            .await
+            .expect("customers record creation failed");
+
+        let updated_record = CamelCaseCustomersTableRecordUpdateQueryParameters {
+            id: 80,
+            name: None,
+            emailAddress: FieldUpdate::Unchanged,
+            phoneNumber: FieldUpdate::Set("1234567890".to_string()),
+            streetAddress: FieldUpdate::Unchanged,
+        };
+        CamelCaseCustomersTable::update_one(&database, updated_record)
+            .await
+            .expect("customers record update failed");
+
+        let filters = [Filter::equals(
+            "email_address",
+            "jdoe@email.com".to_string(),
+        )];
+        let records = CamelCaseCustomersTable::query_where(&database, &filters)
+            .await
+            .expect("customers table filtered query failed")
+            .take_records();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].phoneNumber, Some("1234567890".to_string()));
+
+        CustomersTable::delete_all(&database)
+            .await
+            .expect("customers table deletion failed");
+    }
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn delete_where_returning_should_return_deleted_records() {
+    let customers = customers(90..95)
+        .into_iter()
+        .map(|customer| CustomersTableRecord {
+            phone_number: customer
+                .id
+                .filter(|id| id % 2 == 0)
+                .map(|_| "1234567890".to_string()),
+            ..customer
+        })
+        .collect();
+    let customers_table = CustomersTable { records: customers };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let filters = [Filter::equals("phone_number", "1234567890".to_string())];
+    let deleted_records = CustomersTable::delete_where_returning(&database, &filters)
+        .await
+        .expect("customers conditional deletion failed");
+
+    let deleted_ids: Vec<Option<i32>> = deleted_records.iter().map(|r| r.id).collect();
+    assert_eq!(deleted_ids, vec![Some(90), Some(92), Some(94)]);
+
+    let error = CustomersTable::delete_where(&database, &[])
+        .await
+        .expect_err("conditional deletion without filters should fail");
+
+    assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+
+    let remaining_count = CustomersTable::query_all(&database)
+        .await
+        .expect("customers table query failed")
+        .records
+        .len();
+
+    assert_eq!(remaining_count, 2);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn delete_returning_should_return_deleted_records() {
+    let customers_table = CustomersTable {
+        records: customers(270..274),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let deleted_record =
+        CustomersTable::delete_one_returning(&database, GenericIdParameter::new(271))
+            .await
+            .expect("customers record deletion failed");
+
+    assert_eq!(deleted_record.id, Some(271));
+    assert_eq!(deleted_record.name, "John Doe 271".to_string());
+
+    let error = CustomersTable::delete_one_returning(&database, GenericIdParameter::new(271))
+        .await
+        .err()
+        .expect("deleting a missing customers record should fail");
+
+    assert_eq!(error.status_code, StatusCode::NOT_FOUND);
+
+    let deleted_records = CustomersTable::delete_all_returning(&database)
+        .await
+        .expect("customers table deletion failed");
+
+    let deleted_ids: Vec<Option<i32>> = deleted_records.iter().map(|r| r.id).collect();
+    assert_eq!(deleted_ids, vec![Some(270), Some(272), Some(273)]);
+
+    let remaining_count = CustomersTable::count(&database)
+        .await
+        .expect("customers table count failed");
+
+    assert_eq!(remaining_count, 0);
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_first_and_last_should_select_by_primary_key() {
+    let database = get_database().await;
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+
+    let first_record = CustomersTable::query_first(&database)
+        .await
+        .expect("customers first record query failed");
+    let last_record = CustomersTable::query_last(&database)
+        .await
+        .expect("customers last record query failed");
+
+    assert!(first_record.is_none());
+    assert!(last_record.is_none());
+
+    for id in [236, 235, 237] {
+        CustomersTableRecord {
+            id: Some(id),
+            name: format!("Customer {id}"),
+            email_address: None,
+            phone_number: None,
+            street_address: None,
+        }
+        .insert(&database)
+        .await
+        .expect("customers record creation failed");
+    }
+
+    let first_record = CustomersTable::query_first(&database)
+        .await
+        .expect("customers first record query failed")
+        .expect("customers first record should exist");
+    let last_record = CustomersTable::query_last(&database)
+        .await
+        .expect("customers last record query failed")
+        .expect("customers last record should exist");
+
+    assert_eq!(first_record.id, Some(235));
+    assert_eq!(last_record.id, Some(237));
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_all_columns_should_select_only_given_columns() {
+    use crudkit::projection::FieldsParams;
+
+    let database = get_database().await;
+
+    for id in [235, 236] {
+        CustomersTableRecord {
+            id: Some(id),
+            name: format!("Customer {id}"),
+            email_address: Some(format!("customer{id}@gmail.com")),
+            phone_number: None,
+            street_address: None,
+        }
+        .insert(&database)
+        .await
+        .expect("customers record creation failed");
+    }
+
+    let records = CustomersTable::query_all_columns(&database, &["id", "name"])
+        .await
+        .expect("customers projected query failed");
+
+    assert_eq!(
+        records,
+        [
+            serde_json::json!({ "id": 235, "name": "Customer 235" }),
+            serde_json::json!({ "id": 236, "name": "Customer 236" }),
+        ]
+        .map(|record| record.as_object().unwrap().clone())
+    );
+
+    // * More columns than fit in a single JSON object call are split across several
+    let columns = ["phone_number", "email_address"].repeat(30);
+    let records = CustomersTable::query_all_columns(&database, &columns)
+        .await
+        .expect("customers projected query failed");
+
+    assert_eq!(
+        records[0],
+        *serde_json::json!({ "email_address": "customer235@gmail.com", "phone_number": null })
+            .as_object()
+            .unwrap()
+    );
+
+    for columns in [&[][..], &["id", "password"][..]] {
+        let error = CustomersTable::query_all_columns(&database, columns)
+            .await
+            .expect_err("invalid projection should fail");
+
+        assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+    }
+
+    let response = CustomersTable::query_all_columns_handler(
+        State(Arc::new(TestState {
+            database: get_database().await,
+        })),
+        Query(FieldsParams {
+            fields: Some("id,email_address".to_string()),
+        }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn search_should_match_term_in_given_columns() {
+    use crudkit::search::SearchParams;
+
+    let database = get_database().await;
+
+    for (id, name, email_address) in [
+        (235, "Jane Doe", "jane@gmail.com"),
+        (236, "John Smith", "johnsmith@gmail.com"),
+        (237, "Ann Johnson", "100%_ann@gmail.com"),
+    ] {
+        CustomersTableRecord {
+            id: Some(id),
+            name: name.to_string(),
+            email_address: Some(email_address.to_string()),
+            phone_number: None,
+            street_address: None,
+        }
+        .insert(&database)
+        .await
+        .expect("customers record creation failed");
+    }
+
+    let search_ids = |records: Vec<CustomersTableRecord>| -> Vec<i32> {
+        records.iter().filter_map(|record| record.id).collect()
+    };
+
+    let customers = CustomersTable::search(&database, &["name", "email_address"], "JOHN")
+        .await
+        .expect("customers search failed");
+    assert_eq!(search_ids(customers.records), vec![236, 237]);
+
+    let customers = CustomersTable::search(&database, &["name"], "john")
+        .await
+        .expect("customers search failed");
+    assert_eq!(search_ids(customers.records), vec![236, 237]);
+
+    // * Wildcards in the term are matched literally
+    let customers = CustomersTable::search(&database, &["email_address"], "%_")
+        .await
+        .expect("customers search failed");
+    assert_eq!(search_ids(customers.records), vec![237]);
+
+    let customers = CustomersTable::search_full_text(&database, &["name", "email_address"], "doe")
+        .await
+        .expect("customers full-text search failed");
+    assert_eq!(search_ids(customers.records), vec![235]);
+
+    let customers = CustomersTable::search_full_text(&database, &["name"], "john -smith")
+        .await
+        .expect("customers full-text search failed");
+    assert!(customers.records.is_empty());
+
+    let error = CustomersTable::search(&database, &["password"], "john")
+        .await
+        .err()
+        .expect("search on unknown column should fail");
+    assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+
+    let search_params = SearchParams {
+        q: "smith".to_string(),
+        columns: None,
+    };
+    assert_eq!(
+        search_params.parse_columns::<CustomersTableRecord>(),
+        ["name", "email_address", "phone_number", "street_address"]
+    );
+
+    let response = CustomersTable::search_handler(
+        State(Arc::new(TestState {
+            database: get_database().await,
+        })),
+        Query(search_params),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_page_should_return_requested_page() {
+    let customers_table = CustomersTable {
+        records: customers(100..110),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let records = CustomersTable::query_page(&database, 3, 4)
+        .await
+        .expect("customers table page query failed")
+        .records;
+
+    let ids: Vec<Option<i32>> = records.iter().map(|record| record.id).collect();
+    assert_eq!(ids, vec![Some(104), Some(105), Some(106)]);
+
+    for (limit, offset) in [(-1, 0), (1, -1), (CustomersTable::MAX_PAGE_LIMIT + 1, 0)] {
+        let error = CustomersTable::query_page(&database, limit, offset)
+            .await
+            .err()
+            .expect("invalid page should fail");
+
+        assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+    }
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[test]
+fn page_params_should_apply_defaults_and_reject_invalid_bounds() {
+    let max_limit = CustomersTable::MAX_PAGE_LIMIT;
+
+    let page_params = PageParams::default();
+    assert_eq!(
+        page_params
+            .validate(max_limit)
+            .expect("default page should be valid"),
+        (DEFAULT_PAGE_LIMIT, 0)
+    );
+
+    let page_params = PageParams {
+        limit: Some(max_limit),
+        offset: Some(20),
+    };
+    assert_eq!(
+        page_params
+            .validate(max_limit)
+            .expect("page at the maximum limit should be valid"),
+        (max_limit, 20)
+    );
+
+    for (limit, offset) in [(max_limit + 1, 0), (-1, 0), (10, -1)] {
+        let page_params = PageParams {
+            limit: Some(limit),
+            offset: Some(offset),
+        };
+        let error = page_params
+            .validate(max_limit)
+            .expect_err("page outside the valid range should be rejected");
+
+        assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+        assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn cte_query_should_filter_ranked_records() {
+    let customers = (110..116)
+        .map(|i| CustomersTableRecord {
+            id: Some(i),
+            name: if i % 2 == 0 { "Jane Doe" } else { "John Doe" }.to_string(),
+            email_address: None,
+            phone_number: None,
+            street_address: None,
+        })
+        .collect();
+    let customers_table = CustomersTable { records: customers };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let mut query = CteQuery::<CustomersTable>::new("ranked_customers");
+    query
+        .push(
+            "SELECT *, row_number() OVER (PARTITION BY name ORDER BY id DESC) AS rank \
+            FROM main.customers WHERE id >= ",
+        )
+        .push_bind(110)
+        .push_where()
+        .push("rank <= ")
+        .push_bind(2_i64);
+
+    let records = query
+        .fetch_all(&database)
+        .await
+        .expect("customers CTE query failed")
+        .records;
+
+    let ids: Vec<Option<i32>> = records.iter().map(|record| record.id).collect();
+    assert_eq!(ids, vec![Some(112), Some(113), Some(114), Some(115)]);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[cfg(feature = "arbitrary")]
+#[tokio::test]
+#[serial(customers_table)]
+async fn arbitrary_records_should_round_trip() {
+    use crudkit::arbitrary::{Arbitrary, Unstructured};
+
+    let seed: Vec<u8> = (0..4096).map(|i| (i % 256) as u8).collect();
+    let mut unstructured = Unstructured::new(&seed);
+    let customers: Vec<CustomersTableRecord> = (120..130)
+        .map(|i| {
+            let mut record = CustomersTableRecord::arbitrary(&mut unstructured)
+                .expect("arbitrary customers record generation failed");
+            record.id = Some(i);
+            record
+        })
+        .collect();
+    let customers_table = CustomersTable {
+        records: customers.clone(),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let records = CustomersTable::query_all(&database)
+        .await
+        .expect("customers table query failed")
+        .records;
+
+    assert_eq!(records.len(), customers.len());
+    for (record, customer) in records.iter().zip(&customers) {
+        assert_eq!(record.id, customer.id);
+        assert_eq!(record.name, customer.name);
+        assert_eq!(record.email_address, customer.email_address);
+        assert_eq!(record.phone_number, customer.phone_number);
+        assert_eq!(record.street_address, customer.street_address);
+    }
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn error_should_display_kind_and_source_summary() {
+    let database = get_database().await;
+
+    let error = CustomersTable::query_one(&database, GenericIdParameter::new(999))
+        .await
+        .err()
+        .expect("query for missing customers record should fail");
+
+    assert_eq!(
+        error.to_string(),
+        "unexpected query result: no rows returned"
+    );
+    assert!(std::error::Error::source(&error).is_some());
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn upsert_all_should_report_inserted_and_updated_records() {
+    let database = get_database().await;
+
+    CustomersTableRecord {
+        id: Some(130),
+        name: "John Doe".to_string(),
+        email_address: None,
+        phone_number: None,
+        street_address: None,
+    }
+    .insert(&database)
+    .await
+    .expect("customers record creation failed");
+
+    let customers_table = CustomersTable {
+        records: vec![
+            CustomersTableRecord {
+                id: Some(130),
+                name: "Jane Doe".to_string(),
+                email_address: None,
+                phone_number: None,
+                street_address: None,
+            },
+            CustomersTableRecord {
+                id: Some(131),
+                name: "Jane Doe".to_string(),
+                email_address: None,
+                phone_number: None,
+                street_address: None,
+            },
+        ],
+    };
+
+    let mut upserted_keys = customers_table
+        .upsert_all_returning(&database)
+        .await
+        .expect("customers table upsert failed");
+    upserted_keys.sort();
+
+    assert_eq!(upserted_keys, vec![(130, false), (131, true)]);
+
+    let record = CustomersTable::query_one(&database, GenericIdParameter::new(130))
+        .await
+        .expect("customers record query failed");
+
+    assert_eq!(record.name, "Jane Doe".to_string());
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn upsert_all_should_update_existing_records() {
+    let database = get_database().await;
+
+    CustomersTable {
+        records: customers(310..312),
+    }
+    .insert_all(&database)
+    .await
+    .expect("customers table creation failed");
+
+    let mut customers = customers(311..313);
+    for customer in &mut customers {
+        customer.email_address = Some("jdoe@email.com".to_string());
+    }
+
+    CustomersTable { records: customers }
+        .upsert_all(&database)
+        .await
+        .expect("customers table upsert failed");
+
+    let email_addresses: Vec<_> = CustomersTable::query_all(&database)
+        .await
+        .expect("customers table query failed")
+        .records
+        .into_iter()
+        .map(|record| (record.id, record.email_address))
+        .collect();
+
+    assert_eq!(
+        email_addresses,
+        vec![
+            (Some(310), None),
+            (Some(311), Some("jdoe@email.com".to_string())),
+            (Some(312), Some("jdoe@email.com".to_string())),
+        ]
+    );
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn upsert_all_should_roll_back_every_chunk_on_failure() {
+    let chunk_size = <CustomersTable as BulkInsert>::CHUNK_SIZE as i32;
+    let duplicate_id = 300 + chunk_size;
+    // * The second chunk contains the same primary key twice, which `ON CONFLICT DO UPDATE` rejects
+    let customers_table = CustomersTable {
+        records: customers((300..duplicate_id).chain([duplicate_id, duplicate_id])),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .upsert_all(&database)
+        .await
+        .expect_err("customers table upsert with a duplicate key should fail");
+
+    let count = CustomersTable::count(&database)
+        .await
+        .expect("customers table count failed");
+
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn create_one_returning_should_return_generated_id() {
+    let database = get_database().await;
+
+    let create_params = CustomersTableRecordCreateQueryParameters {
+        name: "John Doe".to_string(),
+        email_address: Some("jdoe@email.com".to_string()),
+        phone_number: None,
+        street_address: None,
+    };
+
+    let record = CustomersTable::create_one_returning(&database, create_params)
+        .await
+        .expect("customers record creation failed");
+
+    let id = record
+        .id
+        .expect("created customers record should have an id");
+    assert_eq!(record.email_address, Some("jdoe@email.com".to_string()));
+
+    let queried_record = CustomersTable::query_one(&database, GenericIdParameter::new(id as usize))
+        .await
+        .expect("customers record query failed");
+
+    assert_eq!(queried_record.name, "John Doe".to_string());
+
+    let state = Arc::new(TestState { database });
+    let create_params = CustomersTableRecordCreateQueryParameters {
+        name: "Jane Doe".to_string(),
+        email_address: None,
+        phone_number: None,
+        street_address: None,
+    };
+
+    let response =
+        CustomersTable::create_one_returning_handler(State(state.clone()), Query(create_params))
+            .await;
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_one_should_reject_out_of_range_id() {
+    let database = get_database().await;
+
+    let error =
+        CustomersTable::query_one(&database, GenericIdParameter::new(i32::MAX as usize + 1))
+            .await
+            .err()
+            .expect("out of range id should fail");
+
+    assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_one_opt_should_return_none_for_missing_record() {
+    let new_record = CustomersTableRecord {
+        id: Some(237),
+        name: "John Doe".to_string(),
+        email_address: None,
+        phone_number: None,
+        street_address: None,
+    };
+
+    let database = get_database().await;
+
+    new_record
+        .insert(&database)
+        .await
+        .expect("customers record creation failed");
+
+    let record = CustomersTable::query_one_opt(&database, GenericIdParameter::new(237))
+        .await
+        .expect("customers record query failed")
+        .expect("customers record should exist");
+
+    assert_eq!(record.id, Some(237));
+
+    let record = CustomersTable::query_one_opt(&database, GenericIdParameter::new(238))
+        .await
+        .expect("customers record query failed");
+
+    assert!(record.is_none());
+
+    let error = CustomersTable::query_one(&database, GenericIdParameter::new(238))
+        .await
+        .err()
+        .expect("missing customers record query should fail");
+
+    assert_eq!(error.status_code, StatusCode::NOT_FOUND);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_where_handler_should_parse_filter_parameters() {
+    let customers_table = CustomersTable {
+        records: customers(140..145),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let filters = [
+        Filter::greater_than("id", 140),
+        Filter::not_equals("id", 142),
+        Filter::is_in("id", [141, 142, 143, 150]),
+    ];
+    let records = CustomersTable::query_where(&database, &filters)
+        .await
+        .expect("customers table filtered query failed")
+        .records;
+
+    let ids: Vec<Option<i32>> = records.iter().map(|record| record.id).collect();
+    assert_eq!(ids, vec![Some(141), Some(143)]);
+
+    let state = Arc::new(TestState { database });
+    let filter_params = [
+        ("id".to_string(), "lt.144".to_string()),
+        ("name".to_string(), "like.*Doe 14*".to_string()),
+    ]
+    .into_iter()
+    .collect();
+
+    let response =
+        CustomersTable::query_where_handler(State(state.clone()), Query(filter_params)).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body should be readable");
+    let body: serde_json::Value =
+        serde_json::from_slice(&body).expect("response body should be JSON");
+    assert_eq!(body["records"].as_array().map(Vec::len), Some(4));
+
+    for parameter in ["ge.1", "eq", "in.1,2"] {
+        let filter_params = [("id".to_string(), parameter.to_string())]
+            .into_iter()
+            .collect();
+        let response =
+            CustomersTable::query_where_handler(State(state.clone()), Query(filter_params)).await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_search_handler_should_filter_sort_and_paginate() {
+    let customers = customers(260..266)
+        .into_iter()
+        .map(|customer| CustomersTableRecord {
+            name: format!("Jane Doe {}", customer.id.unwrap_or_default() % 2),
+            ..customer
+        })
+        .collect();
+    let customers_table = CustomersTable { records: customers };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let filters = [Filter::greater_than("id", 260)];
+    let order_by = [
+        ("name", SortDirection::Ascending),
+        ("id", SortDirection::Descending),
+    ];
+    let page = CustomersTable::query_search(&database, &filters, &order_by, 2, 1)
+        .await
+        .expect("customers table search query failed");
+
+    let ids: Vec<Option<i32>> = page.items.iter().map(|record| record.id).collect();
+    assert_eq!(ids, vec![Some(262), Some(265)]);
+    assert_eq!((page.total, page.limit, page.offset), (5, 2, 1));
+
+    let state = Arc::new(TestState { database });
+    let search_request = serde_json::from_value(serde_json::json!({
+        "filters": { "id": "lt.265", "name": "like.*Doe 1" },
+        "sort": "id.desc",
+        "limit": 1,
+        "offset": 1,
+    }))
+    .expect("search request should deserialize");
+
+    let response =
+        CustomersTable::query_search_handler(State(state.clone()), Json(search_request)).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body should be readable");
+    let body: serde_json::Value =
+        serde_json::from_slice(&body).expect("response body should be JSON");
+    assert_eq!(body["items"][0]["id"], 261);
+    assert_eq!(body["items"].as_array().map(Vec::len), Some(1));
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["limit"], 1);
+    assert_eq!(body["offset"], 1);
+
+    for search_request in [
+        serde_json::json!({ "filters": { "nonexistent": "eq.1" } }),
+        serde_json::json!({ "sort": "nonexistent.asc" }),
+        serde_json::json!({ "sort": "id.sideways" }),
+        serde_json::json!({ "limit": -1 }),
+    ] {
+        let search_request =
+            serde_json::from_value(search_request).expect("search request should deserialize");
+        let response =
+            CustomersTable::query_search_handler(State(state.clone()), Json(search_request)).await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_all_sorted_should_sort_by_given_columns() {
+    let customers = [(150, "Bob"), (151, "Alice"), (152, "Bob")]
+        .into_iter()
+        .map(|(i, name)| CustomersTableRecord {
+            id: Some(i),
+            name: name.to_string(),
+            email_address: None,
+            phone_number: None,
+            street_address: None,
+        })
+        .collect();
+    let customers_table = CustomersTable { records: customers };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let sort_params = SortParams {
+        sort: Some("name.asc,id.desc".to_string()),
+    };
+    let order_by = sort_params.parse().expect("sort parameter should parse");
+    let records = CustomersTable::query_all_sorted(&database, &order_by)
+        .await
+        .expect("customers table sorted query failed")
+        .records;
+
+    let ids: Vec<Option<i32>> = records.iter().map(|record| record.id).collect();
+    assert_eq!(ids, vec![Some(151), Some(152), Some(150)]);
+
+    let error =
+        CustomersTable::query_all_sorted(&database, &[("not_a_column", SortDirection::Ascending)])
+            .await
+            .err()
+            .expect("sort on unknown column should fail");
+
+    assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn writes_in_transaction_should_apply_atomically() {
+    let database = get_database().await;
+
+    let mut transaction = database.begin().await.expect("transaction should begin");
+    CustomersTableRecord {
+        id: Some(160),
+        name: "John Doe".to_string(),
+        email_address: None,
+        phone_number: None,
+        street_address: None,
+    }
+    .insert(&mut transaction)
+    .await
+    .expect("customers record creation failed");
+
+    let updated_record = CustomersTableRecordUpdateQueryParameters {
+        id: Some(160),
+        name: Some("Jane Doe".to_string()),
+        email_address: FieldUpdate::Unchanged,
+        phone_number: FieldUpdate::Unchanged,
+        street_address: FieldUpdate::Unchanged,
+    };
+    CustomersTable::update_one(&mut transaction, updated_record)
+        .await
+        .expect("customers record update failed");
+
+    let records = CustomersTable::query_all(&database)
+        .await
+        .expect("customers table query failed")
+        .records;
+
+    assert!(records.is_empty());
+
+    transaction
+        .commit()
+        .await
+        .expect("transaction should commit");
+
+    let record = CustomersTable::query_one(&database, GenericIdParameter::new(160))
+        .await
+        .expect("customers record query failed");
+
+    assert_eq!(record.name, "Jane Doe".to_string());
+
+    let mut transaction = database.begin().await.expect("transaction should begin");
+    CustomersTable::delete_one(&mut transaction, GenericIdParameter::new(160))
+        .await
+        .expect("customers record deletion failed");
+    drop(transaction);
+
+    let records = CustomersTable::query_all(&database)
+        .await
+        .expect("customers table query failed")
+        .records;
+
+    assert_eq!(records.len(), 1);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn methods_should_accept_pool_connection_or_transaction() {
+    let database = get_database().await;
+
+    let mut transaction = database.begin().await.expect("transaction should begin");
+    CustomersTable::with_records(customers(161..164))
+        .insert_all(&mut transaction)
+        .await
+        .expect("customers table creation failed");
+
+    let count = CustomersTable::count(&mut transaction)
+        .await
+        .expect("customers table count failed");
+
+    assert_eq!(count, 3);
+
+    let count = CustomersTable::count(&database)
+        .await
+        .expect("customers table count failed");
+
+    assert_eq!(count, 0);
+
+    drop(transaction);
+
+    let count = CustomersTable::count(&database.connection)
+        .await
+        .expect("customers table count failed");
+
+    assert_eq!(count, 0);
+
+    let mut connection = database
+        .connection
+        .acquire()
+        .await
+        .expect("connection should be acquired");
+    CustomersTableRecord {
+        id: Some(161),
+        name: "John Doe".to_string(),
+        email_address: None,
+        phone_number: None,
+        street_address: None,
+    }
+    .insert(&mut *connection)
+    .await
+    .expect("customers record creation failed");
+
+    let record = CustomersTable::query_one(&mut *connection, GenericIdParameter::new(161))
+        .await
+        .expect("customers record query failed");
+
+    assert_eq!(record.name, "John Doe".to_string());
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn count_should_count_all_and_filtered_records() {
+    let customers_table = CustomersTable {
+        records: customers(170..175),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let count = CustomersTable::count(&database)
+        .await
+        .expect("customers table count failed");
+
+    assert_eq!(count, 5);
+
+    let filters = [Filter::greater_than("id", 172)];
+    let count = CustomersTable::count_where(&database, &filters)
+        .await
+        .expect("customers table filtered count failed");
+
+    assert_eq!(count, 2);
+
+    let state = Arc::new(TestState { database });
+    let response = CustomersTable::count_handler(State(state.clone())).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body should be readable");
+    let body: serde_json::Value =
+        serde_json::from_slice(&body).expect("response body should be JSON");
+    assert_eq!(body, serde_json::json!({ "count": 5 }));
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn exists_should_check_for_record_by_id() {
+    let database = get_database().await;
+
+    CustomersTableRecord {
+        id: Some(180),
+        name: "John Doe".to_string(),
+        email_address: None,
+        phone_number: None,
+        street_address: None,
+    }
+    .insert(&database)
+    .await
+    .expect("customers record creation failed");
+
+    let exists = CustomersTable::exists(&database, GenericIdParameter::new(180))
+        .await
+        .expect("customers record existence check failed");
+
+    assert!(exists);
+
+    let exists = CustomersTable::exists(&database, GenericIdParameter::new(181))
+        .await
+        .expect("customers record existence check failed");
+
+    assert!(!exists);
+
+    let state = Arc::new(TestState { database });
+    for (id, status) in [(180, StatusCode::OK), (181, StatusCode::NOT_FOUND)] {
+        let response = CustomersTable::exists_handler::<GenericIdParameter, _>(
+            State(state.clone()),
+            Query(GenericIdParameter::new(id)),
+        )
+        .await;
+
+        assert_eq!(response.status(), status);
+    }
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_many_should_select_records_by_ids() {
+    let customers_table = CustomersTable {
+        records: customers(190..195),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let ids: Vec<GenericIdParameter> = [193_usize, 190, 199]
+        .into_iter()
+        .map(GenericIdParameter::new)
+        .collect();
+    let records = CustomersTable::query_many(&database, &ids)
+        .await
+        .expect("customers table multi-query failed")
+        .records;
+
+    let ids: Vec<Option<i32>> = records.iter().map(|record| record.id).collect();
+    assert_eq!(ids, vec![Some(190), Some(193)]);
+
+    let state = Arc::new(TestState { database });
+    let response = CustomersTable::query_many_handler::<GenericIdParameter, _>(
+        State(state.clone()),
+        Query(IdListParameter {
+            ids: "191,192".to_string(),
+        }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body should be readable");
+    let body: serde_json::Value =
+        serde_json::from_slice(&body).expect("response body should be JSON");
+    assert_eq!(body["records"].as_array().map(Vec::len), Some(2));
+
+    let response = CustomersTable::query_many_handler::<GenericIdParameter, _>(
+        State(state.clone()),
+        Query(IdListParameter {
+            ids: "191,abc".to_string(),
+        }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn update_many_should_update_records_by_ids() {
+    let customers_table = CustomersTable {
+        records: customers(200..205),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let ids: Vec<GenericIdParameter> = [200_usize, 202, 204, 209]
+        .into_iter()
+        .map(GenericIdParameter::new)
+        .collect();
+    let update_params = CustomersTableRecordUpdateQueryParameters {
+        id: None,
+        name: None,
+        email_address: FieldUpdate::Unchanged,
+        phone_number: FieldUpdate::Set("0000000000".to_string()),
+        street_address: FieldUpdate::Unchanged,
+    };
+    let updated_count = CustomersTable::update_many(&database, &ids, update_params)
+        .await
+        .expect("customers table multi-update failed");
+
+    assert_eq!(updated_count, 3);
+
+    let updated_ids: Vec<Option<i32>> = CustomersTable::query_all(&database)
+        .await
+        .expect("customers table query failed")
+        .records
+        .iter()
+        .filter(|record| record.phone_number.is_some())
+        .map(|record| record.id)
+        .collect();
+
+    assert_eq!(updated_ids, vec![Some(200), Some(202), Some(204)]);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn delete_many_should_delete_records_by_ids() {
+    let customers_table = CustomersTable {
+        records: customers(210..215),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let ids: Vec<GenericIdParameter> = [210_usize, 211, 219]
+        .into_iter()
+        .map(GenericIdParameter::new)
+        .collect();
+    let deleted_count = CustomersTable::delete_many(&database, &ids)
+        .await
+        .expect("customers table multi-deletion failed");
+
+    assert_eq!(deleted_count, 2);
+
+    let deleted_count = CustomersTable::delete_many(&database, &ids)
+        .await
+        .expect("customers table multi-deletion failed");
+
+    assert_eq!(deleted_count, 0);
+
+    let error = CustomersTable::delete_many_strict(&database, &ids)
+        .await
+        .expect_err("strict multi-deletion of missing records should fail");
+
+    assert!(matches!(error.kind, ErrorKind::UnexpectedQueryResult));
+
+    let state = Arc::new(TestState { database });
+    let response = CustomersTable::delete_many_handler::<GenericIdParameter, _>(
+        State(state.clone()),
+        Query(IdListParameter {
+            ids: "212,213".to_string(),
+        }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body should be readable");
+    let body: serde_json::Value =
+        serde_json::from_slice(&body).expect("response body should be JSON");
+    assert_eq!(body, serde_json::json!({ "count": 2 }));
+
+    let count = CustomersTable::count(&state.database)
+        .await
+        .expect("customers table count failed");
+
+    assert_eq!(count, 1);
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn crud_router_should_route_to_handlers() {
+    use crudkit::axum::body::Body;
+    use crudkit::axum::Router;
+    use crudkit::database::ServerState;
+    use crudkit::http::{Method, Request};
+    use crudkit::router::crud_router;
+    use tower::ServiceExt;
+
+    let database = get_database().await;
+    let app = Router::new()
+        .nest(
+            "/customers",
+            crud_router::<CustomersTable, GenericIdParameter, _>(),
+        )
+        .with_state(Arc::new(ServerState::new(database.clone())));
+
+    let send = |method: Method, uri: &str| {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .expect("request should be valid");
+        app.clone().oneshot(request)
+    };
+
+    let response = send(Method::POST, "/customers?name=Jane%20Doe")
+        .await
+        .expect("router should respond");
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = send(Method::GET, "/customers")
+        .await
+        .expect("router should respond");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body should be readable");
+    let body: serde_json::Value =
+        serde_json::from_slice(&body).expect("response body should be JSON");
+    let id = body["records"][0]["id"]
+        .as_i64()
+        .expect("created record should have an ID");
+
+    let response = send(
+        Method::PATCH,
+        &format!("/customers?id={id}&name=Janet%20Doe"),
+    )
+    .await
+    .expect("router should respond");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send(Method::GET, &format!("/customers/one?id={id}"))
+        .await
+        .expect("router should respond");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body should be readable");
+    let body: serde_json::Value =
+        serde_json::from_slice(&body).expect("response body should be JSON");
+    assert_eq!(body["name"], "Janet Doe");
+
+    let response = send(Method::DELETE, &format!("/customers/one?id={id}"))
+        .await
+        .expect("router should respond");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send(Method::DELETE, "/customers")
+        .await
+        .expect("router should respond");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let count = CustomersTable::count(&database)
+        .await
+        .expect("customers table count failed");
+
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_after_should_select_pages_by_cursor() {
+    use crudkit::pagination::CursorParams;
+
+    let customers_table = CustomersTable {
+        records: customers(220..227),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let mut pages = Vec::new();
+    let mut cursor: Option<i32> = None;
+    loop {
+        let page = CustomersTable::query_after(&database, cursor, 3)
+            .await
+            .expect("customers table cursor query failed");
+        let ids: Vec<i32> = page
+            .relation
+            .records
+            .iter()
+            .filter_map(|record| record.id)
+            .collect();
+
+        if page.next_cursor.is_none() {
+            assert!(ids.is_empty());
+            break;
+        }
+
+        cursor = page.next_cursor;
+        pages.push(ids);
+    }
+
+    assert_eq!(
+        pages,
+        vec![vec![220, 221, 222], vec![223, 224, 225], vec![226]]
+    );
+
+    let error = CustomersTable::query_after(&database, Some(220), -1)
+        .await
+        .err()
+        .expect("negative limit should be rejected");
+
+    assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+
+    let state = Arc::new(TestState { database });
+    let response = CustomersTable::query_after_handler::<usize, _>(
+        State(state.clone()),
+        Query(CursorParams {
+            after: Some(222),
+            limit: Some(2),
+        }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body should be readable");
+    let body: serde_json::Value =
+        serde_json::from_slice(&body).expect("response body should be JSON");
+    assert_eq!(body["records"][0]["id"], 223);
+    assert_eq!(body["records"][1]["id"], 224);
+    assert_eq!(body["next_cursor"], 224);
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_all_stream_should_yield_every_record() {
+    use futures_util::TryStreamExt;
+
+    let customers_table = CustomersTable {
+        records: customers(230..235),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let records: Vec<CustomersTableRecord> = CustomersTable::query_all_stream(&database)
+        .try_collect()
+        .await
+        .expect("customers table streaming query failed");
+    let ids: Vec<i32> = records.iter().filter_map(|record| record.id).collect();
+
+    assert_eq!(ids, vec![230, 231, 232, 233, 234]);
+
+    let state = Arc::new(TestState { database });
+    let response = CustomersTable::query_all_stream_handler(State(state.clone())).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[crudkit::http::header::CONTENT_TYPE],
+        "application/x-ndjson"
+    );
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body should be readable");
+    let ids: Vec<i64> = std::str::from_utf8(&body)
+        .expect("response body should be UTF-8")
+        .lines()
+        .map(|line| {
+            let record: serde_json::Value =
+                serde_json::from_str(line).expect("each line should be JSON");
+            record["id"]
+                .as_i64()
+                .expect("each record should have an ID")
+        })
+        .collect();
+
+    assert_eq!(ids, vec![230, 231, 232, 233, 234]);
+
+    CustomersTable::delete_all(&state.database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[test]
+fn pick_random_with_seeded_rng_should_be_reproducible() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let customers_table = CustomersTable {
+        records: customers(0..20),
+    };
+
+    let pick_ids = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..5)
+            .map(|_| {
+                customers_table
+                    .pick_random_with(&mut rng)
+                    .and_then(|record| record.id)
+                    .expect("non-empty relation should have a record to pick")
+            })
+            .collect::<Vec<i32>>()
+    };
+
+    assert_eq!(pick_ids(42), pick_ids(42));
+    assert!(customers_table.pick_random().is_some());
+
+    let empty_table = CustomersTable { records: vec![] };
+    assert!(empty_table.pick_random().is_none());
+    assert!(empty_table
+        .pick_random_with(&mut StdRng::seed_from_u64(42))
+        .is_none());
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn insert_many_should_insert_records_atomically() {
+    let customers = customers(240..245);
+
+    let database = get_database().await;
+
+    CustomersTableRecord::insert_many(Vec::new(), &database)
+        .await
+        .expect("empty multi-insertion should succeed");
+
+    CustomersTableRecord::insert_many(customers.clone(), &database)
+        .await
+        .expect("customers multi-insertion failed");
+
+    let count = CustomersTable::count(&database)
+        .await
+        .expect("customers table count failed");
+
+    assert_eq!(count, 5);
+
+    let mut conflicting_customers = customers[..1].to_vec();
+    conflicting_customers[0].id = Some(245);
+    conflicting_customers.push(customers[0].clone());
+    CustomersTableRecord::insert_many(conflicting_customers, &database)
+        .await
+        .expect_err("multi-insertion with a duplicate key should fail");
+
+    let count = CustomersTable::count(&database)
+        .await
+        .expect("customers table count failed");
+
+    assert_eq!(count, 5);
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn writes_should_return_affected_row_counts() {
+    let customers_table = CustomersTable {
+        records: customers(250..254),
+    };
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let update_params = |id| CustomersTableRecordUpdateQueryParameters {
+        id: Some(id),
+        name: Some("Jane Doe".to_string()),
+        email_address: FieldUpdate::Unchanged,
+        phone_number: FieldUpdate::Unchanged,
+        street_address: FieldUpdate::Unchanged,
+    };
+
+    let updated_count = CustomersTable::update_one(&database, update_params(250))
+        .await
+        .expect("customers record update failed");
+
+    assert_eq!(updated_count, 1);
+
+    let updated_count = CustomersTable::update_one(&database, update_params(259))
+        .await
+        .expect("customers record update failed");
+
+    assert_eq!(updated_count, 0);
+
+    let deleted_count = CustomersTable::delete_one(&database, GenericIdParameter::new(250_usize))
+        .await
+        .expect("customers record deletion failed");
+
+    assert_eq!(deleted_count, 1);
+
+    let deleted_count = CustomersTable::delete_one(&database, GenericIdParameter::new(250_usize))
+        .await
+        .expect("customers record deletion failed");
+
+    assert_eq!(deleted_count, 0);
+
+    let deleted_count = CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+
+    assert_eq!(deleted_count, 3);
+}
+
+#[derive(Clone)]
+struct NoContentState {
+    database: PgDatabase,
+}
+
+impl DatabaseState for NoContentState {
+    fn get_database(&self) -> &PgDatabase {
+        &self.database
+    }
+
+    fn get_database_connection(&self) -> &sqlx::PgPool {
+        &self.database.connection
+    }
+
+    fn error_status_code(&self, relation_name: &str, error: &CrudkitError) -> StatusCode {
+        match error.kind {
+            ErrorKind::UnexpectedQueryResult
+                if relation_name == CustomersTable::get_qualified_name() =>
+            {
+                StatusCode::NO_CONTENT
+            }
+            _ => error.status_code,
+        }
+    }
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn handlers_should_use_state_error_status_code() {
+    let database = get_database().await;
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+
+    let default_state = Arc::new(TestState {
+        database: database.clone(),
+    });
+    let response = CustomersTable::query_one_handler::<GenericIdParameter, _>(
+        State(default_state),
+        Query(GenericIdParameter::new(260)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let state = Arc::new(NoContentState { database });
+    let response = CustomersTable::query_one_handler::<GenericIdParameter, _>(
+        State(state.clone()),
+        Query(GenericIdParameter::new(260)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body should be readable");
+    assert!(body.is_empty());
+
+    let response = CustomersTable::query_all_sorted_handler(
+        State(state),
+        Query(SortParams {
+            sort: Some("nonexistent_column".to_string()),
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[derive(Clone)]
+struct EnvelopeState {
+    database: PgDatabase,
+}
+
+impl DatabaseState for EnvelopeState {
+    fn get_database(&self) -> &PgDatabase {
+        &self.database
+    }
+
+    fn get_database_connection(&self) -> &sqlx::PgPool {
+        &self.database.connection
+    }
+
+    fn error_response(&self, relation_name: &str, error: CrudkitError) -> Response {
+        let status_code = self.error_status_code(relation_name, &error);
+        let body = serde_json::json!({
+            "relation": relation_name,
+            "status": status_code.as_u16(),
+        });
+
+        (status_code, [("x-crudkit-error", "true")], Json(body)).into_response()
+    }
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn handlers_should_use_state_error_response() {
+    let database = get_database().await;
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+
+    let state = Arc::new(EnvelopeState { database });
+    let read_response = CustomersTable::query_one_handler::<GenericIdParameter, _>(
+        State(state.clone()),
+        Query(GenericIdParameter::new(270)),
+    )
+    .await;
+    let write_response = CustomersTable::update_one_handler(
+        State(state),
+        Query(CustomersTableRecordUpdateQueryParameters {
+            id: None,
+            name: Some("Jane Doe".to_string()),
+            email_address: FieldUpdate::Unchanged,
+            phone_number: FieldUpdate::Unchanged,
+            street_address: FieldUpdate::Unchanged,
+        }),
+    )
+    .await;
+
+    for (response, status_code) in [
+        (read_response, StatusCode::NOT_FOUND),
+        (write_response, StatusCode::BAD_REQUEST),
+    ] {
+        assert_eq!(response.status(), status_code);
+        assert_eq!(response.headers()["x-crudkit-error"], "true");
+
+        let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("response body should be readable");
+        let body: serde_json::Value =
+            serde_json::from_slice(&body).expect("response body should be JSON");
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "relation": CustomersTable::get_qualified_name(),
+                "status": status_code.as_u16(),
+            })
+        );
+    }
+}
+
+#[cfg(feature = "observer")]
+#[tokio::test]
+#[serial(customers_table)]
+async fn query_observer_should_record_each_query() {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use crudkit::observer::{set_query_observer, QueryObserver};
+
+    static OBSERVED_QUERIES: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+    struct TestObserver;
+
+    impl QueryObserver for TestObserver {
+        fn observe(&self, operation: &str, relation_name: &str, _elapsed: Duration) {
+            OBSERVED_QUERIES
+                .lock()
+                .unwrap()
+                .push((operation.to_string(), relation_name.to_string()));
+        }
+    }
+
+    assert!(set_query_observer(TestObserver).is_ok());
+    assert!(set_query_observer(TestObserver).is_err());
+
+    let database = get_database().await;
+    CustomersTable::count(&database)
+        .await
+        .expect("customers table count failed");
+
+    assert!(OBSERVED_QUERIES
+        .lock()
+        .unwrap()
+        .contains(&("count_where".to_string(), "main.customers".to_string())));
+}
+
+#[cfg(feature = "openapi")]
+#[test]
+fn crud_openapi_should_describe_crud_endpoints() {
+    use crudkit::openapi::{crud_openapi, OpenApiFields};
+    use crudkit::utoipa::openapi::path::ParameterIn;
+    use crudkit::utoipa::openapi::Required;
+
+    let paths = crud_openapi::<CustomersTable, GenericIdParameter>("/customers");
+
+    let root_path_item = paths.get_path_item("/customers").unwrap();
+    assert!(root_path_item.get.is_some());
+    assert!(root_path_item.delete.is_some());
+
+    let create_parameters = root_path_item
+        .post
+        .as_ref()
+        .unwrap()
+        .parameters
+        .as_ref()
+        .unwrap();
+    let create_parameter_names: Vec<&str> = create_parameters
+        .iter()
+        .map(|parameter| parameter.name.as_str())
+        .collect();
+    assert_eq!(
+        create_parameter_names,
+        ["name", "email_address", "phone_number", "street_address"]
+    );
+    assert!(create_parameters
+        .iter()
+        .all(|parameter| parameter.parameter_in == ParameterIn::Query));
+    assert!(create_parameters[0].required == Required::True);
+    assert!(create_parameters[1].required == Required::False);
+
+    let update_parameters = root_path_item
+        .patch
+        .as_ref()
+        .unwrap()
+        .parameters
+        .as_ref()
+        .unwrap();
+    assert_eq!(update_parameters.len(), 5);
+    assert!(update_parameters
+        .iter()
+        .all(|parameter| parameter.required == Required::False));
+
+    let one_path_item = paths.get_path_item("/customers/one").unwrap();
+    let query_one_operation = one_path_item.get.as_ref().unwrap();
+    assert_eq!(
+        query_one_operation.parameters.as_ref().unwrap()[0].name,
+        "id"
+    );
+    assert!(query_one_operation.responses.responses.contains_key("200"));
+    assert!(query_one_operation
+        .responses
+        .responses
+        .contains_key("default"));
+    assert!(one_path_item.delete.is_some());
+
+    let record_field_names: Vec<&str> = CustomersTableRecord::FIELDS
+        .iter()
+        .map(|field| field.name)
+        .collect();
+    assert_eq!(
+        record_field_names,
+        [
+            "id",
+            "name",
+            "email_address",
+            "phone_number",
+            "street_address"
+        ]
+    );
+    assert_eq!(
+        CustomersTableRecord::FIELDS[0].postgres_type,
+        Some("integer")
+    );
+}
+
+#[derive(Relation, Serialize)]
+#[relation(view, relation_name = "customer_names", primary_key = "id")]
+pub struct CustomerNamesView {
+    records: Vec<CustomerNamesViewRecord>,
+}
+
+#[derive(Record, sqlx::FromRow, Clone, Serialize)]
+pub struct CustomerNamesViewRecord {
+    pub id: i32,
+    pub name: String,
+}
+
+#[tokio::test]
+#[serial(customers_table)]
+async fn view_should_be_readable() {
+    let customers_table = CustomersTable::with_records(customers(235..=236));
+
+    let database = get_database().await;
+
+    customers_table
+        .insert_all(&database)
+        .await
+        .expect("customers table creation failed");
+
+    let customer_names = CustomerNamesView::query_all(&database)
+        .await
+        .expect("customer names view query failed")
+        .take_records();
+
+    assert_eq!(customer_names.len(), 2);
+    assert_eq!(customer_names[0].id, 235);
+    assert_eq!(customer_names[1].name, "John Doe 236");
+
+    let customer_name = CustomerNamesView::query_one(&database, GenericIdParameter::new(236))
+        .await
+        .expect("customer names view query failed");
+
+    assert_eq!(customer_name.name, "John Doe 236");
+
+    CustomersTable::delete_all(&database)
+        .await
+        .expect("customers table deletion failed");
+}
@@ -0,0 +1,2228 @@
+// TODO: creating a module like this is a little messy, refactor this to something better in the
+// future.
+#[path = "./database_connection.rs"]
+mod database_connection;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::query_builder::Separated;
+use sqlx::Postgres;
+
+use crudkit::axum::extract::{Query, State};
+use crudkit::database::ServerState;
+use crudkit::filter::{Filter, RangeFilter};
+use crudkit::http::StatusCode;
+use crudkit::prelude::*;
+use crudkit::serde_json;
+use database_connection::get_database;
+use serial_test::serial;
+
+#[derive(sqlx::FromRow, Clone, Debug, PartialEq, Serialize, serde::Deserialize)]
+pub struct Money {
+    amount_cents: i64,
+    currency: String,
+}
+
+fn bind_money<Sep: Display>(builder: &mut Separated<'_, '_, Postgres, Sep>, money: Money) {
+    builder.push_bind(money.amount_cents);
+    builder.push_bind(money.currency);
+}
+
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(relation_name = "products", primary_key = "id")]
+pub struct ProductsTable {
+    records: Vec<ProductsTableRecord>,
+}
+
+#[derive(Record, ReadRecord, WriteRecord, SingleInsert, sqlx::FromRow, Clone, Serialize)]
+pub struct ProductsTableRecord {
+    #[manual_primary_key]
+    pub id: i32,
+    #[no_overwrite]
+    pub name: String,
+    #[sqlx(flatten)]
+    #[columns(names = ["amount_cents", "currency"], binder = bind_money)]
+    pub price: Money,
+}
+
+#[tokio::test]
+#[serial(products_table)]
+async fn multi_column_field_should_insert_and_update() {
+    assert_eq!(
+        ProductsTableRecord::COLUMN_NAMES,
+        &["id", "name", "amount_cents", "currency"]
+    );
+    assert_eq!(
+        ProductsTableRecord::insert_columns(),
+        vec!["id", "name", "amount_cents", "currency"]
+    );
+
+    let new_record = ProductsTableRecord {
+        id: 1,
+        name: "Widget".to_string(),
+        price: Money {
+            amount_cents: 1999,
+            currency: "USD".to_string(),
+        },
+    };
+
+    assert_eq!(
+        new_record.get_column("currency"),
+        Some(serde_json::json!("USD"))
+    );
+
+    let database = get_database().await;
+
+    new_record
+        .insert(&database)
+        .await
+        .expect("products record creation failed");
+
+    let updated_record = ProductsTableRecordUpdateQueryParameters {
+        id: 1,
+        name: Some("Gadget".to_string()),
+        price: Some(Money {
+            amount_cents: 2499,
+            currency: "EUR".to_string(),
+        }),
+    };
+    ProductsTable::update_one(&database, updated_record)
+        .await
+        .expect("products record update failed");
+
+    let record = ProductsTable::query_one(&database, GenericIdParameter::new(1))
+        .await
+        .expect("products record query failed");
+
+    assert_eq!(record.name, "Gadget".to_string());
+    assert_eq!(
+        record.price,
+        Money {
+            amount_cents: 2499,
+            currency: "EUR".to_string(),
+        }
+    );
+
+    ProductsTable::delete_all(&database)
+        .await
+        .expect("products table deletion failed");
+}
+
+#[tokio::test]
+#[serial(products_table)]
+async fn upsert_should_keep_no_overwrite_columns() {
+    assert_eq!(ProductsTableRecord::NO_OVERWRITE_COLUMN_NAMES, &["name"]);
+
+    let database = get_database().await;
+
+    for (name, amount_cents) in [("Widget", 1999), ("Gadget", 2499)] {
+        ProductsTableRecord {
+            id: 2,
+            name: name.to_string(),
+            price: Money {
+                amount_cents,
+                currency: "USD".to_string(),
+            },
+        }
+        .upsert(&database)
+        .await
+        .expect("products record upsert failed");
+    }
+
+    let record = ProductsTable::query_one(&database, GenericIdParameter::new(2))
+        .await
+        .expect("products record query failed");
+
+    assert_eq!(record.name, "Widget".to_string());
+    assert_eq!(record.price.amount_cents, 2499);
+
+    ProductsTable::delete_all(&database)
+        .await
+        .expect("products table deletion failed");
+}
+
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(
+    relation_name = "customer_products",
+    primary_key = "(customer_id, product_id)"
+)]
+pub struct CustomerProductsTable {
+    records: Vec<CustomerProductsTableRecord>,
+}
+
+#[derive(Record, ReadRecord, WriteRecord, SingleInsert, sqlx::FromRow, Clone, Serialize)]
+pub struct CustomerProductsTableRecord {
+    #[manual_primary_key]
+    pub customer_id: i32,
+    #[manual_primary_key]
+    pub product_id: i32,
+    pub quantity: i32,
+}
+
+#[tokio::test]
+#[serial(customer_products_table)]
+async fn query_many_composite_should_fetch_matching_keys() {
+    let records = (1..=3)
+        .flat_map(|customer_id| {
+            (1..=3).map(move |product_id| CustomerProductsTableRecord {
+                customer_id,
+                product_id,
+                quantity: customer_id * 10 + product_id,
+            })
+        })
+        .collect();
+    let customer_products_table = CustomerProductsTable { records };
+
+    let database = get_database().await;
+
+    customer_products_table
+        .insert_all(&database)
+        .await
+        .expect("customer products table creation failed");
+
+    let records =
+        CustomerProductsTable::query_many_composite(&database, vec![(1, 2), (3, 1), (4, 4)])
+            .await
+            .expect("customer products composite query failed")
+            .records;
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].quantity, 12);
+    assert_eq!(records[1].quantity, 31);
+
+    let error = CustomerProductsTable::query_many_composite(&database, vec![(1, 2, 3)])
+        .await
+        .err()
+        .expect("composite key with wrong column count should fail");
+
+    assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+
+    CustomerProductsTable::delete_all(&database)
+        .await
+        .expect("customer products table deletion failed");
+}
+
+#[tokio::test]
+#[serial(customer_products_table)]
+async fn query_one_and_delete_one_composite_should_work() {
+    assert_eq!(
+        CustomerProductsTable::PRIMARY_KEY_COLUMNS,
+        &["customer_id", "product_id"]
+    );
+    assert_eq!(
+        CustomerProductsTable::get_primary_key_tuple(),
+        "(customer_id, product_id)"
+    );
+
+    let records = vec![
+        CustomerProductsTableRecord {
+            customer_id: 1,
+            product_id: 2,
+            quantity: 5,
+        },
+        CustomerProductsTableRecord {
+            customer_id: 2,
+            product_id: 1,
+            quantity: 7,
+        },
+    ];
+    let customer_products_table = CustomerProductsTable { records };
+
+    let database = get_database().await;
+
+    customer_products_table
+        .insert_all(&database)
+        .await
+        .expect("customer products table creation failed");
+
+    let record = CustomerProductsTable::query_one_composite(&database, (2, 1))
+        .await
+        .expect("customer products composite query failed");
+
+    assert_eq!(record.quantity, 7);
+
+    let error = CustomerProductsTable::query_one(&database, GenericIdParameter::new(1))
+        .await
+        .err()
+        .expect("single-column key on composite relation should fail");
+
+    assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+
+    CustomerProductsTable::delete_one_composite(&database, (2, 1))
+        .await
+        .expect("customer products composite deletion failed");
+
+    let records = CustomerProductsTable::query_all(&database)
+        .await
+        .expect("customer products table query failed")
+        .records;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].quantity, 5);
+
+    CustomerProductsTable::delete_all(&database)
+        .await
+        .expect("customer products table deletion failed");
+}
+
+#[derive(CompositeIdParameter, serde::Deserialize)]
+pub struct CustomerProductKeyParameter {
+    customer_id: i32,
+    product_id: i32,
+}
+
+#[tokio::test]
+#[serial(customer_products_table)]
+async fn composite_id_parameter_handlers_should_use_every_field() {
+    let records = vec![
+        CustomerProductsTableRecord {
+            customer_id: 3,
+            product_id: 4,
+            quantity: 9,
+        },
+        CustomerProductsTableRecord {
+            customer_id: 3,
+            product_id: 5,
+            quantity: 11,
+        },
+    ];
+    let customer_products_table = CustomerProductsTable { records };
+
+    let database = get_database().await;
+
+    customer_products_table
+        .insert_all(&database)
+        .await
+        .expect("customer products table creation failed");
+
+    let state = Arc::new(ServerState::new(database));
+    let response = CustomerProductsTable::query_one_composite_handler(
+        State(state.clone()),
+        Query(CustomerProductKeyParameter::new((3, 5))),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body["quantity"], 11);
+
+    let status_code = CustomerProductsTable::delete_one_composite_handler(
+        State(state.clone()),
+        Query(CustomerProductKeyParameter::new((3, 4))),
+    )
+    .await
+    .status();
+
+    assert_eq!(status_code, StatusCode::OK);
+
+    let records = CustomerProductsTable::query_all(&state.database)
+        .await
+        .expect("customer products table query failed")
+        .records;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].quantity, 11);
+
+    CustomerProductsTable::delete_all(&state.database)
+        .await
+        .expect("customer products table deletion failed");
+}
+
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(relation_name = "orders", primary_key = "id", key_type = i64)]
+pub struct OrdersTable {
+    records: Vec<OrdersTableRecord>,
+}
+
+#[derive(
+    Record,
+    ReadRecord,
+    WriteRecord,
+    SingleInsert,
+    IdentifiableRecord,
+    sqlx::FromRow,
+    Clone,
+    Serialize,
+)]
+pub struct OrdersTableRecord {
+    #[manual_primary_key]
+    pub id: i64,
+    pub customer_id: i32,
+    pub total_cents: i64,
+}
+
+#[tokio::test]
+#[serial(orders_table)]
+async fn query_one_and_delete_one_should_bind_bigint_keys() {
+    let id = i32::MAX as i64 + 1;
+    let database = get_database().await;
+
+    for (id, total_cents) in [(1, 500), (id, 1000)] {
+        OrdersTableRecord {
+            id,
+            customer_id: 1,
+            total_cents,
+        }
+        .insert(&database)
+        .await
+        .expect("orders record creation failed");
+    }
+
+    let record = OrdersTable::query_one(&database, GenericIdParameter::new(id as usize))
+        .await
+        .expect("orders record query failed");
+
+    assert_eq!(record.id(), Some(id));
+    assert_eq!(record.total_cents, 1000);
+
+    OrdersTable::delete_one(&database, GenericIdParameter::new(id as usize))
+        .await
+        .expect("orders record deletion failed");
+
+    let records = OrdersTable::query_all(&database)
+        .await
+        .expect("orders table query failed")
+        .records;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].id, 1);
+
+    OrdersTable::delete_all(&database)
+        .await
+        .expect("orders table deletion failed");
+}
+
+#[cfg(feature = "uuid")]
+mod uuid_devices {
+    use super::*;
+
+    use crudkit::uuid::Uuid;
+
+    #[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+    #[relation(relation_name = "devices", primary_key = "id", key_type = Uuid)]
+    pub struct DevicesTable {
+        records: Vec<DevicesTableRecord>,
+    }
+
+    #[derive(
+        Record,
+        ReadRecord,
+        WriteRecord,
+        SingleInsert,
+        IdentifiableRecord,
+        sqlx::FromRow,
+        Clone,
+        Serialize,
+    )]
+    pub struct DevicesTableRecord {
+        #[auto_primary_key]
+        pub id: Option<Uuid>,
+        pub name: String,
+    }
+
+    #[tokio::test]
+    #[serial(devices_table)]
+    async fn query_one_and_delete_one_should_bind_uuid_keys() {
+        let database = get_database().await;
+
+        let create_params = DevicesTableRecordCreateQueryParameters {
+            name: "Sensor".to_string(),
+        };
+        let record = DevicesTable::create_one_returning(&database, create_params)
+            .await
+            .expect("devices record creation failed");
+        let id = record
+            .id()
+            .expect("created devices record should have an id");
+
+        let record = DevicesTable::query_one(&database, GenericIdParameter::new(id))
+            .await
+            .expect("devices record query failed");
+
+        assert_eq!(record.name, "Sensor".to_string());
+
+        DevicesTable::delete_one(&database, GenericIdParameter::new(id))
+            .await
+            .expect("devices record deletion failed");
+
+        let records = DevicesTable::query_all(&database)
+            .await
+            .expect("devices table query failed")
+            .records;
+
+        assert!(records.is_empty());
+    }
+}
+
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(relation_name = "wide_records", primary_key = "id")]
+pub struct WideRecordsTable {
+    records: Vec<WideRecordsTableRecord>,
+}
+
+#[derive(Record, ReadRecord, WriteRecord, SingleInsert, sqlx::FromRow, Clone, Serialize)]
+pub struct WideRecordsTableRecord {
+    #[manual_primary_key]
+    pub id: i32,
+    pub column_01: i32,
+    pub column_02: i32,
+    pub column_03: i32,
+    pub column_04: i32,
+    pub column_05: i32,
+    pub column_06: i32,
+    pub column_07: i32,
+    pub column_08: i32,
+    pub column_09: i32,
+    pub column_10: i32,
+    pub column_11: i32,
+    pub column_12: i32,
+    #[generated_column]
+    pub column_sum: i32,
+}
+
+#[tokio::test]
+#[serial(wide_records_table)]
+async fn insert_all_should_fill_chunks_up_to_bind_limit() {
+    // * The generated column is not written, so each record binds 13 of its 14 columns
+    let chunk_size = <WideRecordsTable as BulkInsert>::CHUNK_SIZE;
+    assert_eq!(WideRecordsTableRecord::MAX_BINDINGS_PER_RECORD, 13);
+    assert_eq!(chunk_size, u16::MAX as usize / 13);
+    assert!(chunk_size * 13 <= u16::MAX as usize);
+    assert!((chunk_size + 1) * 13 > u16::MAX as usize);
+
+    let records = (0..chunk_size as i32 * 2 + 1)
+        .map(|i| WideRecordsTableRecord {
+            id: i,
+            column_01: i,
+            column_02: 2,
+            column_03: 3,
+            column_04: 4,
+            column_05: 5,
+            column_06: 6,
+            column_07: 7,
+            column_08: 8,
+            column_09: 9,
+            column_10: 10,
+            column_11: 11,
+            column_12: 12,
+            column_sum: 0,
+        })
+        .collect();
+    let wide_records_table = WideRecordsTable { records };
+
+    let database = get_database().await;
+
+    wide_records_table
+        .insert_all(&database)
+        .await
+        .expect("wide records table creation failed");
+
+    let count = WideRecordsTable::count(&database)
+        .await
+        .expect("wide records table count failed");
+
+    assert_eq!(count, chunk_size as i64 * 2 + 1);
+
+    let record = WideRecordsTable::query_one(&database, GenericIdParameter::new(5_usize))
+        .await
+        .expect("wide records record query failed");
+
+    assert_eq!(record.column_sum, 17);
+
+    WideRecordsTable::delete_all(&database)
+        .await
+        .expect("wide records table deletion failed");
+}
+
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(relation_name = "notes", primary_key = "id")]
+pub struct NotesTable {
+    records: Vec<NotesTableRecord>,
+}
+
+#[derive(
+    Record,
+    ReadRecord,
+    WriteRecord,
+    SingleInsert,
+    IdentifiableRecord,
+    sqlx::FromRow,
+    Clone,
+    Serialize,
+)]
+pub struct NotesTableRecord {
+    #[auto_primary_key]
+    pub id: Option<i32>,
+    pub body: String,
+    #[created_timestamp]
+    pub created_at: DateTime<Utc>,
+    #[updated_timestamp]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[tokio::test]
+#[serial(notes_table)]
+async fn timestamp_columns_should_be_set_by_library() {
+    let database = get_database().await;
+
+    let record = NotesTable::create_one_returning(
+        &database,
+        NotesTableRecordCreateQueryParameters {
+            body: "First draft".to_string(),
+        },
+    )
+    .await
+    .expect("notes record creation failed");
+
+    assert_eq!(record.created_at, record.updated_at);
+    assert!(record.created_at > DateTime::<Utc>::default());
+
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    NotesTable::update_one(
+        &database,
+        NotesTableRecordUpdateQueryParameters {
+            id: record.id,
+            body: Some("Second draft".to_string()),
+        },
+    )
+    .await
+    .expect("notes record update failed");
+
+    let id = record.id.expect("notes record should have an ID") as usize;
+    let updated_record = NotesTable::query_one(&database, GenericIdParameter::new(id))
+        .await
+        .expect("notes record query failed");
+
+    assert_eq!(updated_record.body, "Second draft".to_string());
+    assert_eq!(updated_record.created_at, record.created_at);
+    assert!(updated_record.updated_at > record.updated_at);
+
+    NotesTable::update_one(
+        &database,
+        NotesTableRecordUpdateQueryParameters {
+            id: record.id,
+            body: None,
+        },
+    )
+    .await
+    .expect_err("empty notes record update should fail");
+
+    NotesTable::delete_all(&database)
+        .await
+        .expect("notes table deletion failed");
+}
+
+#[tokio::test]
+#[serial(notes_table)]
+async fn range_filters_should_match_inclusive_bounds() {
+    let database = get_database().await;
+
+    // * Timestamps are set by the library on insertion, so fixed values are written directly
+    sqlx::query(
+        "INSERT INTO main.notes (body, created_at, updated_at) VALUES \
+        ('January', '2025-01-15T00:00:00Z', NOW()), \
+        ('February', '2025-02-15T00:00:00Z', NOW()), \
+        ('March', '2025-03-15T00:00:00Z', NOW())",
+    )
+    .execute(&database.connection)
+    .await
+    .expect("notes table creation failed");
+
+    let timestamp = |timestamp: &str| {
+        timestamp
+            .parse::<DateTime<Utc>>()
+            .expect("timestamp should be valid")
+    };
+    let query_bodies = |range: RangeFilter<DateTime<Utc>>| {
+        let database = &database;
+        async move {
+            NotesTable::query_where(database, &[Filter::range("created_at", range)])
+                .await
+                .expect("notes table query failed")
+                .records
+                .iter()
+                .map(|record| record.body.clone())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    let closed = RangeFilter {
+        from: Some(timestamp("2025-01-15T00:00:00Z")),
+        to: Some(timestamp("2025-02-15T00:00:00Z")),
+    };
+    assert_eq!(query_bodies(closed).await, ["January", "February"]);
+
+    let open_start = RangeFilter {
+        from: None,
+        to: Some(timestamp("2025-02-01T00:00:00Z")),
+    };
+    assert_eq!(query_bodies(open_start).await, ["January"]);
+
+    let open_end = RangeFilter {
+        from: Some(timestamp("2025-02-15T00:00:00Z")),
+        to: None,
+    };
+    assert_eq!(query_bodies(open_end).await, ["February", "March"]);
+
+    assert_eq!(
+        query_bodies(RangeFilter::default()).await,
+        ["January", "February", "March"]
+    );
+
+    let state = Arc::new(ServerState::new(database));
+    let query_range = |parameters: &[(&str, &str)]| {
+        let parameters = parameters
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        NotesTable::query_range_handler(State(state.clone()), Query(parameters))
+    };
+
+    let response = query_range(&[
+        ("created_at_from", "2025-02-01T00:00:00Z"),
+        ("created_at_to", "2025-03-15T00:00:00Z"),
+    ])
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = crudkit::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body should be readable");
+    let body: serde_json::Value =
+        serde_json::from_slice(&body).expect("response body should be JSON");
+    let bodies: Vec<_> = body["records"]
+        .as_array()
+        .expect("response body should contain records")
+        .iter()
+        .map(|record| record["body"].as_str())
+        .collect();
+    assert_eq!(bodies, vec![Some("February"), Some("March")]);
+
+    let response = query_range(&[("created_at_to", "2025-01-31T00:00:00Z")]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    for parameters in [
+        &[("created_at", "2025-01-01T00:00:00Z")][..],
+        &[("created_at_before", "2025-01-01T00:00:00Z")][..],
+        &[("published_at_from", "2025-01-01T00:00:00Z")][..],
+    ] {
+        let response = query_range(parameters).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let response = query_range(&[("created_at_from", "not a timestamp")]).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    NotesTable::delete_all(&state.database)
+        .await
+        .expect("notes table deletion failed");
+}
+
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(relation_name = "documents", primary_key = "id")]
+pub struct DocumentsTable {
+    records: Vec<DocumentsTableRecord>,
+}
+
+#[derive(
+    Record,
+    ReadRecord,
+    WriteRecord,
+    SingleInsert,
+    IdentifiableRecord,
+    sqlx::FromRow,
+    Clone,
+    Serialize,
+)]
+pub struct DocumentsTableRecord {
+    #[auto_primary_key]
+    pub id: Option<i32>,
+    pub title: String,
+    #[version]
+    pub version: i32,
+}
+
+#[tokio::test]
+#[serial(documents_table)]
+async fn update_one_should_reject_stale_version() {
+    let database = get_database().await;
+
+    let record = DocumentsTable::create_one_returning(
+        &database,
+        DocumentsTableRecordCreateQueryParameters {
+            title: "Draft".to_string(),
+        },
+    )
+    .await
+    .expect("documents record creation failed");
+
+    assert_eq!(record.version, 0);
+
+    let updated_record = DocumentsTable::update_one_returning(
+        &database,
+        DocumentsTableRecordUpdateQueryParameters {
+            id: record.id,
+            title: Some("First edit".to_string()),
+            version: 0,
+        },
+    )
+    .await
+    .expect("documents record update failed");
+
+    assert_eq!(updated_record.title, "First edit".to_string());
+    assert_eq!(updated_record.version, 1);
+
+    let error = DocumentsTable::update_one(
+        &database,
+        DocumentsTableRecordUpdateQueryParameters {
+            id: record.id,
+            title: Some("Conflicting edit".to_string()),
+            version: 0,
+        },
+    )
+    .await
+    .expect_err("stale documents record update should fail");
+
+    assert!(matches!(error.kind, ErrorKind::Conflict));
+    assert_eq!(error.status_code, StatusCode::CONFLICT);
+
+    DocumentsTable::update_one(
+        &database,
+        DocumentsTableRecordUpdateQueryParameters {
+            id: record.id,
+            title: Some("Second edit".to_string()),
+            version: 1,
+        },
+    )
+    .await
+    .expect("documents record update failed");
+
+    let id = record.id.expect("documents record should have an ID") as usize;
+    let current_record = DocumentsTable::query_one(&database, GenericIdParameter::new(id))
+        .await
+        .expect("documents record query failed");
+
+    assert_eq!(current_record.title, "Second edit".to_string());
+    assert_eq!(current_record.version, 2);
+
+    DocumentsTable::delete_all(&database)
+        .await
+        .expect("documents table deletion failed");
+}
+
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(
+    relation_name = "articles",
+    primary_key = "id",
+    soft_delete = "deleted_at"
+)]
+pub struct ArticlesTable {
+    records: Vec<ArticlesTableRecord>,
+}
+
+#[derive(
+    Record,
+    ReadRecord,
+    WriteRecord,
+    SingleInsert,
+    IdentifiableRecord,
+    sqlx::FromRow,
+    Clone,
+    Serialize,
+)]
+pub struct ArticlesTableRecord {
+    #[manual_primary_key]
+    pub id: i32,
+    pub title: String,
+    #[defaultable]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[tokio::test]
+#[serial(articles_table)]
+async fn soft_deleted_records_should_be_hidden_until_restored() {
+    let records = (1..=3)
+        .map(|id| ArticlesTableRecord {
+            id,
+            title: format!("Article {id}"),
+            deleted_at: None,
+        })
+        .collect();
+    let articles_table = ArticlesTable { records };
+
+    let database = get_database().await;
+
+    articles_table
+        .insert_all(&database)
+        .await
+        .expect("articles table creation failed");
+
+    ArticlesTable::delete_one(&database, GenericIdParameter::new(2_usize))
+        .await
+        .expect("articles record deletion failed");
+
+    let ids: Vec<i32> = ArticlesTable::query_all(&database)
+        .await
+        .expect("articles table query failed")
+        .records
+        .iter()
+        .map(|record| record.id)
+        .collect();
+
+    assert_eq!(ids, vec![1, 3]);
+
+    ArticlesTable::query_one(&database, GenericIdParameter::new(2_usize))
+        .await
+        .err()
+        .expect("deleted articles record should not be found");
+
+    let count = ArticlesTable::count(&database)
+        .await
+        .expect("articles table count failed");
+
+    assert_eq!(count, 2);
+
+    let records = ArticlesTable::query_all_including_deleted(&database)
+        .await
+        .expect("articles table query failed")
+        .records;
+
+    assert_eq!(records.len(), 3);
+    assert!(records[1].deleted_at.is_some());
+
+    ArticlesTable::restore_one(&database, GenericIdParameter::new(2_usize))
+        .await
+        .expect("articles record restoration failed");
+
+    let record = ArticlesTable::query_one(&database, GenericIdParameter::new(2_usize))
+        .await
+        .expect("articles record query failed");
+
+    assert!(record.deleted_at.is_none());
+
+    ArticlesTable::delete_all(&database)
+        .await
+        .expect("articles table deletion failed");
+
+    let count = ArticlesTable::count(&database)
+        .await
+        .expect("articles table count failed");
+
+    assert_eq!(count, 0);
+
+    // * Soft deletes leave the records in place, so they are removed directly
+    sqlx::query("DELETE FROM main.articles")
+        .execute(&database.connection)
+        .await
+        .expect("articles table purge failed");
+}
+
+// * The same relation, keyed by both columns so that the composite-key methods can be used
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(
+    relation_name = "articles",
+    primary_key = "(id, title)",
+    soft_delete = "deleted_at"
+)]
+pub struct TitledArticlesTable {
+    records: Vec<TitledArticlesTableRecord>,
+}
+
+#[derive(Record, ReadRecord, WriteRecord, SingleInsert, sqlx::FromRow, Clone, Serialize)]
+pub struct TitledArticlesTableRecord {
+    #[manual_primary_key]
+    pub id: i32,
+    #[manual_primary_key]
+    pub title: String,
+    #[defaultable]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[tokio::test]
+#[serial(articles_table)]
+async fn composite_and_conditional_deletes_should_soft_delete() {
+    let records = (1..=5)
+        .map(|id| TitledArticlesTableRecord {
+            id,
+            title: format!("Article {id}"),
+            deleted_at: None,
+        })
+        .collect();
+    let articles_table = TitledArticlesTable { records };
+
+    let database = get_database().await;
+
+    articles_table
+        .insert_all(&database)
+        .await
+        .expect("articles table creation failed");
+
+    let deleted_count =
+        TitledArticlesTable::delete_one_composite(&database, (1, "Article 1".to_string()))
+            .await
+            .expect("articles composite deletion failed");
+
+    assert_eq!(deleted_count, 1);
+
+    TitledArticlesTable::query_one_composite(&database, (1, "Article 1".to_string()))
+        .await
+        .err()
+        .expect("deleted articles record should not be found");
+
+    let records = TitledArticlesTable::query_many_composite(
+        &database,
+        vec![(1, "Article 1".to_string()), (2, "Article 2".to_string())],
+    )
+    .await
+    .expect("articles composite query failed")
+    .records;
+
+    let ids: Vec<i32> = records.iter().map(|record| record.id).collect();
+    assert_eq!(ids, vec![2]);
+
+    let deleted_count =
+        TitledArticlesTable::delete_where(&database, &[Filter::equals("title", "Article 2")])
+            .await
+            .expect("articles conditional deletion failed");
+
+    assert_eq!(deleted_count, 1);
+
+    let filters = [
+        Filter::equals("title", "Article 2"),
+        Filter::equals("id", 2),
+    ];
+    let deleted_count = TitledArticlesTable::delete_where(&database, &filters)
+        .await
+        .expect("articles conditional deletion failed");
+
+    assert_eq!(
+        deleted_count, 0,
+        "deleted articles record should not be deleted again"
+    );
+
+    let deleted_records =
+        TitledArticlesTable::delete_where_returning(&database, &[Filter::equals("id", 3)])
+            .await
+            .expect("articles conditional deletion failed");
+
+    assert_eq!(deleted_records.len(), 1);
+    assert!(deleted_records[0].deleted_at.is_some());
+
+    let ids: Vec<i32> = TitledArticlesTable::query_all(&database)
+        .await
+        .expect("articles table query failed")
+        .records
+        .iter()
+        .map(|record| record.id)
+        .collect();
+
+    assert_eq!(ids, vec![4, 5]);
+
+    let records = TitledArticlesTable::query_all_including_deleted(&database)
+        .await
+        .expect("articles table query failed")
+        .records;
+
+    assert_eq!(records.len(), 5);
+
+    // * Soft deletes leave the records in place, so they are removed directly
+    sqlx::query("DELETE FROM main.articles")
+        .execute(&database.connection)
+        .await
+        .expect("articles table purge failed");
+}
+
+#[tokio::test]
+#[serial(orders_table)]
+async fn restore_one_should_reject_relation_without_soft_deletes() {
+    let database = get_database().await;
+
+    let error = OrdersTable::restore_one(&database, GenericIdParameter::new(1_usize))
+        .await
+        .expect_err("restoring orders record should fail");
+
+    assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+}
+
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(relation_name = "bookmarks", primary_key = "id")]
+pub struct BookmarksTable {
+    records: Vec<BookmarksTableRecord>,
+}
+
+#[derive(
+    Record,
+    ReadRecord,
+    WriteRecord,
+    SingleInsert,
+    GenerateTable,
+    IdentifiableRecord,
+    sqlx::FromRow,
+    Clone,
+    Serialize,
+)]
+pub struct BookmarksTableRecord {
+    #[auto_primary_key]
+    pub id: Option<i32>,
+    pub url: String,
+    pub visits: i64,
+    pub note: Option<String>,
+}
+
+#[tokio::test]
+#[serial(bookmarks_table)]
+async fn create_table_should_create_relation_matching_record() {
+    assert_eq!(
+        BookmarksTableRecord::create_table_sql(),
+        "CREATE TABLE IF NOT EXISTS main.bookmarks (id serial PRIMARY KEY, url text NOT NULL, \
+        visits bigint NOT NULL, note text)"
+    );
+
+    let database = get_database().await;
+
+    sqlx::query("DROP TABLE IF EXISTS main.bookmarks")
+        .execute(&database.connection)
+        .await
+        .expect("bookmarks table cleanup failed");
+
+    database
+        .create_table::<BookmarksTableRecord>()
+        .await
+        .expect("bookmarks table generation failed");
+    database
+        .create_table::<BookmarksTableRecord>()
+        .await
+        .expect("bookmarks table generation should be idempotent");
+
+    let diff = database
+        .diff_schema::<BookmarksTableRecord>()
+        .await
+        .expect("bookmarks schema introspection failed");
+
+    assert!(diff.is_empty(), "unexpected schema diff: {diff:?}");
+
+    BookmarksTableRecord {
+        id: None,
+        url: "https://example.com".to_string(),
+        visits: 3,
+        note: None,
+    }
+    .insert(&database)
+    .await
+    .expect("bookmarks record creation failed");
+
+    let records = BookmarksTable::query_all(&database)
+        .await
+        .expect("bookmarks table query failed")
+        .records;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].id, Some(1));
+
+    sqlx::query("DROP TABLE main.bookmarks")
+        .execute(&database.connection)
+        .await
+        .expect("bookmarks table cleanup failed");
+}
+
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(relation_name = "vehicles", primary_key = "id")]
+pub struct VehiclesTable {
+    records: Vec<VehiclesTableRecord>,
+}
+
+#[derive(
+    Record,
+    ReadRecord,
+    WriteRecord,
+    SingleInsert,
+    IdentifiableRecord,
+    sqlx::FromRow,
+    Clone,
+    Serialize,
+)]
+pub struct VehiclesTableRecord {
+    #[auto_primary_key]
+    pub id: Option<i32>,
+    #[column(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub kind: String,
+    #[column(rename = "license_plate")]
+    #[sqlx(rename = "license_plate")]
+    pub plate: Option<String>,
+    #[skip]
+    #[sqlx(skip)]
+    pub display_name: String,
+}
+
+#[tokio::test]
+#[serial(vehicles_table)]
+async fn renamed_columns_should_use_database_names() {
+    assert_eq!(
+        VehiclesTableRecord::COLUMN_NAMES,
+        &["id", "type", "license_plate"]
+    );
+
+    let database = get_database().await;
+
+    let diff = database
+        .diff_schema::<VehiclesTableRecord>()
+        .await
+        .expect("vehicles schema introspection failed");
+
+    assert!(diff.is_empty(), "unexpected schema diff: {diff:?}");
+
+    let vehicles_table = VehiclesTable {
+        records: vec![VehiclesTableRecord {
+            id: Some(1),
+            kind: "truck".to_string(),
+            plate: None,
+            display_name: String::new(),
+        }],
+    };
+
+    vehicles_table
+        .insert_all(&database)
+        .await
+        .expect("vehicles table creation failed");
+
+    VehiclesTable::update_one(
+        &database,
+        VehiclesTableRecordUpdateQueryParameters {
+            id: Some(1),
+            kind: Some("van".to_string()),
+            plate: FieldUpdate::Set("ABC-123".to_string()),
+        },
+    )
+    .await
+    .expect("vehicles record update failed");
+
+    let record = VehiclesTable::query_one(&database, GenericIdParameter::new(1_usize))
+        .await
+        .expect("vehicles record query failed");
+
+    assert_eq!(record.kind, "van");
+    assert_eq!(record.plate, Some("ABC-123".to_string()));
+    assert_eq!(
+        record.get_column("type"),
+        Some(serde_json::Value::from("van"))
+    );
+
+    VehiclesTable::delete_all(&database)
+        .await
+        .expect("vehicles table deletion failed");
+}
+
+#[tokio::test]
+#[serial(vehicles_table)]
+async fn skipped_fields_should_be_excluded_from_sql() {
+    let database = get_database().await;
+
+    let record = VehiclesTableRecord::from(VehiclesTableRecordCreateQueryParameters {
+        kind: "bus".to_string(),
+        plate: None,
+    });
+
+    assert_eq!(record.display_name, "");
+
+    record
+        .insert(&database)
+        .await
+        .expect("vehicles record creation failed");
+
+    let records = VehiclesTable::query_all(&database)
+        .await
+        .expect("vehicles table query failed")
+        .records;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].get_column("display_name"), None);
+
+    VehiclesTable::delete_all(&database)
+        .await
+        .expect("vehicles table deletion failed");
+}
+
+// * `sqlx::FromRow` binds each field to a local variable of the same name, so the lint must be
+// * allowed for the whole module rather than only on the record type
+#[allow(non_snake_case)]
+mod keyword_order {
+    use super::*;
+
+    #[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+    #[relation(
+        relation_name = "order",
+        primary_key = "orderNumber",
+        identifiers = "quote_all"
+    )]
+    pub struct KeywordOrderTable {
+        records: Vec<KeywordOrderTableRecord>,
+    }
+
+    #[derive(
+        Record,
+        ReadRecord,
+        WriteRecord,
+        SingleInsert,
+        IdentifiableRecord,
+        sqlx::FromRow,
+        Clone,
+        Serialize,
+    )]
+    pub struct KeywordOrderTableRecord {
+        #[manual_primary_key]
+        pub orderNumber: i32,
+        pub itemCount: i32,
+        pub group: Option<String>,
+    }
+
+    #[tokio::test]
+    #[serial(order_table)]
+    async fn quoted_identifiers_should_allow_keyword_and_mixed_case_names() {
+        assert_eq!(
+            KeywordOrderTable::get_qualified_identifier(),
+            "\"main\".\"order\""
+        );
+
+        let records = (1..=3)
+            .map(|orderNumber| KeywordOrderTableRecord {
+                orderNumber,
+                itemCount: orderNumber * 10,
+                group: None,
+            })
+            .collect();
+        let order_table = KeywordOrderTable { records };
+
+        let database = get_database().await;
+
+        order_table
+            .insert_all(&database)
+            .await
+            .expect("order table creation failed");
+
+        KeywordOrderTable::update_one(
+            &database,
+            KeywordOrderTableRecordUpdateQueryParameters {
+                orderNumber: 2,
+                itemCount: Some(25),
+                group: FieldUpdate::Set("priority".to_string()),
+            },
+        )
+        .await
+        .expect("order record update failed");
+
+        let record = KeywordOrderTable::query_one(&database, GenericIdParameter::new(2_usize))
+            .await
+            .expect("order record query failed");
+
+        assert_eq!(record.itemCount, 25);
+        assert_eq!(record.group, Some("priority".to_string()));
+
+        let exists = KeywordOrderTable::exists(&database, GenericIdParameter::new(3_usize))
+            .await
+            .expect("order record existence check failed");
+
+        assert!(exists);
+
+        let ids = [
+            GenericIdParameter::new(1_usize),
+            GenericIdParameter::new(3_usize),
+        ];
+        let records = KeywordOrderTable::query_many(&database, &ids)
+            .await
+            .expect("order records query failed")
+            .records;
+
+        assert_eq!(records.len(), 2);
+
+        let page = KeywordOrderTable::query_after(&database, Some(1_usize), 10)
+            .await
+            .expect("order table cursor query failed");
+
+        assert_eq!(page.next_cursor, Some(3));
+
+        let last_record = KeywordOrderTable::query_last(&database)
+            .await
+            .expect("order table last record query failed")
+            .expect("order table should not be empty");
+
+        assert_eq!(last_record.orderNumber, 3);
+
+        let upserted = KeywordOrderTable {
+            records: vec![KeywordOrderTableRecord {
+                orderNumber: 3,
+                itemCount: 35,
+                group: None,
+            }],
+        }
+        .upsert_all_returning(&database)
+        .await
+        .expect("order table upsert failed");
+
+        assert_eq!(upserted, vec![(3, false)]);
+
+        KeywordOrderTable::delete_one(&database, GenericIdParameter::new(1_usize))
+            .await
+            .expect("order record deletion failed");
+
+        let item_counts: Vec<i32> = KeywordOrderTable::query_all(&database)
+            .await
+            .expect("order table query failed")
+            .records
+            .iter()
+            .map(|record| record.itemCount)
+            .collect();
+
+        assert_eq!(item_counts, vec![25, 35]);
+
+        let deleted_record =
+            KeywordOrderTable::delete_one_returning(&database, GenericIdParameter::new(2_usize))
+                .await
+                .expect("order record deletion failed");
+
+        assert_eq!(deleted_record.orderNumber, 2);
+
+        KeywordOrderTable::delete_all(&database)
+            .await
+            .expect("order table deletion failed");
+    }
+}
+
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(relation_name = "settings", primary_key = "name", key_type = String)]
+pub struct SettingsTable {
+    records: Vec<SettingsTableRecord>,
+}
+
+#[derive(Record, ReadRecord, WriteRecord, SingleInsert, sqlx::FromRow, Clone, Serialize)]
+pub struct SettingsTableRecord {
+    #[manual_primary_key]
+    #[map_key]
+    pub name: String,
+    #[map_value]
+    pub value: String,
+}
+
+#[tokio::test]
+#[serial(settings_table)]
+async fn update_one_should_bind_string_primary_key() {
+    let injected_name = "theme' OR '1' = '1".to_string();
+    let records = vec![
+        SettingsTableRecord {
+            name: "theme".to_string(),
+            value: "light".to_string(),
+        },
+        SettingsTableRecord {
+            name: injected_name.clone(),
+            value: "light".to_string(),
+        },
+    ];
+    let settings_table = SettingsTable { records };
+
+    let database = get_database().await;
+
+    settings_table
+        .insert_all(&database)
+        .await
+        .expect("settings table creation failed");
+
+    SettingsTable::update_one(
+        &database,
+        SettingsTableRecordUpdateQueryParameters {
+            name: injected_name.clone(),
+            value: Some("dark".to_string()),
+        },
+    )
+    .await
+    .expect("settings record update failed");
+
+    let values: Vec<(String, String)> = SettingsTable::query_all(&database)
+        .await
+        .expect("settings table query failed")
+        .records
+        .into_iter()
+        .map(|record| (record.name, record.value))
+        .collect();
+
+    assert_eq!(
+        values,
+        vec![
+            ("theme".to_string(), "light".to_string()),
+            (injected_name, "dark".to_string()),
+        ]
+    );
+
+    SettingsTable::delete_all(&database)
+        .await
+        .expect("settings table deletion failed");
+}
+
+#[tokio::test]
+#[serial(settings_table)]
+async fn key_value_relations_should_round_trip_through_maps() {
+    let settings_table = SettingsTable {
+        records: vec![
+            SettingsTableRecord {
+                name: "theme".to_string(),
+                value: "light".to_string(),
+            },
+            SettingsTableRecord {
+                name: "language".to_string(),
+                value: "en".to_string(),
+            },
+            SettingsTableRecord {
+                name: "theme".to_string(),
+                value: "dark".to_string(),
+            },
+        ],
+    };
+
+    let map = settings_table.to_map();
+    assert_eq!(
+        map,
+        HashMap::from([
+            ("theme".to_string(), "dark".to_string()),
+            ("language".to_string(), "en".to_string()),
+        ])
+    );
+
+    let database = get_database().await;
+
+    SettingsTable::from_map(map.clone())
+        .insert_all(&database)
+        .await
+        .expect("settings table creation failed");
+
+    let queried_map = SettingsTable::query_all(&database)
+        .await
+        .expect("settings table query failed")
+        .to_map();
+
+    assert_eq!(queried_map, map);
+
+    SettingsTable::delete_all(&database)
+        .await
+        .expect("settings table deletion failed");
+}
+
+#[tokio::test]
+#[serial(settings_table)]
+async fn constraint_violations_should_map_to_client_errors() {
+    let record = SettingsTableRecord {
+        name: "language".to_string(),
+        value: "en".to_string(),
+    };
+
+    let database = get_database().await;
+
+    record
+        .clone()
+        .insert(&database)
+        .await
+        .expect("settings record insertion failed");
+
+    let error = record
+        .insert(&database)
+        .await
+        .expect_err("duplicate settings record insertion should fail");
+
+    assert!(matches!(error.kind, ErrorKind::Conflict));
+    assert_eq!(error.status_code, StatusCode::CONFLICT);
+
+    let error: crudkit::error::Error =
+        sqlx::query("INSERT INTO main.settings (name, value) VALUES ('currency', NULL)")
+            .execute(&database.connection)
+            .await
+            .expect_err("settings record without value should fail")
+            .into();
+
+    assert!(matches!(error.kind, ErrorKind::ConstraintViolation));
+    assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+
+    SettingsTable::delete_all(&database)
+        .await
+        .expect("settings table deletion failed");
+}
+
+#[tokio::test]
+async fn driver_errors_should_map_to_server_errors() {
+    let database = get_database().await;
+
+    let error: crudkit::error::Error = sqlx::query_as::<_, (i32,)>("SELECT 'not a number'::text")
+        .fetch_one(&database.connection)
+        .await
+        .expect_err("decoding text as an integer should fail")
+        .into();
+
+    assert!(matches!(error.kind, ErrorKind::UnexpectedQueryResult));
+    assert_eq!(error.status_code, StatusCode::INTERNAL_SERVER_ERROR);
+
+    for source_error in [
+        sqlx::Error::BeginFailed,
+        sqlx::Error::InvalidSavePointStatement,
+        sqlx::Error::InvalidArgument("invalid argument".to_string()),
+    ] {
+        let error = crudkit::error::Error::from(source_error);
+
+        assert!(matches!(error.kind, ErrorKind::BrokenDatabaseConnection));
+        assert_eq!(error.status_code, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
+
+#[derive(IdParameter, serde::Deserialize)]
+pub struct SettingNameParameter {
+    name: String,
+}
+
+#[tokio::test]
+#[serial(settings_table)]
+async fn derived_id_parameter_should_select_string_key() {
+    let database = get_database().await;
+
+    SettingsTableRecord {
+        name: "timezone".to_string(),
+        value: "UTC".to_string(),
+    }
+    .insert(&database)
+    .await
+    .expect("settings record insertion failed");
+
+    let record =
+        SettingsTable::query_one(&database, SettingNameParameter::new("timezone".to_string()))
+            .await
+            .expect("settings record query failed");
+
+    assert_eq!(record.value, "UTC");
+
+    SettingsTable::delete_all(&database)
+        .await
+        .expect("settings table deletion failed");
+}
+
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(relation_name = "tickets", primary_key = "id")]
+pub struct TicketsTable {
+    records: Vec<TicketsTableRecord>,
+}
+
+#[derive(
+    Record,
+    ReadRecord,
+    WriteRecord,
+    SingleInsert,
+    IdentifiableRecord,
+    sqlx::FromRow,
+    Clone,
+    Serialize,
+)]
+pub struct TicketsTableRecord {
+    #[manual_primary_key]
+    pub id: i32,
+    pub title: String,
+    #[defaultable]
+    pub priority: Option<i32>,
+}
+
+// * The same relation, but inserted in much smaller chunks than the bind limit allows
+#[derive(Relation, ReadRelation, WriteRelation, Clone, Serialize)]
+#[relation(relation_name = "tickets", primary_key = "id")]
+pub struct SmallChunkTicketsTable {
+    records: Vec<SmallChunkTicketsTableRecord>,
+}
+
+impl BulkInsert for SmallChunkTicketsTable {
+    const CHUNK_SIZE: usize = 2;
+}
+
+#[derive(Record, ReadRecord, WriteRecord, SingleInsert, sqlx::FromRow, Clone, Serialize)]
+pub struct SmallChunkTicketsTableRecord {
+    #[manual_primary_key]
+    pub id: i32,
+    pub title: String,
+    #[defaultable]
+    pub priority: Option<i32>,
+}
+
+#[tokio::test]
+#[serial(tickets_table)]
+async fn insert_all_should_honor_overridden_chunk_size() {
+    let database = get_database().await;
+
+    let tickets_table = SmallChunkTicketsTable {
+        records: (1..=5)
+            .map(|id| SmallChunkTicketsTableRecord {
+                id,
+                title: format!("Ticket {id}"),
+                priority: None,
+            })
+            .collect(),
+    };
+
+    let mut progress = Vec::new();
+    tickets_table
+        .insert_all_with_progress(&database, |inserted_chunks, chunk_count| {
+            progress.push((inserted_chunks, chunk_count))
+        })
+        .await
+        .expect("tickets table creation failed");
+
+    assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+
+    let records = SmallChunkTicketsTable::query_all(&database)
+        .await
+        .expect("tickets table query failed")
+        .records;
+
+    assert_eq!(records.len(), 5);
+    assert!(records.iter().all(|record| record.priority == Some(3)));
+
+    SmallChunkTicketsTable::delete_all(&database)
+        .await
+        .expect("tickets table deletion failed");
+}
+
+// * The same relation, but with the primary key declared after the other fields
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(relation_name = "tickets", primary_key = "id")]
+pub struct ReorderedTicketsTable {
+    records: Vec<ReorderedTicketsTableRecord>,
+}
+
+#[derive(
+    Record,
+    ReadRecord,
+    WriteRecord,
+    SingleInsert,
+    IdentifiableRecord,
+    sqlx::FromRow,
+    Clone,
+    Serialize,
+)]
+pub struct ReorderedTicketsTableRecord {
+    pub title: String,
+    #[defaultable]
+    pub priority: Option<i32>,
+    #[manual_primary_key]
+    pub id: i32,
+}
+
+#[tokio::test]
+#[serial(tickets_table)]
+async fn identifiable_record_should_use_primary_key_field() {
+    let database = get_database().await;
+
+    let record = ReorderedTicketsTableRecord {
+        title: "Update dependencies".to_string(),
+        priority: Some(2),
+        id: 7,
+    };
+    assert_eq!(record.id(), Some(7));
+
+    record
+        .insert(&database)
+        .await
+        .expect("tickets record creation failed");
+
+    let page = ReorderedTicketsTable::query_after(&database, None::<i32>, 10)
+        .await
+        .expect("tickets table cursor query failed");
+
+    assert_eq!(page.next_cursor, Some(7));
+
+    ReorderedTicketsTable::delete_all(&database)
+        .await
+        .expect("tickets table deletion failed");
+}
+
+#[tokio::test]
+#[serial(tickets_table)]
+async fn update_one_should_reset_defaultable_column_to_default() {
+    let database = get_database().await;
+
+    TicketsTableRecord {
+        id: 1,
+        title: "Fix login".to_string(),
+        priority: Some(1),
+    }
+    .insert(&database)
+    .await
+    .expect("tickets record creation failed");
+
+    let update_params = TicketsTableRecordUpdateQueryParameters {
+        id: 1,
+        title: Some("Fix login page".to_string()),
+        priority: UpdateValue::Keep,
+    };
+    TicketsTable::update_one(&database, update_params)
+        .await
+        .expect("tickets record update failed");
+
+    let record = TicketsTable::query_one(&database, GenericIdParameter::new(1_usize))
+        .await
+        .expect("tickets record query failed");
+
+    assert_eq!(record.title, "Fix login page");
+    assert_eq!(record.priority, Some(1));
+
+    let update_params = TicketsTableRecordUpdateQueryParameters {
+        id: 1,
+        title: None,
+        priority: UpdateValue::Default,
+    };
+    TicketsTable::update_one(&database, update_params)
+        .await
+        .expect("tickets record update failed");
+
+    let record = TicketsTable::query_one(&database, GenericIdParameter::new(1_usize))
+        .await
+        .expect("tickets record query failed");
+
+    assert_eq!(record.priority, Some(3));
+
+    let update_params: TicketsTableRecordUpdateQueryParameters =
+        serde_json::from_value(serde_json::json!({ "id": 1, "priority": 2 }))
+            .expect("tickets update parameters should deserialize");
+    assert_eq!(update_params.priority, UpdateValue::Set(2));
+    TicketsTable::update_one(&database, update_params)
+        .await
+        .expect("tickets record update failed");
+
+    let record = TicketsTable::query_one(&database, GenericIdParameter::new(1_usize))
+        .await
+        .expect("tickets record query failed");
+
+    assert_eq!(record.title, "Fix login page");
+    assert_eq!(record.priority, Some(2));
+
+    let update_params: TicketsTableRecordUpdateQueryParameters =
+        serde_json::from_value(serde_json::json!({ "id": 1 }))
+            .expect("tickets update parameters should deserialize");
+    assert_eq!(update_params.priority, UpdateValue::Keep);
+
+    TicketsTable::delete_all(&database)
+        .await
+        .expect("tickets table deletion failed");
+}
+
+mod custom_validated_customers {
+    use super::*;
+
+    use crudkit::validate::Validate;
+
+    #[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+    #[relation(relation_name = "customers", primary_key = "id")]
+    pub struct CustomValidatedCustomersTable {
+        records: Vec<CustomValidatedCustomersTableRecord>,
+    }
+
+    #[derive(Record, ReadRecord, WriteRecord, SingleInsert, sqlx::FromRow, Clone, Serialize)]
+    #[custom_validation]
+    pub struct CustomValidatedCustomersTableRecord {
+        #[auto_primary_key]
+        #[defaultable]
+        pub id: Option<i32>,
+        pub name: String,
+        pub email_address: Option<String>,
+        pub phone_number: Option<String>,
+        pub street_address: Option<String>,
+    }
+
+    impl Validate for CustomValidatedCustomersTableRecordCreateQueryParameters {
+        fn validate(&self) -> Result<(), String> {
+            match self.name.is_empty() {
+                true => Err("name: must not be empty".to_string()),
+                false => Ok(()),
+            }
+        }
+    }
+
+    impl Validate for CustomValidatedCustomersTableRecordUpdateQueryParameters {
+        fn validate(&self) -> Result<(), String> {
+            match self.name.as_deref() {
+                Some("") => Err("name: must not be empty".to_string()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[serial(customers_table)]
+    async fn custom_validation_should_reject_invalid_parameters() {
+        let database = get_database().await;
+
+        let create_params = CustomValidatedCustomersTableRecordCreateQueryParameters {
+            name: String::new(),
+            email_address: None,
+            phone_number: None,
+            street_address: None,
+        };
+        let error = CustomValidatedCustomersTable::create_one(&database, create_params)
+            .await
+            .expect_err("creating a customer without a name should fail");
+
+        assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+        assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+        assert_eq!(error.message.as_deref(), Some("name: must not be empty"));
+
+        let records = CustomValidatedCustomersTable::query_all(&database)
+            .await
+            .expect("customers table query failed")
+            .records;
+
+        assert!(records.is_empty());
+
+        let update_params: CustomValidatedCustomersTableRecordUpdateQueryParameters =
+            serde_json::from_value(serde_json::json!({ "id": 1, "name": "" }))
+                .expect("customers update parameters should deserialize");
+        let error = CustomValidatedCustomersTable::update_one(&database, update_params)
+            .await
+            .expect_err("updating a customer to have no name should fail");
+
+        assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+        assert_eq!(error.message.as_deref(), Some("name: must not be empty"));
+    }
+}
+
+mod plural_customers {
+    use super::*;
+
+    // * Neither type follows the `Record` suffix convention, so each names the other explicitly
+    #[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+    #[relation(relation_name = "customers", primary_key = "id", record = "Customer")]
+    pub struct Customers {
+        records: Vec<Customer>,
+    }
+
+    #[derive(Record, ReadRecord, WriteRecord, SingleInsert, sqlx::FromRow, Clone, Serialize)]
+    #[record(relation = "Customers")]
+    pub struct Customer {
+        #[manual_primary_key]
+        pub id: i32,
+        pub name: String,
+        pub email_address: Option<String>,
+        pub phone_number: Option<String>,
+        pub street_address: Option<String>,
+    }
+
+    #[tokio::test]
+    #[serial(customers_table)]
+    async fn explicitly_paired_types_should_read_and_write() {
+        let database = get_database().await;
+
+        let customers = Customers::with_records(
+            (280..283)
+                .map(|id| Customer {
+                    id,
+                    name: format!("Customer {id}"),
+                    email_address: None,
+                    phone_number: None,
+                    street_address: None,
+                })
+                .collect::<Vec<_>>(),
+        );
+        customers
+            .insert_all(&database)
+            .await
+            .expect("customers table creation failed");
+
+        let create_params = CustomerCreateQueryParameters {
+            id: 283,
+            name: "Customer 283".to_string(),
+            email_address: None,
+            phone_number: None,
+            street_address: None,
+        };
+        Customers::create_one(&database, create_params)
+            .await
+            .expect("customer creation failed");
+
+        let ids: Vec<i32> = Customers::query_all(&database)
+            .await
+            .expect("customers table query failed")
+            .into_iter()
+            .map(|customer| customer.id)
+            .collect();
+
+        assert_eq!(ids, vec![280, 281, 282, 283]);
+
+        Customers::delete_all(&database)
+            .await
+            .expect("customers table deletion failed");
+    }
+}
+
+// * Without the `validator` feature, a record can still derive `validator::Validate` itself, and its
+// * attributes must not be copied onto the parameters types, which do not derive it
+#[cfg(not(feature = "validator"))]
+mod record_validated_customers {
+    use super::*;
+
+    use validator::Validate;
+
+    #[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+    #[relation(relation_name = "customers", primary_key = "id")]
+    pub struct RecordValidatedCustomersTable {
+        records: Vec<RecordValidatedCustomersTableRecord>,
+    }
+
+    #[derive(
+        Record, ReadRecord, WriteRecord, SingleInsert, Validate, sqlx::FromRow, Clone, Serialize,
+    )]
+    pub struct RecordValidatedCustomersTableRecord {
+        #[auto_primary_key]
+        #[defaultable]
+        pub id: Option<i32>,
+        #[validate(length(min = 1))]
+        pub name: String,
+        pub email_address: Option<String>,
+        pub phone_number: Option<String>,
+        pub street_address: Option<String>,
+    }
+
+    #[tokio::test]
+    #[serial(customers_table)]
+    async fn record_validator_attributes_should_not_apply_to_parameters() {
+        let database = get_database().await;
+
+        let create_params = RecordValidatedCustomersTableRecordCreateQueryParameters {
+            name: String::new(),
+            email_address: None,
+            phone_number: None,
+            street_address: None,
+        };
+        RecordValidatedCustomersTable::create_one(&database, create_params)
+            .await
+            .expect("customer creation failed");
+
+        let records = RecordValidatedCustomersTable::query_all(&database)
+            .await
+            .expect("customers table query failed")
+            .records;
+
+        assert_eq!(records.len(), 1);
+        records[0]
+            .validate()
+            .expect_err("record without a name should be invalid");
+
+        RecordValidatedCustomersTable::delete_all(&database)
+            .await
+            .expect("customers table deletion failed");
+    }
+}
+
+#[cfg(feature = "validator")]
+mod validator_customers {
+    use super::*;
+
+    #[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+    #[relation(relation_name = "customers", primary_key = "id")]
+    pub struct ValidatedCustomersTable {
+        records: Vec<ValidatedCustomersTableRecord>,
+    }
+
+    #[derive(Record, ReadRecord, WriteRecord, SingleInsert, sqlx::FromRow, Clone, Serialize)]
+    pub struct ValidatedCustomersTableRecord {
+        #[auto_primary_key]
+        #[defaultable]
+        pub id: Option<i32>,
+        #[validate(length(min = 1))]
+        pub name: String,
+        #[validate(email)]
+        pub email_address: Option<String>,
+        pub phone_number: Option<String>,
+        pub street_address: Option<String>,
+    }
+
+    #[tokio::test]
+    #[serial(customers_table)]
+    async fn validator_attributes_should_reject_invalid_parameters() {
+        let database = get_database().await;
+
+        let create_params = ValidatedCustomersTableRecordCreateQueryParameters {
+            name: String::new(),
+            email_address: Some("not an email address".to_string()),
+            phone_number: None,
+            street_address: None,
+        };
+        let error = ValidatedCustomersTable::create_one(&database, create_params)
+            .await
+            .expect_err("creating an invalid customer should fail");
+
+        assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+        assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            error.message.as_deref(),
+            Some("email_address: email; name: length")
+        );
+
+        let create_params = ValidatedCustomersTableRecordCreateQueryParameters {
+            name: "Jane Doe".to_string(),
+            email_address: Some("jane@example.com".to_string()),
+            phone_number: None,
+            street_address: None,
+        };
+        let record = ValidatedCustomersTable::create_one_returning(&database, create_params)
+            .await
+            .expect("customers record creation failed");
+        let id = record
+            .id
+            .expect("created customers record should have an id");
+
+        // * Fields which are not being set are not checked
+        let update_params: ValidatedCustomersTableRecordUpdateQueryParameters =
+            serde_json::from_value(serde_json::json!({ "id": id, "phone_number": "555-0100" }))
+                .expect("customers update parameters should deserialize");
+        ValidatedCustomersTable::update_one(&database, update_params)
+            .await
+            .expect("customers record update failed");
+
+        let update_params: ValidatedCustomersTableRecordUpdateQueryParameters =
+            serde_json::from_value(serde_json::json!({ "id": id, "email_address": "jane" }))
+                .expect("customers update parameters should deserialize");
+        let error = ValidatedCustomersTable::update_one(&database, update_params)
+            .await
+            .expect_err("updating a customer with an invalid email address should fail");
+
+        assert!(matches!(error.kind, ErrorKind::InvalidQuery));
+        assert_eq!(error.message.as_deref(), Some("email_address: email"));
+
+        let record = ValidatedCustomersTable::query_one(&database, GenericIdParameter::new(id))
+            .await
+            .expect("customers record query failed");
+
+        assert_eq!(record.email_address, Some("jane@example.com".to_string()));
+        assert_eq!(record.phone_number, Some("555-0100".to_string()));
+
+        ValidatedCustomersTable::delete_all(&database)
+            .await
+            .expect("customers table deletion failed");
+    }
+}
+
+#[derive(sqlx::Type, Clone, Copy, Debug, PartialEq, Serialize, serde::Deserialize)]
+#[sqlx(type_name = "main.shipment_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ShipmentStatus {
+    Pending,
+    Shipped,
+    Delivered,
+}
+
+#[derive(sqlx::Type, Clone, Copy, Debug, PartialEq, Serialize, serde::Deserialize)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Carrier {
+    Post,
+    Courier,
+}
+
+#[derive(Relation, ReadRelation, WriteRelation, BulkInsert, Clone, Serialize)]
+#[relation(relation_name = "shipments", primary_key = "id")]
+pub struct ShipmentsTable {
+    records: Vec<ShipmentsTableRecord>,
+}
+
+#[derive(Record, ReadRecord, WriteRecord, SingleInsert, sqlx::FromRow, Clone, Serialize)]
+pub struct ShipmentsTableRecord {
+    #[manual_primary_key]
+    pub id: i32,
+    pub status: ShipmentStatus,
+    pub carrier: Carrier,
+}
+
+// * The same relation, but with the status written as text, which must be cast to the enum type
+#[derive(Relation, WriteRelation, Clone, Serialize)]
+#[relation(relation_name = "shipments", primary_key = "id")]
+pub struct ShipmentLabelsTable {
+    records: Vec<ShipmentLabelsTableRecord>,
+}
+
+#[derive(Record, WriteRecord, SingleInsert, GenerateTable, sqlx::FromRow, Clone, Serialize)]
+pub struct ShipmentLabelsTableRecord {
+    #[manual_primary_key]
+    pub id: i32,
+    #[column(pg_type = "main.shipment_status")]
+    pub status: String,
+    pub carrier: String,
+}
+
+#[tokio::test]
+#[serial(shipments_table)]
+async fn enum_columns_should_insert_and_update() {
+    let database = get_database().await;
+
+    ShipmentsTable::create_one(
+        &database,
+        ShipmentsTableRecordCreateQueryParameters {
+            id: 1,
+            status: ShipmentStatus::Pending,
+            carrier: Carrier::Post,
+        },
+    )
+    .await
+    .expect("shipments record creation failed");
+
+    let update_params: ShipmentsTableRecordUpdateQueryParameters =
+        serde_json::from_value(serde_json::json!({ "id": 1, "status": "shipped" }))
+            .expect("shipments update parameters should deserialize");
+    ShipmentsTable::update_one(&database, update_params)
+        .await
+        .expect("shipments record update failed");
+
+    let record = ShipmentsTable::query_one(&database, GenericIdParameter::new(1))
+        .await
+        .expect("shipments record query failed");
+
+    assert_eq!(record.status, ShipmentStatus::Shipped);
+    assert_eq!(record.carrier, Carrier::Post);
+
+    ShipmentLabelsTable::create_one(
+        &database,
+        ShipmentLabelsTableRecordCreateQueryParameters {
+            id: 2,
+            status: "pending".to_string(),
+            carrier: "courier".to_string(),
+        },
+    )
+    .await
+    .expect("shipments record creation with a cast status failed");
+
+    ShipmentLabelsTable::update_one(
+        &database,
+        ShipmentLabelsTableRecordUpdateQueryParameters {
+            id: 2,
+            status: Some("delivered".to_string()),
+            carrier: None,
+        },
+    )
+    .await
+    .expect("shipments record update with a cast status failed");
+
+    let record = ShipmentsTable::query_one(&database, GenericIdParameter::new(2))
+        .await
+        .expect("shipments record query failed");
+
+    assert_eq!(record.status, ShipmentStatus::Delivered);
+    assert_eq!(record.carrier, Carrier::Courier);
+
+    assert_eq!(
+        ShipmentLabelsTableRecord::create_table_sql(),
+        "CREATE TABLE IF NOT EXISTS main.shipments (id integer PRIMARY KEY, status \
+        main.shipment_status NOT NULL, carrier text NOT NULL)"
+    );
+
+    ShipmentsTable::delete_all(&database)
+        .await
+        .expect("shipments table deletion failed");
+}